@@ -0,0 +1,71 @@
+use crate::lexer::{Symbol, Token};
+
+/// A token-lexeme pair, with its bracket annotation if it is a bracket, see
+/// `q1_lib::lex_with_brackets`.
+pub type BracketToken = (Token, String, Option<Bracket>);
+
+/// Where a bracket (`(`, `)`, `{`, or `}`) is among the brackets around it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bracket {
+    /// How many brackets enclose this one (`0` for an outermost bracket). A
+    /// bracket is at the same depth as its partner.
+    pub depth: usize,
+    /// The index of the token of the bracket matching this one, or `None` if
+    /// it is unmatched.
+    pub partner: Option<usize>,
+}
+
+/// The closing bracket symbol of an opening bracket token, if it is one.
+fn closer_of(token: Token) -> Option<Symbol> {
+    match token {
+        Token::Symbol(Symbol::LeftParen) => Some(Symbol::RightParen),
+        Token::Symbol(Symbol::LeftCurly) => Some(Symbol::RightCurly),
+        _ => None,
+    }
+}
+
+/// Matches every bracket of a token stream in a single pass, with a stack of
+/// the brackets still open, returning the annotation of each token (`None`
+/// for any token which is not a bracket) along with every mismatch found.
+/// 
+/// An unmatched bracket (ex. the `)` of `a)`, the `(` of `(a`, or the `}` of
+/// `(}`) has no partner, and is also reported as an error. A closing bracket
+/// of the wrong kind closes nothing, so the opening bracket before it can still
+/// be closed after it.
+pub fn match_brackets(tokens: &[(Token, String)]) -> (Vec<Option<Bracket>>, Vec<String>) {
+    let mut brackets = vec![None; tokens.len()];
+    let mut errors = vec![];
+    let mut open: Vec<(usize, Symbol)> = vec![]; // every open bracket, with the symbol closing it
+
+    for (i, (token, lexeme)) in tokens.iter().enumerate() {
+        if let Some(closer) = closer_of(*token) {
+            brackets[i] = Some(Bracket { depth: open.len(), partner: None });
+            open.push((i, closer));
+            continue;
+        }
+        if !matches!(token, Token::Symbol(Symbol::RightParen | Symbol::RightCurly)) {
+            continue;
+        }
+
+        match open.last() {
+            Some(&(opener, closer)) if *token == Token::Symbol(closer) => {
+                open.pop();
+                brackets[opener] = Some(Bracket { depth: open.len(), partner: Some(i) });
+                brackets[i] = Some(Bracket { depth: open.len(), partner: Some(opener) });
+            },
+            Some(&(opener, _closer)) => {
+                brackets[i] = Some(Bracket { depth: open.len(), partner: None });
+                errors.push(format!("Mismatched `{lexeme}` at token {i}, which cannot close the `{}` at token {opener}", tokens[opener].1));
+            },
+            None => {
+                brackets[i] = Some(Bracket { depth: 0, partner: None });
+                errors.push(format!("Unmatched `{lexeme}` at token {i}, with no bracket open"));
+            },
+        }
+    }
+
+    for (opener, _closer) in open {
+        errors.push(format!("Unclosed `{}` at token {opener}", tokens[opener].1));
+    }
+    (brackets, errors)
+}
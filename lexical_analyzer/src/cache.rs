@@ -0,0 +1,92 @@
+//! # Token Cache
+//! 
+//! A compact binary encoding of a token stream, so that `save_tokens` and
+//! `load_tokens` can skip re-lexing a large input which is parsed repeatedly.
+//! 
+//! Every token is a one-byte code (its index in `TOKENS`), so a cache is
+//! only readable by the same `VERSION` of the format.
+
+use crate::lexer::{Literal, Symbol, Token, Type};
+
+/// The first bytes of every token cache, followed by its format version.
+const MAGIC: &[u8] = b"Q1TOKENS";
+/// The version of the format, bumped whenever the encoding (or `TOKENS`) changes.
+const VERSION: u8 = 5;
+
+/// Every token, where the index of a token is its one-byte code in a cache.
+const TOKENS: &[Token] = &[
+    Token::Literal(Literal::Int),
+    Token::Literal(Literal::Float),
+    Token::Identifier,
+    Token::RawIdentifier,
+    Token::Symbol(Symbol::Plus),
+    Token::Symbol(Symbol::Minus),
+    Token::Symbol(Symbol::Multiply),
+    Token::Symbol(Symbol::Divide),
+    Token::Symbol(Symbol::Modulo),
+    Token::Symbol(Symbol::Equal),
+    Token::Symbol(Symbol::Semicolon),
+    Token::Symbol(Symbol::LeftParen),
+    Token::Symbol(Symbol::RightParen),
+    Token::Symbol(Symbol::LeftCurly),
+    Token::Symbol(Symbol::RightCurly),
+    Token::Symbol(Symbol::Underscore),
+    Token::Symbol(Symbol::Comma),
+    Token::Symbol(Symbol::Period),
+    Token::Symbol(Symbol::Question),
+    Token::Symbol(Symbol::Colon),
+    Token::Symbol(Symbol::Not),
+    Token::Type(Type::Int),
+    Token::Type(Type::Float),
+    Token::Return,
+    Token::If,
+    Token::Else,
+    Token::Newline,
+    Token::Const,
+];
+
+/// Encodes a token stream as a compact binary cache.
+///
+/// After the `MAGIC` and `VERSION`, each token is its one-byte code (see
+/// `TOKENS`), then the length of its lexeme (as 4 little-endian bytes), then
+/// the UTF-8 bytes of its lexeme.
+/// 
+/// A token missing from `TOKENS`, or a lexeme too long for its length, is an
+/// error rather than a cache which cannot be decoded.
+pub(crate) fn encode(tokens: &[(Token, String)]) -> Result<Vec<u8>, String> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    for (i, (token, lexeme)) in tokens.iter().enumerate() {
+        let code = TOKENS.iter().position(|known| known == token)
+            .ok_or(format!("The token `{token:?}` of token {i} has no code in the token cache"))?;
+        let length = u32::try_from(lexeme.len())
+            .map_err(|_| format!("The lexeme of token {i} is too long for the token cache ({} bytes)", lexeme.len()))?;
+        bytes.push(code as u8);
+        bytes.extend(length.to_le_bytes());
+        bytes.extend(lexeme.as_bytes());
+    }
+    Ok(bytes)
+}
+
+/// Decodes a token stream from a binary cache, see `encode`.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Vec<(Token, String)>, String> {
+    let rest = bytes.strip_prefix(MAGIC).ok_or("Not a token cache".to_string())?;
+    let (&version, mut rest) = rest.split_first().ok_or("Expected the version of the token cache".to_string())?;
+    if version != VERSION {
+        Err(format!("Unsupported token cache version {version}, expected version {VERSION}"))?
+    }
+
+    let mut tokens = vec![];
+    while let Some((&code, after_code)) = rest.split_first() {
+        let i = tokens.len();
+        let token = *TOKENS.get(code as usize).ok_or(format!("Unknown token code {code} in token {i} of the cache"))?;
+        let (length, after_length) = after_code.split_first_chunk::<4>()
+            .ok_or(format!("Expected the lexeme length of token {i} of the cache"))?;
+        let length = u32::from_le_bytes(*length) as usize;
+        let lexeme = after_length.get(..length).ok_or(format!("Expected {length} lexeme bytes in token {i} of the cache"))?;
+        let lexeme = String::from_utf8(lexeme.to_vec()).map_err(|err| format!("Invalid lexeme in token {i} of the cache: {err}"))?;
+        tokens.push((token, lexeme));
+        rest = &after_length[length..];
+    }
+    Ok(tokens)
+}
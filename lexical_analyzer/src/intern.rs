@@ -0,0 +1,54 @@
+use std::{collections::HashMap, rc::Rc};
+
+/// The id of an interned lexeme, in the `Interner` which interned it.
+///
+/// Two ids of the same interner are equal exactly if their lexemes are, so
+/// comparing lexemes is only comparing integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LexemeId(u32);
+
+/// A pool of lexemes, where every distinct lexeme is only stored once, no
+/// matter how many tokens it is the lexeme of.
+/// 
+/// The lookup from a lexeme to its id and the lookup from an id to its lexeme
+/// share the same allocation of the lexeme, rather than each having a copy.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    ids: HashMap<Rc<str>, LexemeId>,
+    lexemes: Vec<Rc<str>>,
+}
+impl Interner {
+    /// An empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of a lexeme, storing the lexeme if it was never interned before.
+    pub fn intern(&mut self, lexeme: &str) -> LexemeId {
+        if let Some(&id) = self.ids.get(lexeme) {
+            return id;
+        }
+        let id = LexemeId(self.lexemes.len() as u32);
+        let lexeme: Rc<str> = lexeme.into();
+        self.ids.insert(Rc::clone(&lexeme), id);
+        self.lexemes.push(lexeme);
+        id
+    }
+
+    /// The lexeme of an id.
+    ///
+    /// Panics if the id is not from this interner.
+    pub fn resolve(&self, id: LexemeId) -> &str {
+        &self.lexemes[id.0 as usize]
+    }
+
+    /// The number of distinct lexemes stored.
+    pub fn len(&self) -> usize {
+        self.lexemes.len()
+    }
+
+    /// If no lexeme was interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.lexemes.is_empty()
+    }
+}
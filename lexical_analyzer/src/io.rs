@@ -1,66 +1,73 @@
 use std::{
     env::args,
+    fmt,
     fs::File,
-    io::{Bytes, Read},
+    io::{BufReader, Bytes, Read},
     sync::LazyLock,
 };
 
 use crate::error_codes::{BYTE_READ_ERROR, CLI_PARSE_ERROR, OPEN_FILE_ERROR};
 
-/// The input path passed-in from the CLI arguments, which is always expected.
+/// An IO failure, returned rather than exiting, so that the IO layer can be
+/// used as a library. Only the binary (see `get_lexemes`) exits on one, with
+/// its `exit_code`.
+#[derive(Debug)]
+pub enum IoError {
+    /// No input path was passed-in as the first argument.
+    MissingInputPath,
+    /// The input file could not be opened.
+    OpenFile { path: String, source: std::io::Error },
+    /// A byte of the input file could not be read.
+    ReadByte { path: String, source: std::io::Error },
+}
+impl IoError {
+    /// The unique error code the program exits with on this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            IoError::MissingInputPath => CLI_PARSE_ERROR,
+            IoError::OpenFile { .. } => OPEN_FILE_ERROR,
+            IoError::ReadByte { .. } => BYTE_READ_ERROR,
+        }
+    }
+}
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::MissingInputPath => write!(
+                f,
+                "expected at least one argument\n          - first argument is expected to be an input path"
+            ),
+            IoError::OpenFile { path, source } => write!(f, "could not open file `{path}` due to IO error - `{source}`"),
+            IoError::ReadByte { path, source } => write!(f, "while reading byte at `{path}` due to IO error - `{source}`"),
+        }
+    }
+}
+impl std::error::Error for IoError {}
+
+/// The input path passed-in from the CLI arguments, if any.
 ///
 /// This is purposely left private to compartmentalize the IO module.
 ///
 /// LazyLock ensures that the value is loaded in static run-time memory
 /// when first accessed, and ensures that the value is never mutated.
-static INPUT_PATH: LazyLock<String> = LazyLock::new(|| {
-    // read program's arguments, skipping the trivial first argument, and expecting some "first" argument
-    let found_file_path = args()
-        .skip(1)
-        .next();
-
-    // exit if the flag is not found.
-    if found_file_path.is_none() {
-        eprintln!("ERROR - expected at least one argument");
-        eprintln!("          - first argument is expected to be an input path");
-        std::process::exit(CLI_PARSE_ERROR)
-    }
-
-    // the argument is the input path
-    return found_file_path.unwrap();
+static INPUT_PATH: LazyLock<Option<String>> = LazyLock::new(|| {
+    // read program's arguments, skipping the trivial first argument
+    args().nth(1)
 });
 
-/// Returns an interator over the bytes of a file.
-///
-/// The program will exit with an error message if the file cannot be opened.
-pub fn open_file() -> Bytes<File> {
-    match File::open(INPUT_PATH.as_str()) {
-        Ok(file) => file.bytes(),
+/// The input path passed-in as the first CLI argument, which is always expected.
+pub fn input_path() -> Result<&'static str, IoError> {
+    INPUT_PATH.as_deref().ok_or(IoError::MissingInputPath)
+}
 
-        Err(err) => {
-            eprintln!(
-                "ERROR - could not open file `{}` due to IO error - `{}`",
-                INPUT_PATH.as_str(),
-                err
-            );
-            std::process::exit(OPEN_FILE_ERROR)
-        }
-    }
+/// Returns an interator over the bytes of a file.
+pub fn open_file(path: &str) -> Result<Bytes<BufReader<File>>, IoError> {
+    File::open(path)
+        .map(|file| BufReader::new(file).bytes())
+        .map_err(|source| IoError::OpenFile { path: path.to_string(), source })
 }
 
-/// Helper unwrapping function for an IO read of a byte.
-///
-/// If the input is `None`, the program exits with an error message.
-/// Otherwise, the input is `Some(_)` and we safely unwrap the Result.
-pub fn expected_read(maybe_c: Result<u8, std::io::Error>) -> u8 {
-    maybe_c
-        .map_err(|err| {
-            println!(
-                "ERROR - while reading byte at `{}` due to IO error - `{}`",
-                INPUT_PATH.as_str(),
-                err
-            );
-            std::process::exit(BYTE_READ_ERROR)
-        })
-        .unwrap()
+/// Helper function for an IO read of a byte of the file at `path`.
+pub fn read_byte(path: &str, maybe_c: Result<u8, std::io::Error>) -> Result<u8, IoError> {
+    maybe_c.map_err(|source| IoError::ReadByte { path: path.to_string(), source })
 }
@@ -0,0 +1,128 @@
+use std::{iter::Peekable, str::CharIndices};
+
+/// A JSON object with only string values, as its key-value pairs in order.
+pub(crate) type StringObject = Vec<(String, String)>;
+
+/// Reads a JSON array of objects, where every value is a string
+/// (ex. `[{"token": "Identifier", "lexeme": "x"}]`).
+///
+/// This is only as much of JSON as a token stream needs, and nothing more.
+pub(crate) fn read_string_objects(json: &str) -> Result<Vec<StringObject>, String> {
+    let mut reader = Reader::new(json);
+
+    let mut objects = vec![];
+    reader.expect('[')?;
+    if !reader.next_is(']') {
+        loop {
+            objects.push(reader.object()?);
+            if !reader.next_is(',') {
+                break;
+            }
+        }
+        reader.expect(']')?;
+    }
+
+    reader.expect_end("array")?;
+    Ok(objects)
+}
+
+/// A cursor over some JSON text.
+/// 
+/// Only the pieces of JSON which are read by hand elsewhere (ex. the JSON
+/// trees of `Q2`) are public: a value is read by expecting its characters in
+/// turn, and its strings with `string`.
+pub struct Reader<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+impl<'a> Reader<'a> {
+    /// A cursor at the start of some JSON text.
+    pub fn new(json: &'a str) -> Self {
+        Reader { chars: json.char_indices().peekable() }
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+    }
+
+    /// Consumes the next character (after any whitespace) if it is `expected`.
+    pub fn next_is(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|&(_, c)| c == expected).is_some()
+    }
+
+    /// Expects the next character (after any whitespace) to be `expected`.
+    pub fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((at, c)) => Err(format!("Expected `{expected}` at {at} in the JSON, but found `{c}` instead")),
+            None => Err(format!("Expected `{expected}` in the JSON, but found nothing instead")),
+        }
+    }
+
+    /// Expects nothing but whitespace after the end of the JSON `what` (ex. `array`).
+    pub fn expect_end(&mut self, what: &str) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((at, c)) => Err(format!("Unexpected `{c}` at {at} after the end of the JSON {what}")),
+            None => Ok(()),
+        }
+    }
+
+    fn object(&mut self) -> Result<StringObject, String> {
+        let mut pairs = vec![];
+        self.expect('{')?;
+        if self.next_is('}') {
+            return Ok(pairs);
+        }
+        loop {
+            let key = self.string()?;
+            self.expect(':')?;
+            let value = self.string()?;
+            pairs.push((key, value));
+            if !self.next_is(',') {
+                break;
+            }
+        }
+        self.expect('}')?;
+        Ok(pairs)
+    }
+
+    /// A JSON string, with every escape sequence replaced by its character.
+    pub fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            let c = match self.chars.next() {
+                Some((_, '"')) => return Ok(string),
+                Some((_, '\\')) => self.escaped()?,
+                Some((_, c)) => c,
+                None => Err("Expected the end of a JSON string, but found nothing instead".to_string())?,
+            };
+            string.push(c);
+        }
+    }
+
+    /// The character of an escape sequence, after its `\`.
+    fn escaped(&mut self) -> Result<char, String> {
+        Ok(match self.chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, '/')) => '/',
+            Some((_, 'b')) => '\u{8}',
+            Some((_, 'f')) => '\u{c}',
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((at, 'u')) => {
+                let hex = (0..4).filter_map(|_| self.chars.next().map(|(_, c)| c)).collect::<String>();
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or(format!("Unsupported JSON escape `\\u{hex}` at {at}"))?
+            },
+            Some((at, c)) => Err(format!("Unknown JSON escape `\\{c}` at {at}"))?,
+            None => Err("Expected a JSON escape, but found nothing instead".to_string())?,
+        })
+    }
+}
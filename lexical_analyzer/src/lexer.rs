@@ -1,15 +1,27 @@
-use crate::error_codes::LEXICAL_ERROR;
+use std::collections::{HashMap, VecDeque}; // A keyword table, and a queue of flushed tokens, waiting to be yielded.
+use std::ops::Range; // The byte range of a token in the input.
+use std::str::FromStr; // Parsing a token back from its name.
 
 /// The cream-of-the-crop (it always rises to the top) of this
 /// assignment: the Token enum.
 /// This token "tags" a lexeme for the syntactical analysis as `(Token, String)`
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Token {
     Literal(Literal),
     Identifier,
+    /// An identifier between backticks (ex. `` `if` ``), which is never a
+    /// keyword, no matter how it is spelled.
+    RawIdentifier,
     Symbol(Symbol),
     Type(Type),
     Return,
+    If,
+    Else,
+    /// A qualifier of a type, ex. `const int`.
+    Const,
+    /// A line break, only ever lexed by a `Lexer::with_newlines` (it is
+    /// otherwise just whitespace).
+    Newline,
 }
 impl From<Symbol> for Token {
     fn from(sym: Symbol) -> Self {
@@ -26,6 +38,61 @@ impl From<Literal> for Token {
         Token::Literal(value)
     }
 }
+/// Parses a token back from its name, exactly as it is printed with `{:?}`
+/// (ex. `Identifier`, `Symbol(Plus)`, or `Type(Int)`).
+impl FromStr for Token {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let token = match name.split_once('(') {
+            Some(("Literal", inner)) => Token::Literal(inner.strip_suffix(')').unwrap_or("").parse()?),
+            Some(("Symbol", inner)) => Token::Symbol(inner.strip_suffix(')').unwrap_or("").parse()?),
+            Some(("Type", inner)) => Token::Type(inner.strip_suffix(')').unwrap_or("").parse()?),
+            Some(_) => Err(format!("Unknown token `{name}`"))?,
+            None => match name {
+                "Identifier" => Token::Identifier,
+                "RawIdentifier" => Token::RawIdentifier,
+                "Return" => Token::Return,
+                "If" => Token::If,
+                "Else" => Token::Else,
+                "Const" => Token::Const,
+                "Newline" => Token::Newline,
+                _ => Err(format!("Unknown token `{name}`"))?,
+            },
+        };
+        Ok(token)
+    }
+}
+
+/// Every keyword, along with its token.
+///
+/// The state machine recognizes each keyword by its own states, so this is only
+/// to look up the keyword an identifier may be spelled as (see
+/// `Lexer::with_ci_keywords`), or to tell a keyword from a word of a custom
+/// keyword table (see `Lexer::with_keywords`).
+const KEYWORDS: [(&str, Token); 6] = [
+    ("int", Token::Type(Type::Int)),
+    ("float", Token::Type(Type::Float)),
+    ("return", Token::Return),
+    ("if", Token::If),
+    ("else", Token::Else),
+    ("const", Token::Const),
+];
+
+/// The token of a keyword, if some word is one.
+fn keyword(word: &str) -> Option<Token> {
+    KEYWORDS.iter()
+        .find(|(keyword, _)| *keyword == word)
+        .map(|(_, token)| *token)
+}
+
+/// The built-in keyword table, as a map from each keyword to its token (ex. to
+/// start a custom table of `Lexer::with_keywords` from).
+pub fn default_keywords() -> HashMap<String, Token> {
+    KEYWORDS.iter()
+        .map(|(keyword, token)| (keyword.to_string(), *token))
+        .collect()
+}
 
 /// All the singleton character parseable symbols.
 ///
@@ -35,13 +102,16 @@ impl From<Literal> for Token {
 /// - Grouping Operators
 /// - Identifier Underscore
 /// - Comma/Period
-#[derive(Clone, Copy, Debug)]
+/// - Question/Colon
+/// - Logical Operators
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Symbol {
     // Arithmetic Operators
     Plus,
     Minus,
     Multiply,
     Divide,
+    Modulo,
 
     // Assignment Operator
     Equal,
@@ -61,6 +131,40 @@ pub enum Symbol {
 
     // Period: for floating point
     Period,
+
+    // Question/Colon: for conditional expressions
+    Question,
+    Colon,
+
+    // Logical Operators
+    Not,
+}
+
+impl FromStr for Symbol {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "Plus" => Symbol::Plus,
+            "Minus" => Symbol::Minus,
+            "Multiply" => Symbol::Multiply,
+            "Divide" => Symbol::Divide,
+            "Modulo" => Symbol::Modulo,
+            "Equal" => Symbol::Equal,
+            "Semicolon" => Symbol::Semicolon,
+            "LeftParen" => Symbol::LeftParen,
+            "RightParen" => Symbol::RightParen,
+            "LeftCurly" => Symbol::LeftCurly,
+            "RightCurly" => Symbol::RightCurly,
+            "Underscore" => Symbol::Underscore,
+            "Comma" => Symbol::Comma,
+            "Period" => Symbol::Period,
+            "Question" => Symbol::Question,
+            "Colon" => Symbol::Colon,
+            "Not" => Symbol::Not,
+            _ => Err(format!("Unknown symbol `{name}`"))?,
+        })
+    }
 }
 
 /// A determinant for a grouping of a character.
@@ -69,7 +173,7 @@ pub enum Symbol {
 /// 
 /// - `Letter` (all alphabetical ascii [a-zA-Z])
 /// - `Digit` (all digital ascii [0-9])
-/// - `Symbol` (all expected symbols [+-*/=;(){}_,.])
+/// - `Symbol` (all expected symbols [+-*/%=;(){}_,.?:!])
 /// - `Unknown` (any other character, almost always means to invoke an error)
 #[derive(Clone, Copy)]
 enum CharClass {
@@ -79,7 +183,7 @@ enum CharClass {
     /// [0-9]
     Digit,
 
-    /// [+-*/=;(){}_,.]
+    /// [+-*/%=;(){}_,.?:!]
     Symbol(Symbol),
 
     /// An unexpected character was parsed...
@@ -89,7 +193,7 @@ impl CharClass {
     /// Parses a byte, expecting a 7-bit ascii code.
     pub fn parse(c: u8) -> Self {
         // Expect only certain range of characters from the non-extended ascii table
-        if c < 0x21 || 0x7E < c {
+        if !(0x21..=0x7E).contains(&c) {
             return Self::Unknown;
         }
 
@@ -105,6 +209,7 @@ impl CharClass {
             '-' => Symbol::Minus.into(),
             '*' => Symbol::Multiply.into(),
             '/' => Symbol::Divide.into(),
+            '%' => Symbol::Modulo.into(),
 
             '=' => Symbol::Equal.into(),
             ';' => Symbol::Semicolon.into(),
@@ -120,6 +225,11 @@ impl CharClass {
 
             '.' => Symbol::Period.into(),
 
+            '?' => Symbol::Question.into(),
+            ':' => Symbol::Colon.into(),
+
+            '!' => Symbol::Not.into(),
+
             _ => Self::Unknown,
         }
     }
@@ -131,7 +241,7 @@ impl From<Symbol> for CharClass {
 }
 
 /// A type keyword.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Type {
     /// the `int` type
     Int,
@@ -139,8 +249,20 @@ pub enum Type {
     Float,
 }
 
+impl FromStr for Type {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "Int" => Type::Int,
+            "Float" => Type::Float,
+            _ => Err(format!("Unknown type `{name}`"))?,
+        })
+    }
+}
+
 /// A literal value
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Literal {
     /// An `int` literal
     /// 
@@ -153,14 +275,23 @@ pub enum Literal {
     Float,
 }
 
-/// Returns `true` for any ascii whitespace characters.
-fn is_whitespace(c: u8) -> bool {
-    match c {
-        0x9 | 0xA | 0xB | 0xC | 0xD | 0x20 => true,
-        _ => false,
+impl FromStr for Literal {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "Int" => Literal::Int,
+            "Float" => Literal::Float,
+            _ => Err(format!("Unknown literal `{name}`"))?,
+        })
     }
 }
 
+/// Returns `true` for any ascii whitespace characters.
+pub(crate) fn is_whitespace(c: u8) -> bool {
+    matches!(c, 0x9 | 0xA | 0xB | 0xC | 0xD | 0x20)
+}
+
 /// Compares a character literal and an 8-bit byte for equality.
 fn matches(control: char, test: u8) -> bool {
     // Since `char` in Rust is UTF-8, `char` is 4 bytes.
@@ -175,6 +306,8 @@ fn matches(control: char, test: u8) -> bool {
 /// - `int`
 /// - `float`
 /// - `return`
+/// - `if`
+/// - `else`
 ///
 /// there are *n* unique states, with *n* being the number
 /// of characters in a keyword.
@@ -194,10 +327,30 @@ enum State {
     /// Parsing the decimal part of the floating point number.
     NumberFloat,
 
+    /// A `0x` (or `0X`), expecting the first digit of a hexadecimal literal.
+    HexPrefix,
+    /// Expecting a hexadecimal int literal.
+    /// This can be promoted to a hex float with '.' or a 'p' exponent later on.
+    HexDigits,
+    /// Parsing the fractional part of a hex float, which must end with a 'p' exponent.
+    HexFraction,
+    /// A 'p' (or 'P') of a hex float, expecting the sign or the first digit of the exponent.
+    HexExponent,
+    /// The sign of a hex float's exponent, expecting its first digit.
+    HexExponentSign,
+    /// Parsing the (decimal) binary exponent of a hex float.
+    HexExponentDigits,
+
     /// Expecting an identifier.
     /// This happens after other word possibilities (types/keywords) have been ruled out.
     Identifier,
 
+    /// An opening backtick, expecting the first character of a raw identifier.
+    RawIdentifierStart,
+    /// Parsing a raw identifier (ex. `` `return` ``), which is never a keyword,
+    /// until its closing backtick. The backticks are not part of the lexeme.
+    RawIdentifier,
+
     /// A word that is possibly the `int` keyword.
     /// Test the second letter for 'n'.
     /// If passed, go on to test the third letter, defaulting to identifier.
@@ -242,6 +395,32 @@ enum State {
     MaybeKeywordReturn6,
     /// A word that is possibly the `return` keyword.
     ConfirmKeywordReturn,
+
+    /// Test that the lexeme is, in fact, the if keyword depending on the given byte.
+    /// Only if it is a letter, underscore, or digit, it will not confirm.
+    ///
+    /// This shares its first letter with `int`, see `MaybeTypeInt2`.
+    ConfirmKeywordIf,
+
+    /// A word that is possibly the `else` keyword.
+    MaybeKeywordElse2,
+    /// A word that is possibly the `else` keyword.
+    MaybeKeywordElse3,
+    /// A word that is possibly the `else` keyword.
+    MaybeKeywordElse4,
+    /// A word that is possibly the `else` keyword.
+    ConfirmKeywordElse,
+
+    /// A word that is possibly the `const` keyword.
+    MaybeKeywordConst2,
+    /// A word that is possibly the `const` keyword.
+    MaybeKeywordConst3,
+    /// A word that is possibly the `const` keyword.
+    MaybeKeywordConst4,
+    /// A word that is possibly the `const` keyword.
+    MaybeKeywordConst5,
+    /// A word that is possibly the `const` keyword.
+    ConfirmKeywordConst,
 }
 
 /// The core structure of the lexical analysis.
@@ -254,18 +433,33 @@ enum State {
 /// given input and the current state of the machine.
 ///
 /// 0. 0 tokens implies either a whitespace character was passed in while
-/// ignoring whitespaces, or the character was purely concatenated into the
-/// internal lexeme buffer.
+///    ignoring whitespaces, or the character was purely concatenated into the
+///    internal lexeme buffer.
 /// 1. 1 token implies that a non-symbol byte was passed in, which also completed the lexeme.
 /// 2. 2 tokens implies that symbol byte was passed in (which completes immediately),
-/// which forces the current lexeme to also flush to preserve token-lexeme order.
+///    which forces the current lexeme to also flush to preserve token-lexeme order.
 pub(crate) struct StateMachine {
     state: State,
     lexeme: String,
+    /// The bytes read so far of a multi-byte UTF-8 character, see `tick_utf8`.
+    #[cfg(feature = "unicode-ident")]
+    utf8: Vec<u8>,
+    /// If the input has ended, and the last byte ticked is only `finalize`'s
+    /// stand-in newline.
+    finalized: bool,
 }
 impl StateMachine {
     /* PRIVATE METHODS */
 
+    /// What a lexical error found in place of what it expected: the byte just
+    /// ticked, or the end of the input (see `finalize`).
+    fn found(&self, c: u8) -> String {
+        match self.finalized {
+            true => "the end of input".into(),
+            false => format!("`0x{c:x}`"),
+        }
+    }
+
     /// Hard resets the state machine,
     /// erasing the lexeme and going into its default state
     fn reset(&mut self) {
@@ -273,10 +467,53 @@ impl StateMachine {
         self.lexeme.truncate(0);
     }
 
-    /// Report an error with a given error message, and exit the program.
-    fn detonate(&self, err_msg: String) -> ! {
-        eprintln!("ERROR - failed to parse lexemes: {err_msg}");
-        std::process::exit(LEXICAL_ERROR)
+    /// Advances the state machine by a byte of a multi-byte UTF-8 character.
+    ///
+    /// Once the character is complete, it either starts an identifier (if it
+    /// is `XID_Start`) or continues one (if it is `XID_Continue`), following
+    /// the Unicode rules for identifiers. It is a lexical error anywhere else.
+    #[cfg(feature = "unicode-ident")]
+    fn tick_utf8(&mut self, c: u8) -> Result<Option<Vec<(Token, String)>>, String> {
+        self.utf8.push(c);
+        let character = match std::str::from_utf8(&self.utf8) {
+            Ok(decoded) => decoded.chars().next().unwrap(),
+            // the character is not complete yet
+            Err(err) if err.error_len().is_none() => return Ok(None),
+            Err(_) => {
+                let bytes = self.utf8.drain(..).map(|b| format!("0x{b:x}")).collect::<Vec<_>>();
+                return Err(format!("Invalid UTF-8 sequence `{}`", bytes.join(" ")));
+            }
+        };
+        self.utf8.clear();
+
+        self.state = match self.state {
+            State::ScrollToNext if unicode_ident::is_xid_start(character) => State::Identifier,
+            State::ScrollToNext => return Err(format!("Unknown character `{character}`")),
+
+            State::NumberDigit | State::NumberFloat | State::HexPrefix | State::HexDigits | State::HexFraction
+            | State::HexExponent | State::HexExponentSign | State::HexExponentDigits => return Err(format!(
+                "Unexpected character `{character}` after `{}`",
+                self.lexeme
+            )),
+
+            State::RawIdentifierStart if unicode_ident::is_xid_start(character) => State::RawIdentifier,
+            State::RawIdentifier if unicode_ident::is_xid_continue(character) => State::RawIdentifier,
+            State::RawIdentifierStart | State::RawIdentifier => return Err(format!(
+                "Unexpected character `{character}` in a raw identifier after `{}`",
+                self.lexeme
+            )),
+
+            // Every other word state (keywords included) falls back to an identifier
+            _ if unicode_ident::is_xid_continue(character) => State::Identifier,
+            _ => return Err(format!(
+                "Unexpected character `{character}` after `{}`",
+                self.lexeme
+            )),
+        };
+
+        self.lexeme.push(character);
+
+        Ok(None)
     }
 
     /* PUBLIC METHODS */
@@ -289,6 +526,9 @@ impl StateMachine {
         Self {
             state: State::ScrollToNext,
             lexeme: "".into(),
+            #[cfg(feature = "unicode-ident")]
+            utf8: vec![],
+            finalized: false,
         }
     }
 
@@ -296,8 +536,12 @@ impl StateMachine {
     ///
     /// This is useful to use once EOF has been reached from the input source.
     ///
-    /// This function is identical to matching a whitespace.
-    pub fn finalize(mut self) -> Option<Vec<(Token, String)>> {
+    /// This function is identical to matching a whitespace, except that a
+    /// lexical error names the end of the input, rather than a newline, as what
+    /// was found (ex. "Expected a hexadecimal digit after `0x`, but found the
+    /// end of input").
+    pub fn finalize(mut self) -> Result<Option<Vec<(Token, String)>>, String> {
+        self.finalized = true;
         self.tick(0xA)
     }
 
@@ -307,8 +551,11 @@ impl StateMachine {
     /// updating the internal state of the state machine.
     ///
     /// If one or more lexemes have been detected as complete,
-    /// this function will return `Some`.
-    /// Otherwise, this will return `None`.
+    /// this function will return `Ok(Some(_))`.
+    /// Otherwise, this will return `Ok(None)`.
+    ///
+    /// If the byte cannot be accepted in the current state, this function
+    /// returns `Err(_)` with a message describing the lexical error.
     ///
     /// It is the user's responsibility to know when the input has ended, and
     /// then use `finalize`.
@@ -320,7 +567,7 @@ impl StateMachine {
     /// is intended to make the code more readable and maintainable.
     ///
     /// This is important to mention, because this function
-    /// returns `Some(_)` rather than `None` if and only if
+    /// returns `Ok(Some(_))` rather than `Ok(None)` if and only if
     ///
     /// 1. It was called through 1 of the 3 macros, and
     /// 2. The state machine was reset.
@@ -328,7 +575,7 @@ impl StateMachine {
     /// Hense, the verbage of "flush" in each of the macros.
     ///
     /// Each of the three macros are documented in source code.
-    pub fn tick(&mut self, c: u8) -> Option<Vec<(Token, String)>> {
+    pub fn tick(&mut self, c: u8) -> Result<Option<Vec<(Token, String)>>, String> {
         use crate::lexer::Symbol as Sym;
         use CharClass::*;
         use Type as Ty;
@@ -342,7 +589,7 @@ impl StateMachine {
 
                 self.reset();
 
-                return Some(vec![output]);
+                return Ok(Some(vec![output]));
             }};
         }
 
@@ -355,7 +602,7 @@ impl StateMachine {
 
                 self.reset();
 
-                return Some(vec![output]);
+                return Ok(Some(vec![output]));
             }};
         }
 
@@ -374,21 +621,34 @@ impl StateMachine {
 
                 self.reset();
 
-                return Some(output);
+                return Ok(Some(output));
             }};
         }
 
+        // A non-ASCII byte is part of a multi-byte UTF-8 character
+        #[cfg(feature = "unicode-ident")]
+        if c >= 0x80 || !self.utf8.is_empty() {
+            return self.tick_utf8(c);
+        }
+
         match self.state {
-            State::ScrollToNext if is_whitespace(c) => return None,
+            State::ScrollToNext if is_whitespace(c) => return Ok(None),
+            // the opening backtick of a raw identifier is not part of its lexeme
+            State::ScrollToNext if matches('`', c) => {
+                self.state = State::RawIdentifierStart;
+                return Ok(None);
+            }
             State::ScrollToNext => {
                 self.state = match CharClass::parse(c) {
                     Letter if matches('i', c) => State::MaybeTypeInt2,
                     Letter if matches('f', c) => State::MaybeTypeFloat2,
                     Letter if matches('r', c) => State::MaybeKeywordReturn2,
+                    Letter if matches('e', c) => State::MaybeKeywordElse2,
+                    Letter if matches('c', c) => State::MaybeKeywordConst2,
                     Letter | Symbol(Sym::Underscore) => State::Identifier,
                     Digit => State::NumberDigit,
                     Symbol(sym) => flush_symbol_as_token!(sym, c as char),
-                    Unknown => self.detonate(format!("Unknown character `0x{c:x}`")),
+                    Unknown => return Err(format!("Unknown character `0x{c:x}`")),
                 };
             }
 
@@ -397,12 +657,13 @@ impl StateMachine {
                 self.state = match CharClass::parse(c) {
                     Digit => State::NumberDigit,
                     Symbol(Sym::Period) => State::NumberFloat,
+                    Letter if self.lexeme == "0" && (matches('x', c) || matches('X', c)) => State::HexPrefix,
 
                     Symbol(sym) => {
                         flush_lexeme_and_symbol_as_tokens!(Literal::Int.into(), (sym, c as char))
                     }
 
-                    _ => self.detonate(format!(
+                    _ => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -418,13 +679,102 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Literal::Float.into(), (sym, c as char))
                     }
 
-                    _ => self.detonate(format!(
+                    _ => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::HexPrefix => {
+                self.state = match CharClass::parse(c) {
+                    Digit | Letter if c.is_ascii_hexdigit() => State::HexDigits,
+                    _ => return Err(format!(
+                        "Expected a hexadecimal digit after `{}`, but found {}",
+                        self.lexeme,
+                        self.found(c),
+                    )),
+                };
+            }
+
+            State::HexDigits if is_whitespace(c) => flush_lexeme_as_token!(Literal::Int.into()),
+            State::HexDigits => {
+                self.state = match CharClass::parse(c) {
+                    Digit | Letter if c.is_ascii_hexdigit() => State::HexDigits,
+                    Letter if matches('p', c) || matches('P', c) => State::HexExponent,
+                    Symbol(Sym::Period) => State::HexFraction,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Literal::Int.into(), (sym, c as char))
+                    }
+
+                    _ => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
                 };
             }
 
+            State::HexFraction => {
+                self.state = match CharClass::parse(c) {
+                    Digit | Letter if c.is_ascii_hexdigit() => State::HexFraction,
+                    Letter if matches('p', c) || matches('P', c) => State::HexExponent,
+
+                    // unlike a decimal float, a hex float cannot end without its exponent
+                    Symbol(_) => return Err(format!("The hex float `{}` is missing its `p` exponent", self.lexeme)),
+                    _ if is_whitespace(c) => return Err(format!("The hex float `{}` is missing its `p` exponent", self.lexeme)),
+
+                    _ => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::HexExponent | State::HexExponentSign => {
+                self.state = match CharClass::parse(c) {
+                    Digit => State::HexExponentDigits,
+                    Symbol(Sym::Plus | Sym::Minus) if matches!(self.state, State::HexExponent) => State::HexExponentSign,
+                    _ => return Err(format!(
+                        "Expected the exponent digits of `{}`, but found {}",
+                        self.lexeme,
+                        self.found(c),
+                    )),
+                };
+            }
+
+            State::HexExponentDigits if is_whitespace(c) => flush_lexeme_as_token!(Literal::Float.into()),
+            State::HexExponentDigits => {
+                self.state = match CharClass::parse(c) {
+                    Digit => State::HexExponentDigits,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Literal::Float.into(), (sym, c as char))
+                    }
+
+                    _ => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::RawIdentifierStart => {
+                self.state = match CharClass::parse(c) {
+                    Letter | Symbol(Sym::Underscore) => State::RawIdentifier,
+                    _ => return Err(format!("Expected an identifier after an opening backtick, but found {}", self.found(c))),
+                };
+            }
+
+            // the closing backtick is not part of the lexeme either
+            State::RawIdentifier if matches('`', c) => flush_lexeme_as_token!(Token::RawIdentifier),
+            State::RawIdentifier => {
+                self.state = match CharClass::parse(c) {
+                    Letter | Symbol(Sym::Underscore) | Digit => State::RawIdentifier,
+                    _ => return Err(format!("Unterminated raw identifier `{}`, expected a closing backtick", self.lexeme)),
+                };
+            }
+
             State::Identifier if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
             State::Identifier => {
                 self.state = match CharClass::parse(c) {
@@ -434,7 +784,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char));
                     }
 
-                    _ => self.detonate(format!(
+                    _ => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -445,13 +795,14 @@ impl StateMachine {
             State::MaybeTypeInt2 => {
                 self.state = match CharClass::parse(c) {
                     Letter if matches('n', c) => State::MaybeTypeInt3,
+                    Letter if matches('f', c) => State::ConfirmKeywordIf,
                     Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
 
                     Symbol(sym) => {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -468,7 +819,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -482,7 +833,7 @@ impl StateMachine {
                     Symbol(sym) => {
                         flush_lexeme_and_symbol_as_tokens!(Ty::Int.into(), (sym, c as char))
                     }
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -499,7 +850,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -516,7 +867,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -533,7 +884,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -550,7 +901,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -564,7 +915,7 @@ impl StateMachine {
                     Symbol(sym) => {
                         flush_lexeme_and_symbol_as_tokens!(Ty::Float.into(), (sym, c as char))
                     }
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -583,7 +934,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -602,7 +953,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -621,7 +972,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -640,7 +991,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -657,7 +1008,7 @@ impl StateMachine {
                         flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
                     }
 
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -673,7 +1024,168 @@ impl StateMachine {
                     Symbol(sym) => {
                         flush_lexeme_and_symbol_as_tokens!(Token::Return, (sym, c as char))
                     }
-                    Unknown => self.detonate(format!(
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::ConfirmKeywordIf if is_whitespace(c) => flush_lexeme_as_token!(Token::If),
+            State::ConfirmKeywordIf => {
+                self.state = match CharClass::parse(c) {
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::If, (sym, c as char))
+                    }
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordElse2 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordElse2 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('l', c) => State::MaybeKeywordElse3,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordElse3 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordElse3 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('s', c) => State::MaybeKeywordElse4,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordElse4 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordElse4 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('e', c) => State::ConfirmKeywordElse,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::ConfirmKeywordElse if is_whitespace(c) => flush_lexeme_as_token!(Token::Else),
+            State::ConfirmKeywordElse => {
+                self.state = match CharClass::parse(c) {
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Else, (sym, c as char))
+                    }
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordConst2 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordConst2 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('o', c) => State::MaybeKeywordConst3,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordConst3 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordConst3 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('n', c) => State::MaybeKeywordConst4,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordConst4 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordConst4 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('s', c) => State::MaybeKeywordConst5,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::MaybeKeywordConst5 if is_whitespace(c) => flush_lexeme_as_token!(Token::Identifier),
+            State::MaybeKeywordConst5 => {
+                self.state = match CharClass::parse(c) {
+                    Letter if matches('t', c) => State::ConfirmKeywordConst,
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Identifier, (sym, c as char))
+                    }
+
+                    Unknown => return Err(format!(
+                        "Unexpected character `0x{c:x}` after `{}`",
+                        self.lexeme
+                    )),
+                };
+            }
+
+            State::ConfirmKeywordConst if is_whitespace(c) => flush_lexeme_as_token!(Token::Const),
+            State::ConfirmKeywordConst => {
+                self.state = match CharClass::parse(c) {
+                    Letter | Symbol(Sym::Underscore) | Digit => State::Identifier,
+                    Symbol(sym) => {
+                        flush_lexeme_and_symbol_as_tokens!(Token::Const, (sym, c as char))
+                    }
+                    Unknown => return Err(format!(
                         "Unexpected character `0x{c:x}` after `{}`",
                         self.lexeme
                     )),
@@ -683,6 +1195,317 @@ impl StateMachine {
 
         self.lexeme.push(c as char);
 
-        None
+        Ok(None)
+    }
+}
+
+/// A lazy lexer: an iterator of token-lexeme pairs over some stream of bytes.
+///
+/// Each byte is only read (and ticked through the `StateMachine`) once the
+/// next token is asked for, so the input never has to be fully in memory.
+///
+/// If a lexical error is found, it is yielded as `Err(_)`, and the iterator
+/// ends afterwards.
+///
+/// If the very first byte is `#`, the whole first line is skipped, so that
+/// scripts can start with a shebang (ex. `#!/usr/bin/analyze`). Anywhere else,
+/// `#` is still an unknown character.
+pub struct Lexer<I: Iterator<Item = u8>> {
+    bytes: I,
+    /// The state machine, until the input is finalized or an error is found.
+    state_machine: Option<StateMachine>,
+    /// Tokens flushed by the state machine (with their byte ranges and locations), but not yet yielded.
+    pending: VecDeque<(OffsetToken, Location)>,
+    /// Whether no byte has been read yet, to skip a leading shebang line.
+    at_start: bool,
+    /// The number of bytes read so far: the byte offset of the next byte.
+    offset: usize,
+    /// The location of the next byte.
+    next_location: Location,
+    /// The location of the last byte read, see `next_byte`.
+    location: Location,
+    /// Whether each line break is also yielded as a `Newline` token.
+    newlines: bool,
+    /// Whether an int literal too large for an `i64` is a lexical error.
+    checked_ints: bool,
+    /// Whether a keyword is recognized in any case (ex. `RETURN`).
+    ci_keywords: bool,
+    /// The keyword table which replaces the built-in one, if any.
+    keywords: Option<HashMap<String, Token>>,
+    /// The columns between two tab stops, see `with_tab_width`.
+    tab_width: usize,
+}
+impl<I: Iterator<Item = u8>> Lexer<I> {
+    /// Creates a new lazy lexer over the given bytes.
+    pub fn new(bytes: I) -> Self {
+        Lexer {
+            bytes,
+            state_machine: Some(StateMachine::new()),
+            pending: VecDeque::new(),
+            at_start: true,
+            offset: 0,
+            next_location: (1, 1),
+            location: (1, 1),
+            newlines: false,
+            checked_ints: false,
+            ci_keywords: false,
+            keywords: None,
+            tab_width: DEFAULT_TAB_WIDTH,
+        }
+    }
+
+    /// Creates a new lazy lexer over the given bytes, which resume some input
+    /// at a token boundary (where the state machine is reset), `offset` bytes
+    /// in, at the line and column `location`.
+    /// 
+    /// Unlike `new`, a leading `#` is not a shebang, since it is not the very
+    /// start of the input.
+    pub fn resume(bytes: I, offset: usize, location: Location) -> Self {
+        Lexer {
+            at_start: false,
+            offset,
+            next_location: location,
+            location,
+            ..Lexer::new(bytes)
+        }
+    }
+
+    /// Also yields each line break (`\n`) as a `Newline` token, after any token
+    /// it ends, for dialects where a line break can separate items.
+    /// 
+    /// The line break of a skipped shebang line is never yielded.
+    pub fn with_newlines(mut self) -> Self {
+        self.newlines = true;
+        self
+    }
+
+    /// Also checks the magnitude of each int literal as it is finalized: an int
+    /// literal larger than `i64::MAX` is a lexical error (ex. "Integer literal
+    /// `…` is too large"), rather than being left to wrap later on.
+    /// 
+    /// A `-` is always its own token, so the limit is `i64::MAX`, even though
+    /// `-9223372036854775808` would fit.
+    pub fn with_checked_ints(mut self) -> Self {
+        self.checked_ints = true;
+        self
+    }
+
+    /// Also recognizes a keyword spelled in any case (ex. `RETURN`, `Float`, or
+    /// `iF`) as its keyword token, for languages where keywords are
+    /// case-insensitive.
+    /// 
+    /// The lexeme is kept as it was spelled (ex. `RETURN` is a `Return` token,
+    /// with the lexeme `RETURN`). A raw identifier (ex. `` `RETURN` ``) is still
+    /// always a `RawIdentifier`.
+    pub fn with_ci_keywords(mut self) -> Self {
+        self.ci_keywords = true;
+        self
+    }
+
+    /// Looks up every word in the given keyword table instead of the built-in
+    /// one (see `default_keywords`), for alternate syntaxes (ex. `fn` as the
+    /// `Return` keyword).
+    /// 
+    /// A word is the token it maps to, and otherwise an `Identifier`, even if it
+    /// is a built-in keyword (ex. `int`, unless it is also in the table). A raw
+    /// identifier (ex. `` `fn` ``) is still always a `RawIdentifier`. With
+    /// `with_ci_keywords`, a word is looked up in lowercase.
+    pub fn with_keywords(mut self, keywords: HashMap<String, Token>) -> Self {
+        self.keywords = Some(keywords);
+        self
+    }
+
+    /// Also yields the byte range of each token in the input, see `OffsetLexer`.
+    pub fn with_offsets(self) -> OffsetLexer<I> {
+        OffsetLexer(self)
+    }
+
+    /// Also yields the line and column of each token in the input, see `LocatedLexer`.
+    pub fn with_locations(self) -> LocatedLexer<I> {
+        LocatedLexer(self)
+    }
+
+    /// Set the columns between two tab stops, where a tab advances the column
+    /// to the next one (ex. 8 to match the alignment of an editor). The default
+    /// is `DEFAULT_TAB_WIDTH`, where a tab is a single column like any other
+    /// character.
+    /// 
+    /// This only changes the columns of locations (and of lexical errors),
+    /// never a byte offset.
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
+    /// The next byte, counting it in `offset` and in its `location`.
+    /// 
+    /// A column is counted in characters, so only the first byte of a UTF-8
+    /// sequence advances it (and the location of the others is the first's).
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.bytes.next()?;
+        self.offset += 1;
+        if !matches!(byte, 0x80..=0xBF) {
+            self.location = self.next_location;
+        }
+        let (line, column) = &mut self.next_location;
+        match byte {
+            b'\n' => (*line, *column) = (*line + 1, 1),
+            b'\t' => *column += self.tab_width - (*column - 1) % self.tab_width,
+            0x80..=0xBF => (),
+            _ => *column += 1,
+        }
+        Some(byte)
+    }
+
+    /// Rejects an int literal too large for an `i64`, only if `with_checked_ints`.
+    /// 
+    /// The error is at the `location` of the literal.
+    fn check_int(&self, token: Token, lexeme: &str, (line, column): Location) -> Result<(), String> {
+        let (radix, digits) = match lexeme.strip_prefix("0x").or(lexeme.strip_prefix("0X")) {
+            Some(digits) => (16, digits),
+            None => (10, lexeme),
+        };
+        if self.checked_ints && token == Token::Literal(Literal::Int) && i64::from_str_radix(digits, radix).is_err() {
+            Err(format!("Integer literal `{lexeme}` is too large, an int can be at most {} at {line}:{column}", i64::MAX))?
+        }
+        Ok(())
+    }
+
+    /// The next token, along with its byte range and its location.
+    fn next_located(&mut self) -> Option<Result<(OffsetToken, Location), String>> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                return Some(Ok(token));
+            }
+
+            // Tick the state machine until some tokens are flushed, or the input ends.
+            self.state_machine.as_ref()?;
+            let mut byte = self.next_byte();
+            let shebang = std::mem::take(&mut self.at_start) && byte == Some(b'#');
+            if shebang {
+                // skip the shebang line, up to (and including) its newline
+                while byte.is_some_and(|b| b != b'\n') {
+                    byte = self.next_byte();
+                }
+            }
+            let flushed = match byte {
+                Some(byte) => self.state_machine.as_mut().unwrap().tick(byte),
+                None => self.state_machine.take().unwrap().finalize(),
+            };
+
+            match flushed {
+                Ok(tokens) => {
+                    let tokens = tokens.unwrap_or_default();
+
+                    // A symbol is always the byte just ticked, and any other
+                    // token always ends right before it.
+                    let (ticked, ticked_location) = match byte {
+                        Some(_) => (self.offset - 1, self.location),
+                        None => (self.offset, self.next_location),
+                    };
+                    let (ci_keywords, keywords) = (self.ci_keywords, &self.keywords);
+                    let tokens = tokens.into_iter().map(|(token, lexeme)| {
+                        let raw = token == Token::RawIdentifier;
+                        let range = match token {
+                            Token::Symbol(_) => ticked..ticked + 1,
+                            // a raw identifier also spans its backticks (the byte just ticked is the closing one)
+                            Token::RawIdentifier => ticked - lexeme.len() - 1..ticked + 1,
+                            _ => ticked - lexeme.len()..ticked,
+                        };
+                        // no token spans lines, so it starts on the line it ends on
+                        let (line, column) = ticked_location;
+                        let location = match token {
+                            Token::Symbol(_) => (line, column),
+                            _ => (line, column - lexeme.chars().count() - usize::from(raw)),
+                        };
+                        // any other spelling of a keyword is an identifier to the state machine
+                        let word = token == Token::Identifier || keyword(&lexeme) == Some(token);
+                        let token = match (keywords, word) {
+                            (Some(keywords), true) => match ci_keywords {
+                                true => keywords.get(&lexeme.to_lowercase()),
+                                false => keywords.get(&lexeme),
+                            }.copied().unwrap_or(Token::Identifier),
+                            (None, true) if ci_keywords => keyword(&lexeme.to_lowercase()).unwrap_or(token),
+                            _ => token,
+                        };
+                        ((token, lexeme, range), location)
+                    }).collect::<Vec<_>>();
+                    let checked = tokens.iter().find_map(|((token, lexeme, _), location)| self.check_int(*token, lexeme, *location).err());
+                    if let Some(err_msg) = checked {
+                        self.state_machine = None;
+                        return Some(Err(err_msg));
+                    }
+                    self.pending.extend(tokens);
+                    if self.newlines && !shebang && byte == Some(b'\n') {
+                        self.pending.push_back(((Token::Newline, "\n".into(), ticked..ticked + 1), ticked_location));
+                    }
+                },
+                Err(err_msg) => {
+                    self.state_machine = None;
+                    // the error is at the byte just ticked, or at the very end of the input
+                    let (line, column) = match byte {
+                        Some(_) => self.location,
+                        None => self.next_location,
+                    };
+                    return Some(Err(format!("{err_msg} at {line}:{column}")));
+                }
+            }
+        }
+    }
+}
+impl<I: Iterator<Item = u8>> Iterator for Lexer<I> {
+    type Item = Result<(Token, String), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next_located()?;
+        Some(next.map(|((token, lexeme, _), _)| (token, lexeme)))
+    }
+}
+
+/// A token-lexeme pair, with the byte range of the lexeme in the input.
+pub type OffsetToken = (Token, String, Range<usize>);
+
+/// The line and column of a token in the input (both counted from 1), where
+/// the column is counted in characters.
+pub type Location = (usize, usize);
+
+/// The columns a tab advances by default (see `Lexer::with_tab_width`), where
+/// it is a single column like any other character.
+pub const DEFAULT_TAB_WIDTH: usize = 1;
+
+/// A token-lexeme pair, with the location of the start of the lexeme in the input.
+pub type LocatedToken = (Token, String, Location);
+
+/// A token-lexeme pair, with its leading trivia: the source text between the
+/// lexeme and the one before it (ex. whitespace), see `q1_lib::lex_with_trivia`.
+pub type TriviaToken = (Token, String, String);
+
+/// A lazy lexer which also yields the byte range of each token in the input
+/// (ex. for the text edits of an editor), see `Lexer::with_offsets`.
+/// 
+/// A range is of the lexeme alone: it never includes the whitespace around it.
+/// The only exception is a raw identifier, whose range includes its backticks.
+pub struct OffsetLexer<I: Iterator<Item = u8>>(Lexer<I>);
+impl<I: Iterator<Item = u8>> Iterator for OffsetLexer<I> {
+    type Item = Result<OffsetToken, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.0.next_located()?;
+        Some(next.map(|(token, _)| token))
+    }
+}
+
+/// A lazy lexer which also yields the location of each token in the input
+/// (ex. to report where a parse error is), see `Lexer::with_locations`.
+/// 
+/// Like a byte range, a location is of the lexeme alone, except for a raw
+/// identifier, whose location is its opening backtick.
+pub struct LocatedLexer<I: Iterator<Item = u8>>(Lexer<I>);
+impl<I: Iterator<Item = u8>> Iterator for LocatedLexer<I> {
+    type Item = Result<LocatedToken, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.0.next_located()?;
+        Some(next.map(|((token, lexeme, _), location)| (token, lexeme, location)))
     }
 }
@@ -3,11 +3,27 @@
 //! This library is split between IO (CLI argument parsing, file handling), and 
 //! the lexical analysis (the lexical State Machine, token types).
 
-use crate::io::{expected_read, open_file};
-use crate::lexer::{StateMachine, Token};
+use std::collections::HashMap; // A custom keyword table.
+use std::ops::Range; // The byte range of an edit.
+use std::path::Path; // The path of a token cache.
 
-/// Handler of all IO related functionality.
-mod io;
+use crate::brackets::{match_brackets, BracketToken};
+use crate::error_codes::LEXICAL_ERROR;
+use crate::intern::{Interner, LexemeId};
+use crate::io::{input_path, open_file, read_byte, IoError};
+use crate::lexer::{Lexer, LocatedToken, OffsetToken, Token, TriviaToken};
+
+/// Matching every bracket with its partner, for editors (ex. rainbow brackets).
+pub mod brackets;
+/// The binary token cache, of `save_tokens` and `load_tokens`.
+mod cache;
+/// Lexeme interning, storing every distinct lexeme only once.
+pub mod intern;
+/// Handler of all IO related functionality, returning an `IoError` rather
+/// than exiting.
+pub mod io;
+/// A minimal JSON reader, for the token streams of other lexers.
+pub mod json;
 /// Module for all lexical analysis types, implementations,
 /// and the **lexical state machine**.
 pub mod lexer;
@@ -30,24 +46,285 @@ mod error_codes {
 /// in 1 pass, in order.
 ///
 /// Returns the constructed token-lexeme pairs in order.
-pub fn get_lexemes() -> Vec<(Token, String)> {
+///
+/// Keywords are only recognized in any case with `ci_keywords` (see
+/// `Lexer::with_ci_keywords`), which the binary passes for `--ci-keywords`.
+///
+/// This is the entrypoint of the binary: the program will exit with an error
+/// message if an IO error (see `io::IoError`) or a lexical error is found.
+pub fn get_lexemes(ci_keywords: bool) -> Vec<(Token, String)> {
+    get_located_lexemes(ci_keywords)
+        .into_iter()
+        .map(|(token, lexeme, _)| (token, lexeme))
+        .collect()
+}
+
+/// The same as `get_lexemes`, but also with the location of each token in the
+/// input file (see `Lexer::with_locations`), ex. to report where a parse error is.
+pub fn get_located_lexemes(ci_keywords: bool) -> Vec<LocatedToken> {
+    match try_get_located_lexemes(ci_keywords) {
+        Ok(lexemes) => lexemes,
+        Err(err_msg) => {
+            eprintln!("ERROR - failed to parse lexemes: {err_msg}");
+            std::process::exit(LEXICAL_ERROR)
+        }
+    }
+}
+
+/// The same as `get_located_lexemes`, but a lexical error is returned rather
+/// than exiting, so that another binary can exit on it as it sees fit. The
+/// program still exits on an IO error.
+pub fn try_get_located_lexemes(ci_keywords: bool) -> Result<Vec<LocatedToken>, String> {
+    let exit = |err: IoError| -> ! {
+        eprintln!("ERROR - {err}");
+        std::process::exit(err.exit_code())
+    };
+
     // Try to open the file
-    let source = open_file();
+    let path = input_path().unwrap_or_else(|err| exit(err));
+    let source = open_file(path).unwrap_or_else(|err| exit(err));
 
-    // Initialize the state machine for parsing
-    let mut lexer_state_machine = StateMachine::new();
+    // Read the bytes from the file until one cannot be read.
+    let mut read_err = None;
+    let bytes = source.map_while(|maybe_c| {
+        read_byte(path, maybe_c).map_err(|err| read_err = Some(err)).ok()
+    });
 
-    // Continuously parses characters until EOF is reached
-    let mut lexemes = source
-        .map(|maybe_c| expected_read(maybe_c)) // Expect the next byte from the file, and report an io and exit otherwise.
-        .filter_map(|byte: u8| lexer_state_machine.tick(byte)) // Tick the state machine by the input byte, keeping any flushed lexemes.
-        .flatten() // Converts our iterator of batches into an iterator over all of the batches' items instead
-        .collect::<Vec<_>>(); // Collect the iterator to a list
+    let lexer = match ci_keywords {
+        true => Lexer::new(bytes).with_ci_keywords(),
+        false => Lexer::new(bytes),
+    };
+    let lexemes = lexer.with_locations().collect::<Result<Vec<_>, _>>();
 
-    // EOF has been reached. Finalize the state machine (send a dummy whitespace).
-    if let Some(final_tokens) = lexer_state_machine.finalize() {
-        lexemes.extend(final_tokens);
+    // A lexical error may only be from the input being cut short.
+    if let Some(err) = read_err {
+        exit(err)
     }
-
     lexemes
 }
+
+/// Builds the tokens/lexemes of some in-memory source text,
+/// byte-by-byte in 1 pass, in order.
+///
+/// Unlike `get_lexemes`, this never touches the CLI arguments or the
+/// filesystem, and a lexical error is returned rather than exiting.
+pub fn lex(source: &str) -> Result<Vec<(Token, String)>, String> {
+    lex_bytes(source.bytes())
+}
+
+/// The same as `lex`, but also with the byte range of each token in the source
+/// text (ex. `2..8` for the `return` of `  return 1;`).
+pub fn lex_with_offsets(source: &str) -> Result<Vec<OffsetToken>, String> {
+    Lexer::new(source.bytes()).with_offsets().collect()
+}
+
+/// The same as `lex`, but also with the line and column of each token in the
+/// source text (ex. `(2, 3)` for the `return` of `int f() {\n  return 1;`).
+pub fn lex_with_locations(source: &str) -> Result<Vec<LocatedToken>, String> {
+    Lexer::new(source.bytes()).with_locations().collect()
+}
+
+/// The same as `lex`, but also with the leading trivia of each token: all of
+/// the source text between its lexeme and the one before it (ex. `"  "` for
+/// the `return` of `  return 1;`), along with the trailing trivia after the
+/// very last lexeme (ex. a final line break).
+/// 
+/// This is everything the lexemes leave out, including a shebang line or the
+/// backticks of a raw identifier, so each trivia followed by its lexeme, in
+/// order, then the trailing trivia, is exactly the source text.
+pub fn lex_with_trivia(source: &str) -> Result<(Vec<TriviaToken>, String), String> {
+    let mut end = 0; // the end of the last lexeme
+    let tokens = lex_with_offsets(source)?;
+    let tokens = tokens.into_iter().map(|(token, lexeme, range)| {
+        // a raw identifier's range also spans its backticks, which are trivia
+        let start = range.end - lexeme.len() - usize::from(source[range.clone()] != lexeme);
+        let trivia = source[end..start].to_string();
+        end = start + lexeme.len();
+        (token, lexeme, trivia)
+    }).collect();
+    Ok((tokens, source[end..].to_string()))
+}
+
+/// The same as `lex`, but also with each line break as a `Newline` token
+/// (see `Lexer::with_newlines`).
+pub fn lex_with_newlines(source: &str) -> Result<Vec<(Token, String)>, String> {
+    Lexer::new(source.bytes()).with_newlines().collect()
+}
+
+/// The same as `lex`, but every word is looked up in the given keyword table
+/// instead of the built-in one (see `Lexer::with_keywords`), ex. mapping `fn`
+/// to `Token::Return`.
+/// 
+/// The built-in table is `lexer::default_keywords`, to extend rather than replace.
+pub fn lex_with_keywords(source: &str, keywords: &HashMap<String, Token>) -> Result<Vec<(Token, String)>, String> {
+    Lexer::new(source.bytes()).with_keywords(keywords.clone()).collect()
+}
+
+/// The same as `lex`, but with each lexeme interned (see `intern::Interner`):
+/// every token carries the id of its lexeme, and each distinct lexeme is only
+/// stored once, in the returned interner (ex. the many `x` of a long function).
+/// 
+/// A lexeme is looked up again with `Interner::resolve`.
+pub fn lex_interned(source: &str) -> Result<(Vec<(Token, LexemeId)>, Interner), String> {
+    let mut interner = Interner::new();
+    let tokens = Lexer::new(source.bytes())
+        .map(|token| token.map(|(token, lexeme)| (token, interner.intern(&lexeme))))
+        .collect::<Result<_, _>>()?;
+    Ok((tokens, interner))
+}
+
+/// The same as `lex`, but also with the nesting depth and matching partner of
+/// each bracket (see `brackets::match_brackets`), along with every unmatched
+/// bracket found, for bracket-matching before (or without) parsing.
+/// 
+/// An unmatched bracket is not a lexical error: it is only reported, and has
+/// no partner.
+pub fn lex_with_brackets(source: &str) -> Result<(Vec<BracketToken>, Vec<String>), String> {
+    let tokens = lex(source)?;
+    let (brackets, errors) = match_brackets(&tokens);
+    let tokens = tokens.into_iter()
+        .zip(brackets)
+        .map(|((token, lexeme), bracket)| (token, lexeme, bracket))
+        .collect();
+    Ok((tokens, errors))
+}
+
+/// Re-lexes only the part of some source text changed by an edit, rather than
+/// all of it, returning the edited source text and its tokens.
+/// 
+/// `tokens` must be the tokens of all of `source` (see `lex_with_offsets`),
+/// and the edit replaces the bytes of `edited` (in `source`) with `replacement`.
+/// 
+/// Lexing restarts at the end of the last token which cannot be changed (a
+/// token ending before the edit, since a token right before the edit can be
+/// joined to it), where the state machine was reset (see `is_reset_at`). It
+/// stops as soon as a token starts exactly where a token after the edit used
+/// to start (shifted by the edit), with the state machine reset before both,
+/// so every token after it is the same, only shifted. Otherwise, it lexes to
+/// the end. The result is always the same as lexing all of the edited source
+/// text.
+pub fn relex(source: &str, tokens: &[OffsetToken], edited: Range<usize>, replacement: &str) -> Result<(String, Vec<OffsetToken>), String> {
+    let edited_source = format!("{}{replacement}{}", &source[..edited.start], &source[edited.end..]);
+    let edited_end = edited.start + replacement.len(); // the end of the edit, in the edited source
+    let shifted = |(token, lexeme, range): &OffsetToken| {
+        let shift = |offset: usize| offset + replacement.len() - edited.len();
+        (*token, lexeme.clone(), shift(range.start)..shift(range.end))
+    };
+
+    // every token ending before the edit is unchanged, but lexing can only
+    // restart where the state machine was reset
+    let mut unchanged = tokens.iter().take_while(|(_, _, range)| range.end < edited.start).count();
+    while unchanged > 0 && !is_reset_at(source, tokens[unchanged - 1].2.end, Some(&tokens[unchanged - 1])) {
+        unchanged -= 1;
+    }
+    let restart = match unchanged {
+        0 => 0,
+        _ => tokens[unchanged - 1].2.end,
+    };
+    let mut relexed = tokens[..unchanged].to_vec();
+
+    // every token starting after the edit may be the same, only shifted
+    let mut after = tokens.iter().position(|(_, _, range)| range.start >= edited.end).unwrap_or(tokens.len());
+
+    let lexer = match restart {
+        0 => Lexer::new(edited_source.bytes()),
+        _ => {
+            // the line and column of the restart, counted like the lexer does
+            let before = &edited_source[..restart];
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+            Lexer::resume(edited_source[restart..].bytes(), restart, (line, column))
+        },
+    };
+    for token in lexer.with_offsets() {
+        let token = token?;
+        // skip every old token which the re-lexed tokens went past
+        while tokens.get(after).is_some_and(|old| shifted(old).2.start < token.2.start) {
+            after += 1;
+        }
+        let resynced = token.2.start >= edited_end
+            && tokens.get(after).is_some_and(|old| shifted(old).2.start == token.2.start)
+            && is_reset_at(&edited_source, token.2.start, relexed.last())
+            && is_reset_at(source, tokens[after].2.start, after.checked_sub(1).map(|previous| &tokens[previous]));
+        if resynced {
+            relexed.extend(tokens[after..].iter().map(shifted));
+            return Ok((edited_source, relexed));
+        }
+        relexed.push(token);
+    }
+    Ok((edited_source, relexed))
+}
+
+/// If the state machine is reset right before the byte at `offset` of some
+/// source text, so that lexing it afresh from there is the same as lexing up
+/// to it: at the start of the input, after whitespace, or right after a token
+/// ending on its own (a symbol, or a raw identifier's closing backtick).
+/// `previous` is the token before `offset`, if any.
+/// 
+/// Any other token only ends at the byte after it, which is lexed along with
+/// it (ex. the `_` of `5_` is a symbol, but would start an identifier afresh).
+fn is_reset_at(source: &str, offset: usize, previous: Option<&OffsetToken>) -> bool {
+    let Some(&last) = offset.checked_sub(1).and_then(|last| source.as_bytes().get(last)) else {
+        return true;
+    };
+    lexer::is_whitespace(last)
+        || previous.is_some_and(|(token, _, range)| range.end == offset && matches!(token, Token::Symbol(_) | Token::RawIdentifier))
+}
+
+/// Asserts that some source text lexes to exactly the `expected` token-lexeme pairs, in order.
+///
+/// Only with the `test-util` feature.
+#[cfg(feature = "test-util")]
+#[track_caller]
+pub fn assert_lexes(source: &str, expected: &[(Token, &str)]) {
+    let lexemes = lex(source).unwrap_or_else(|err| panic!("`{source}` failed to lex: {err}"));
+    let lexemes = lexemes.iter()
+        .map(|(token, lexeme)| (*token, lexeme.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(lexemes, expected, "`{source}` did not lex as expected");
+}
+
+/// Reads an already lexed token stream from JSON, as an array of
+/// `{"token": "...", "lexeme": "..."}` objects, in order.
+///
+/// Each token is named exactly as it is printed with `{:?}` (ex. `Identifier`,
+/// `Symbol(Plus)`, or `Literal(Float)`), see `Token::from_str`.
+///
+/// This lets the token stream of any other lexer stand in for `lex`.
+pub fn tokens_from_json(json: &str) -> Result<Vec<(Token, String)>, String> {
+    json::read_string_objects(json)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, object)| {
+            let field = |key: &str| {
+                object.iter()
+                    .find(|(object_key, _)| object_key == key)
+                    .map(|(_, value)| value.clone())
+                    .ok_or(format!("Expected a `{key}` in token {i} of the JSON"))
+            };
+            Ok((field("token")?.parse()?, field("lexeme")?))
+        })
+        .collect()
+}
+
+/// Saves a token stream to a compact binary cache file, so that it can be
+/// loaded again with `load_tokens`, rather than lexing the source again.
+pub fn save_tokens(path: impl AsRef<Path>, tokens: &[(Token, String)]) -> Result<(), String> {
+    let path = path.as_ref();
+    std::fs::write(path, cache::encode(tokens)?)
+        .map_err(|err| format!("Could not write the token cache `{}`: {err}", path.display()))
+}
+
+/// Loads a token stream from a binary cache file, as saved by `save_tokens`.
+pub fn load_tokens(path: impl AsRef<Path>) -> Result<Vec<(Token, String)>, String> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)
+        .map_err(|err| format!("Could not read the token cache `{}`: {err}", path.display()))?;
+    cache::decode(&bytes)
+}
+
+/// The shared driver of the state machine over some stream of bytes.
+fn lex_bytes(bytes: impl Iterator<Item = u8>) -> Result<Vec<(Token, String)>, String> {
+    // Continuously parses characters until EOF is reached, stopping at the first error.
+    Lexer::new(bytes).collect()
+}
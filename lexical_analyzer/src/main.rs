@@ -1,13 +1,18 @@
+use std::env::args;
+
 use q1_lib::get_lexemes;
 
 /// The main function.
 ///
 /// Look in crate `q1_lib` for the backend implementation.
 fn main() {
+    // Every option follows the input path.
+    let ci_keywords = args().skip(2).any(|arg| arg == "--ci-keywords");
+
     // Get the tagged tokens, immutably storing it in lexemes.
-    let lexemes = get_lexemes();
+    let lexemes = get_lexemes(ci_keywords);
 
-    println!("{:<24}|{}\n{:_<24}|{:_<24}", "TOKEN", "LEXEME", "", "");
+    println!("{:<24}|LEXEME\n{:_<24}|{:_<24}", "TOKEN", "", "");
     for (token, lexeme) in lexemes {
         println!("{:<24}|{}", format!("{token:?}"), lexeme)
     }
@@ -0,0 +1,454 @@
+//! Tests of the lexer, through its `assert_lexes` test helper.
+
+use std::collections::{HashMap, HashSet};
+
+use q1_lib::{
+    assert_lexes, lex, lex_interned, lex_with_brackets, lex_with_keywords, lex_with_locations, lex_with_newlines, lex_with_offsets, lex_with_trivia, load_tokens, relex, save_tokens,
+    brackets::Bracket,
+    intern::Interner,
+    io::{open_file, IoError},
+    lexer::{default_keywords, Lexer, Literal, Symbol, Token, Type},
+};
+
+#[test]
+fn keywords_are_tokens_of_their_own() {
+    assert_lexes("int", &[(Token::Type(Type::Int), "int")]);
+    assert_lexes("float", &[(Token::Type(Type::Float), "float")]);
+    assert_lexes("return", &[(Token::Return, "return")]);
+    assert_lexes("if", &[(Token::If, "if")]);
+    assert_lexes("else", &[(Token::Else, "else")]);
+    assert_lexes("const", &[(Token::Const, "const")]);
+}
+
+#[test]
+fn words_around_keywords_are_identifiers() {
+    assert_lexes("x", &[(Token::Identifier, "x")]);
+    assert_lexes("integer", &[(Token::Identifier, "integer")]);
+    assert_lexes("in", &[(Token::Identifier, "in")]);
+    assert_lexes("iff", &[(Token::Identifier, "iff")]);
+    assert_lexes("elsewhere", &[(Token::Identifier, "elsewhere")]);
+    assert_lexes("cons", &[(Token::Identifier, "cons")]);
+    assert_lexes("constant", &[(Token::Identifier, "constant")]);
+    assert_lexes("x1", &[(Token::Identifier, "x1")]);
+}
+
+#[test]
+fn numbers_are_int_or_float_literals() {
+    assert_lexes("0", &[(Token::Literal(Literal::Int), "0")]);
+    assert_lexes("42", &[(Token::Literal(Literal::Int), "42")]);
+    assert_lexes("3.14", &[(Token::Literal(Literal::Float), "3.14")]);
+    assert_lexes("0x1F", &[(Token::Literal(Literal::Int), "0x1F")]);
+    assert_lexes(
+        "0x1.8p3+0x1p-1;",
+        &[
+            (Token::Literal(Literal::Float), "0x1.8p3"),
+            (Token::Symbol(Symbol::Plus), "+"),
+            (Token::Literal(Literal::Float), "0x1p-1"),
+            (Token::Symbol(Symbol::Semicolon), ";"),
+        ],
+    );
+    assert!(lex("0x1.8 ").is_err());
+    assert!(lex("0x1.8;").is_err());
+}
+
+#[test]
+fn every_symbol_is_a_single_character() {
+    assert_lexes(
+        "+-*/%=;(){},?:!",
+        &[
+            (Token::Symbol(Symbol::Plus), "+"),
+            (Token::Symbol(Symbol::Minus), "-"),
+            (Token::Symbol(Symbol::Multiply), "*"),
+            (Token::Symbol(Symbol::Divide), "/"),
+            (Token::Symbol(Symbol::Modulo), "%"),
+            (Token::Symbol(Symbol::Equal), "="),
+            (Token::Symbol(Symbol::Semicolon), ";"),
+            (Token::Symbol(Symbol::LeftParen), "("),
+            (Token::Symbol(Symbol::RightParen), ")"),
+            (Token::Symbol(Symbol::LeftCurly), "{"),
+            (Token::Symbol(Symbol::RightCurly), "}"),
+            (Token::Symbol(Symbol::Comma), ","),
+            (Token::Symbol(Symbol::Question), "?"),
+            (Token::Symbol(Symbol::Colon), ":"),
+            (Token::Symbol(Symbol::Not), "!"),
+        ],
+    );
+}
+
+#[test]
+fn adjacent_tokens_need_no_whitespace() {
+    assert_lexes(
+        "x+1",
+        &[
+            (Token::Identifier, "x"),
+            (Token::Symbol(Symbol::Plus), "+"),
+            (Token::Literal(Literal::Int), "1"),
+        ],
+    );
+    assert_lexes(
+        "return(a*2.5);",
+        &[
+            (Token::Return, "return"),
+            (Token::Symbol(Symbol::LeftParen), "("),
+            (Token::Identifier, "a"),
+            (Token::Symbol(Symbol::Multiply), "*"),
+            (Token::Literal(Literal::Float), "2.5"),
+            (Token::Symbol(Symbol::RightParen), ")"),
+            (Token::Symbol(Symbol::Semicolon), ";"),
+        ],
+    );
+}
+
+#[test]
+fn whitespace_only_separates_tokens() {
+    assert_lexes("", &[]);
+    assert_lexes(" \t\n ", &[]);
+    assert_lexes(
+        "  int\n\tf ",
+        &[(Token::Type(Type::Int), "int"), (Token::Identifier, "f")],
+    );
+}
+
+#[test]
+fn newlines_can_be_kept_as_tokens() {
+    let tokens = lex_with_newlines("x\n\n  y,\t\nz").unwrap();
+    let tokens = tokens.iter().map(|(token, lexeme)| (*token, lexeme.as_str())).collect::<Vec<_>>();
+    assert_eq!(tokens, [
+        (Token::Identifier, "x"),
+        (Token::Newline, "\n"),
+        (Token::Newline, "\n"),
+        (Token::Identifier, "y"),
+        (Token::Symbol(Symbol::Comma), ","),
+        (Token::Newline, "\n"),
+        (Token::Identifier, "z"),
+    ]);
+
+    // the line break of a shebang is skipped along with it
+    assert_eq!(lex_with_newlines("#!/usr/bin/analyze\nx").unwrap(), [(Token::Identifier, "x".to_string())]);
+}
+
+#[test]
+fn backticks_make_raw_identifiers() {
+    assert_lexes("`if`", &[(Token::RawIdentifier, "if")]);
+    assert_lexes("if", &[(Token::If, "if")]);
+    assert_lexes(
+        "`return`=`x_1`;",
+        &[
+            (Token::RawIdentifier, "return"),
+            (Token::Symbol(Symbol::Equal), "="),
+            (Token::RawIdentifier, "x_1"),
+            (Token::Symbol(Symbol::Semicolon), ";"),
+        ],
+    );
+
+    // the range of a raw identifier spans its backticks
+    let tokens = lex_with_offsets("a `else` b").unwrap();
+    assert_eq!(tokens[1], (Token::RawIdentifier, "else".into(), 2..8));
+    assert_relexes("a `else` b", 9..10, "c");
+
+    assert_eq!(lex("`if").err(), Some("Unterminated raw identifier `if`, expected a closing backtick at 1:4".into()));
+    assert!(lex("`if x`").is_err());
+    assert!(lex("``").is_err());
+    assert!(lex("`1`").is_err());
+}
+
+#[test]
+fn huge_int_literals_can_be_rejected_by_the_lexer() {
+    let lex_checked = |source: &str| Lexer::new(source.bytes()).with_checked_ints().collect::<Result<Vec<_>, _>>();
+
+    assert_eq!(
+        lex_checked("x = 123456789012345678901234567890;").err(),
+        Some("Integer literal `123456789012345678901234567890` is too large, an int can be at most 9223372036854775807 at 1:5".into())
+    );
+    assert!(lex_checked("x = 9223372036854775807;").is_ok());
+    assert!(lex_checked("x = 9223372036854775808").is_err());
+    assert!(lex_checked("x = 0x7FFFFFFFFFFFFFFF;").is_ok());
+    assert!(lex_checked("x = 0x8000000000000000;").is_err());
+    // unchecked, the digits of an int are unlimited
+    assert!(lex("x = 123456789012345678901234567890;").is_ok());
+}
+
+#[test]
+fn keywords_can_be_lexed_in_any_case() {
+    let lex_ci = |source: &str| Lexer::new(source.bytes()).with_ci_keywords().collect::<Result<Vec<_>, _>>().unwrap();
+    let semicolon = (Token::Symbol(Symbol::Semicolon), ";".to_string());
+
+    assert_eq!(
+        lex_ci("RETURN 1;"),
+        vec![(Token::Return, "RETURN".into()), (Token::Literal(Literal::Int), "1".into()), semicolon.clone()]
+    );
+    assert_eq!(
+        lex("RETURN 1;").unwrap(),
+        vec![(Token::Identifier, "RETURN".into()), (Token::Literal(Literal::Int), "1".into()), semicolon]
+    );
+
+    let tokens = lex_ci("INT Float iF Else CONST");
+    let tokens = tokens.iter().map(|(token, _)| *token).collect::<Vec<_>>();
+    assert_eq!(tokens, [Token::Type(Type::Int), Token::Type(Type::Float), Token::If, Token::Else, Token::Const]);
+
+    // only whole keywords, and never raw identifiers
+    assert_eq!(lex_ci("RETURNS `RETURN`")[0].0, Token::Identifier);
+    assert_eq!(lex_ci("RETURNS `RETURN`")[1].0, Token::RawIdentifier);
+}
+
+#[test]
+fn keywords_can_come_from_a_custom_table() {
+    let keywords = HashMap::from([("fn".to_string(), Token::Return)]);
+    assert_eq!(
+        lex_with_keywords("fn x;", &keywords).unwrap(),
+        vec![(Token::Return, "fn".into()), (Token::Identifier, "x".into()), (Token::Symbol(Symbol::Semicolon), ";".into())]
+    );
+
+    // the table replaces the built-in one, and never applies to raw identifiers
+    let tokens = lex_with_keywords("return `fn` fns", &keywords).unwrap();
+    let tokens = tokens.iter().map(|(token, _)| *token).collect::<Vec<_>>();
+    assert_eq!(tokens, [Token::Identifier, Token::RawIdentifier, Token::Identifier]);
+
+    // which is still the default, to extend
+    let mut keywords = default_keywords();
+    keywords.insert("fn".to_string(), Token::Return);
+    let tokens = lex_with_keywords("int fn return", &keywords).unwrap();
+    let tokens = tokens.iter().map(|(token, _)| *token).collect::<Vec<_>>();
+    assert_eq!(tokens, [Token::Type(Type::Int), Token::Return, Token::Return]);
+    assert_eq!(lex_with_keywords("int x = 1;", &default_keywords()), lex("int x = 1;"));
+}
+
+#[test]
+fn the_end_of_input_ends_a_token_like_a_newline() {
+    let fixtures = [
+        // keywords, and every prefix of one
+        "int", "in", "i", "if",
+        "float", "floa", "flo", "fl", "f",
+        "return", "retur", "retu", "ret", "re", "r",
+        "else", "els", "el", "e",
+        "const", "cons", "con", "co", "c",
+        // identifiers, including ones extending a keyword
+        "x", "x_1", "integer", "returns", "`if`",
+        // literals
+        "0", "123", "1.5", "0x1F", "0x1.8p3", "0x1p-2",
+        // a token after a symbol
+        "x;", "(int", "1+2",
+    ];
+    for fixture in fixtures {
+        let at_eof = lex(fixture);
+        assert!(at_eof.as_ref().is_ok_and(|tokens| !tokens.is_empty()), "`{fixture}` lexed to {at_eof:?}");
+        assert_eq!(at_eof, lex(&format!("{fixture}\n")), "`{fixture}` lexed differently at the end of the input");
+    }
+
+    // an unfinished token is an error either way, but only a newline is found after it
+    assert_eq!(lex("0x"), Err("Expected a hexadecimal digit after `0x`, but found the end of input at 1:3".into()));
+    assert_eq!(lex("0x\n"), Err("Expected a hexadecimal digit after `0x`, but found `0xa` at 1:3".into()));
+    assert_eq!(lex("0x1p"), Err("Expected the exponent digits of `0x1p`, but found the end of input at 1:5".into()));
+    assert_eq!(lex("`"), Err("Expected an identifier after an opening backtick, but found the end of input at 1:2".into()));
+}
+
+#[test]
+fn unknown_characters_are_errors() {
+    assert!(lex("x = 1 @ 2").is_err());
+    assert!(lex("a # b").is_err());
+
+    // an error is at the character it was found at
+    assert_eq!(lex("int x;\n  x = @;").err(), Some("Unknown character `0x40` at 2:7".into()));
+}
+
+#[test]
+fn a_missing_file_is_an_error_rather_than_an_exit() {
+    let err = open_file("../targets/missing.txt").err().unwrap();
+    assert!(matches!(err, IoError::OpenFile { ref path, .. } if path == "../targets/missing.txt"));
+    assert_eq!(err.exit_code(), 2);
+    assert!(err.to_string().starts_with("could not open file `../targets/missing.txt` due to IO error"));
+}
+
+#[test]
+fn a_leading_shebang_line_is_skipped() {
+    assert_lexes("#!/usr/bin/analyze\nx", &[(Token::Identifier, "x")]);
+    assert_lexes("#!/usr/bin/analyze", &[]);
+
+    // only the very first line can be a shebang
+    assert!(lex(" #!/usr/bin/analyze\nx").is_err());
+    assert!(lex("x\n#!/usr/bin/analyze").is_err());
+}
+
+#[test]
+fn tokens_can_be_map_keys() {
+    let tokens = lex("int x = y + 1; x = 2.5 + y;").unwrap();
+
+    let kinds = tokens.iter().map(|(token, _)| *token).collect::<HashSet<_>>();
+    assert_eq!(kinds.len(), 7);
+    assert!(kinds.contains(&Token::Literal(Literal::Float)));
+    assert!(!kinds.contains(&Token::Return));
+
+    let mut counts = HashMap::<Token, usize>::new();
+    for (token, _) in &tokens {
+        *counts.entry(*token).or_default() += 1;
+    }
+    assert_eq!(counts[&Token::Identifier], 4);
+    assert_eq!(counts[&Token::Symbol(Symbol::Plus)], 2);
+}
+
+#[test]
+fn tokens_carry_their_byte_ranges() {
+    let tokens = lex_with_offsets("  return 1;").unwrap();
+    let ranges = tokens.iter().map(|(_, lexeme, range)| (lexeme.as_str(), range.clone())).collect::<Vec<_>>();
+    assert_eq!(ranges, [("return", 2..8), ("1", 9..10), (";", 10..11)]);
+
+    // every range is exactly its lexeme in the source, even around a shebang or at the end of the input
+    for source in ["int f(float x){return x*2.5;}", "#!/usr/bin/analyze\n\tx = a+b ", "y"] {
+        for (_, lexeme, range) in lex_with_offsets(source).unwrap() {
+            assert_eq!(&source[range], lexeme);
+        }
+    }
+}
+
+#[test]
+fn tokens_carry_their_locations() {
+    let tokens = lex_with_locations("int f() {\n  return `if`+x;\n}").unwrap();
+    let locations = tokens.iter().map(|(_, lexeme, location)| (lexeme.as_str(), *location)).collect::<Vec<_>>();
+    assert_eq!(
+        locations,
+        [
+            ("int", (1, 1)), ("f", (1, 5)), ("(", (1, 6)), (")", (1, 7)), ("{", (1, 9)),
+            // a raw identifier is located at its opening backtick
+            ("return", (2, 3)), ("if", (2, 10)), ("+", (2, 14)), ("x", (2, 15)), (";", (2, 16)),
+            ("}", (3, 1)),
+        ]
+    );
+
+    // a tab advances a column, or to the next tab stop with a tab width
+    let source = "\tx\t= 1;\n  \ty\t@";
+    let located = |lexer: Lexer<_>| lexer.with_locations().map(|token| token.map(|(_, _, location)| location)).collect::<Result<Vec<_>, _>>();
+    assert_eq!(located(Lexer::new("\tx\t= 1;".bytes())), Ok(vec![(1, 2), (1, 4), (1, 6), (1, 7)]));
+    assert_eq!(located(Lexer::new("\tx\t= 1;".bytes()).with_tab_width(4)), Ok(vec![(1, 5), (1, 9), (1, 11), (1, 12)]));
+    assert_eq!(located(Lexer::new(source.bytes()).with_tab_width(4)).err(), Some("Unknown character `0x40` at 2:9".into()));
+}
+
+#[test]
+fn trivia_reconstructs_the_exact_source() {
+    let (tokens, trailing) = lex_with_trivia("  return 1;").unwrap();
+    let trivia = tokens.iter().map(|(_, lexeme, trivia)| (lexeme.as_str(), trivia.as_str())).collect::<Vec<_>>();
+    assert_eq!(trivia, [("return", "  "), ("1", " "), (";", "")]);
+    assert_eq!(trailing, "");
+
+    // the text after the last token is trailing trivia, before no token
+    let source = "#!/usr/bin/analyze
+x = `if`;  \n";
+    let (tokens, trailing) = lex_with_trivia(source).unwrap();
+    assert_eq!(trailing, "  \n");
+    let exact = tokens.iter().map(|(_, lexeme, trivia)| format!("{trivia}{lexeme}")).collect::<String>();
+    assert_eq!(exact + &trailing, source);
+    assert_eq!(lex_with_trivia("\n").unwrap(), (vec![], "\n".to_string()));
+}
+
+/// Edits some source text, asserting that re-lexing only the edit gives the same tokens as lexing all of it.
+fn assert_relexes(source: &str, edited: std::ops::Range<usize>, replacement: &str) {
+    let tokens = lex_with_offsets(source).unwrap();
+    let (edited_source, relexed) = relex(source, &tokens, edited, replacement).unwrap();
+    assert_eq!(relexed, lex_with_offsets(&edited_source).unwrap(), "`{edited_source}` did not re-lex as expected");
+}
+
+#[test]
+fn relexing_an_edit_matches_a_full_lex() {
+    let source = (0..1000)
+        .map(|i| format!("int f{i}(int x, float y) {{\n    x = y * {i}.5 + x;\n    return x;\n}}\n"))
+        .collect::<String>();
+    let digit = source.find("500.5").unwrap();
+    assert_relexes(&source, digit..digit + 1, "7");
+    assert_relexes(&source, digit..digit + 1, "");
+
+    // edits which join, split, or change the kind of tokens
+    assert_relexes("x +y", 1..3, "");
+    assert_relexes("xy = 1;", 1..1, " ");
+    assert_relexes("a = 12;", 5..5, ".5");
+    assert_relexes("a = 1; b = 2;", 3..10, "");
+    assert_relexes("retur x;", 5..5, "n");
+    assert_relexes("x", 1..1, " = y;");
+    assert_relexes("#!/usr/bin/analyze\nx", 19..20, "y");
+    assert_relexes("x = 1;", 0..0, "#!/usr/bin/analyze\n");
+    assert_relexes("", 0..0, "int");
+    // a token lexed along with the one before it (the `_` symbol of `8_`) is not a restart
+    assert_relexes("8_", 2..2, "x");
+    assert_relexes("8_ y", 3..4, "z");
+}
+
+#[test]
+fn token_caches_round_trip() {
+    let path = std::env::temp_dir().join(format!("q1_token_cache_{}.bin", std::process::id()));
+    let tokens = lex("#!/usr/bin/analyze\nint f(float x, int y) { return (int)x * 0x1.8p3 + y / 2; }").unwrap();
+    save_tokens(&path, &tokens).unwrap();
+    let loaded = load_tokens(&path);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.unwrap(), tokens);
+
+    // every token kind (and a non-ASCII lexeme) survives the cache
+    let every_token = lex_with_newlines("a\n`b` 1 2.5 int float return if else const +-*/%=;(){},?:!").unwrap();
+    let mut every_token = every_token.into_iter().chain([(Token::Identifier, "π".to_string())]).collect::<Vec<_>>();
+    every_token.push((Token::Symbol(Symbol::Underscore), "_".into()));
+    every_token.push((Token::Symbol(Symbol::Period), ".".into()));
+    save_tokens(&path, &every_token).unwrap();
+    let loaded = load_tokens(&path);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.unwrap(), every_token);
+
+    assert!(load_tokens(&path).is_err());
+}
+
+#[test]
+fn interned_lexemes_are_stored_once() {
+    let (tokens, interner) = lex_interned("x = x + y * x;").unwrap();
+    let lexemes = tokens.iter().map(|(_, id)| interner.resolve(*id)).collect::<Vec<_>>();
+    assert_eq!(lexemes, ["x", "=", "x", "+", "y", "*", "x", ";"]);
+
+    // each distinct lexeme is stored once, so equal lexemes have equal ids
+    assert_eq!(interner.len(), 6);
+    assert_eq!(tokens[0].1, tokens[2].1);
+    assert_ne!(tokens[0].1, tokens[4].1);
+    assert_eq!(tokens.iter().map(|(token, _)| *token).collect::<Vec<_>>(), lex("x = x + y * x;").unwrap().into_iter().map(|(token, _)| token).collect::<Vec<_>>());
+
+    let mut interner = Interner::new();
+    let id = interner.intern("total");
+    assert_eq!(interner.intern("total"), id);
+    assert_eq!(interner.resolve(id), "total");
+}
+
+#[test]
+fn brackets_know_their_depth_and_partner() {
+    let (tokens, errors) = lex_with_brackets("f(g(x))").unwrap();
+    assert!(errors.is_empty());
+    let brackets = tokens.iter().map(|(_, _, bracket)| *bracket).collect::<Vec<_>>();
+    let bracket = |depth, partner| Some(Bracket { depth, partner: Some(partner) });
+    assert_eq!(brackets, [None, bracket(0, 6), None, bracket(1, 5), None, bracket(1, 3), bracket(0, 1)]);
+
+    // an unmatched bracket has no partner, and is reported
+    let (tokens, errors) = lex_with_brackets("{ a) }").unwrap();
+    assert_eq!(tokens[2].2, Some(Bracket { depth: 1, partner: None }));
+    assert_eq!(tokens[3].2, Some(Bracket { depth: 0, partner: Some(0) }));
+    assert_eq!(errors, ["Mismatched `)` at token 2, which cannot close the `{` at token 0"]);
+
+    let (_, errors) = lex_with_brackets(") (").unwrap();
+    assert_eq!(errors, ["Unmatched `)` at token 0, with no bracket open", "Unclosed `(` at token 1"]);
+}
+
+#[test]
+fn relexing_random_edits_matches_a_full_lex() {
+    const FRAGMENTS: [&str; 20] = ["int", "x", "1", "8", "_", ".", "5", "0x", "p", "e", " ", "\n", "`", "(", ")", "+", ";", "if", "#", "/"];
+    let mut seed = 0x2545_F491_4F6C_DD1Du64;
+    let mut random = |below: usize| {
+        // xorshift, so that every run makes the same edits
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % below as u64) as usize
+    };
+
+    for _ in 0..20_000 {
+        let source = (0..random(12)).map(|_| FRAGMENTS[random(FRAGMENTS.len())]).collect::<String>();
+        let Ok(tokens) = lex_with_offsets(&source) else { continue };
+        let start = random(source.len() + 1);
+        let end = start + random(source.len() - start + 1);
+        let replacement = (0..random(3)).map(|_| FRAGMENTS[random(FRAGMENTS.len())]).collect::<String>();
+
+        let edited_source = format!("{}{replacement}{}", &source[..start], &source[end..]);
+        let relexed = relex(&source, &tokens, start..end, &replacement).map(|(_, relexed)| relexed);
+        assert_eq!(relexed, lex_with_offsets(&edited_source), "`{source}` edited to `{edited_source}` did not re-lex as expected");
+    }
+}
@@ -0,0 +1,25 @@
+//! Tests of lexing Unicode identifiers, only with the `unicode-ident` feature.
+#![cfg(feature = "unicode-ident")]
+
+use q1_lib::{lex, lex_with_locations, lex_with_offsets};
+
+#[test]
+fn identifiers_can_contain_non_ascii_letters() {
+    // a keyword prefix still falls back to an identifier
+    let tokens = lex("intä über").unwrap();
+    let lexemes = tokens.iter().map(|(_, lexeme)| lexeme.as_str()).collect::<Vec<_>>();
+    assert_eq!(lexemes, ["intä", "über"]);
+
+    // byte ranges count every byte of a multi-byte character, where columns count characters
+    let ranges = lex_with_offsets("intä über").unwrap().into_iter().map(|(_, _, range)| range).collect::<Vec<_>>();
+    assert_eq!(ranges, [0..5, 6..11]);
+    let locations = lex_with_locations("intä über").unwrap().into_iter().map(|(_, _, location)| location).collect::<Vec<_>>();
+    assert_eq!(locations, [(1, 1), (1, 6)]);
+}
+
+#[test]
+fn non_identifier_characters_are_still_errors() {
+    assert_eq!(lex("x = 1€").err(), Some("Unexpected character `€` after `1` at 1:6".into()));
+    assert_eq!(lex("→").err(), Some("Unknown character `→` at 1:1".into()));
+    assert_eq!(lex("a\u{e9}").map(|tokens| tokens.len()), Ok(1));
+}
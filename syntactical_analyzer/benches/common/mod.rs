@@ -0,0 +1,38 @@
+//! The peak heap memory measurement shared by the memory benches.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The system allocator, keeping track of the current and peak allocated bytes.
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = self.current.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        self.peak.fetch_max(current, Ordering::SeqCst);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.current.fetch_sub(layout.size(), Ordering::SeqCst);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Runs `f`, returning the peak heap bytes allocated above the starting point.
+pub fn peak_bytes_of(f: impl FnOnce()) -> usize {
+    let start = ALLOCATOR.current.load(Ordering::SeqCst);
+    ALLOCATOR.peak.store(start, Ordering::SeqCst);
+    f();
+    ALLOCATOR.peak.load(Ordering::SeqCst) - start
+}
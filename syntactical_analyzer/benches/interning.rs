@@ -0,0 +1,41 @@
+//! Compares the peak heap memory of lexing a large function with many
+//! repeated names, when every token owns its lexeme (`q1_lib::lex`), or when
+//! every distinct lexeme is only stored once (`q1_lib::lex_interned`).
+//!
+//! Run with `cargo bench --bench interning`.
+
+mod common;
+
+use q1_lib::{lex, lex_interned};
+
+use common::peak_bytes_of;
+
+/// The number of statements in the generated function.
+const STATEMENTS: usize = 100_000;
+
+/// A function with `STATEMENTS` statements, all over the same few names.
+fn source() -> String {
+    let mut source = "int large(int accumulated_total, float scaling_factor) {\n".to_string();
+    for _ in 0..STATEMENTS {
+        source.push_str("    accumulated_total = accumulated_total + scaling_factor * accumulated_total;\n");
+    }
+    source.push_str("    return accumulated_total;\n}\n");
+    source
+}
+
+fn main() {
+    let source = source();
+
+    let owned = peak_bytes_of(|| {
+        lex(&source).unwrap();
+    });
+
+    let mut distinct = 0;
+    let interned = peak_bytes_of(|| {
+        distinct = lex_interned(&source).unwrap().1.len();
+    });
+
+    println!("peak heap bytes lexing {STATEMENTS} statements ({distinct} distinct lexemes)");
+    println!("    owned    (lex):          {owned:>12}");
+    println!("    interned (lex_interned): {interned:>12}");
+}
@@ -0,0 +1,41 @@
+//! Compares the peak heap memory of parsing a large function, when the input
+//! is lexed eagerly (`ParseBuffer::from_tokens`) or lazily
+//! (`ParseBuffer::from_lexer`).
+//!
+//! Run with `cargo bench --bench lazy_memory`.
+
+mod common;
+
+use q1_lib::{lex, lexer::Lexer};
+use q2_lib::{non_terminals::FunctionDefinition, Parse, ParseBuffer};
+
+use common::peak_bytes_of;
+
+/// The number of statements in the generated function.
+const STATEMENTS: usize = 200_000;
+
+/// The bytes of a function with `STATEMENTS` statements, generated on the fly.
+fn source_bytes() -> impl Iterator<Item = u8> {
+    let statement = "    total = total + value * 2;\n".bytes();
+    "int large(int value) {\n"
+        .bytes()
+        .chain((0..STATEMENTS).flat_map(move |_| statement.clone()))
+        .chain("    return total;\n}\n".bytes())
+}
+
+fn main() {
+    let eager = peak_bytes_of(|| {
+        let source = String::from_utf8(source_bytes().collect()).unwrap();
+        let mut buffer = ParseBuffer::from_tokens(lex(&source).unwrap());
+        FunctionDefinition::parse(&mut buffer).unwrap();
+    });
+
+    let lazy = peak_bytes_of(|| {
+        let mut buffer = ParseBuffer::from_lexer(Lexer::new(source_bytes()));
+        FunctionDefinition::parse(&mut buffer).unwrap();
+    });
+
+    println!("peak heap bytes parsing {STATEMENTS} statements");
+    println!("    eager (from_tokens): {eager:>12}");
+    println!("    lazy  (from_lexer):  {lazy:>12}");
+}
@@ -0,0 +1,47 @@
+//! Compares the time and output size of displaying a large function, when the
+//! signatures shown inline are complete, or cut short with
+//! `DisplayConfig::max_signature_len`.
+//!
+//! Run with `cargo bench --bench signature_len`.
+
+use std::time::Instant;
+
+use q1_lib::lex;
+use q2_lib::{display::DisplayConfig, non_terminals::FunctionDefinition, Parse, ParseBuffer, ParseDisplay};
+
+/// The number of statements in the generated function.
+const STATEMENTS: usize = 2_000;
+
+/// The cut off length of every signature, when cut short.
+const MAX_SIGNATURE_LEN: usize = 60;
+
+/// A function with `STATEMENTS` statements.
+fn source() -> String {
+    let mut source = "int large(int value) {\n".to_string();
+    for _ in 0..STATEMENTS {
+        source.push_str("    total = total + value * 2;\n");
+    }
+    source.push_str("    return total;\n}\n");
+    source
+}
+
+/// Displays the function with `config`, returning the seconds taken and the
+/// bytes of the display.
+fn display_with(function: &FunctionDefinition, config: DisplayConfig) -> (f64, usize) {
+    let start = Instant::now();
+    let display = function.render_with(0, None, config);
+    (start.elapsed().as_secs_f64(), display.len())
+}
+
+fn main() {
+    let mut buffer = ParseBuffer::from_tokens(lex(&source()).unwrap());
+    let function = FunctionDefinition::parse(&mut buffer).unwrap();
+
+    let (full_secs, full_bytes) = display_with(&function, DisplayConfig::default());
+    let config = DisplayConfig { max_signature_len: Some(MAX_SIGNATURE_LEN), ..Default::default() };
+    let (short_secs, short_bytes) = display_with(&function, config);
+
+    println!("displaying {STATEMENTS} statements");
+    println!("    full signatures:          {full_secs:>10.4}s {full_bytes:>12} bytes");
+    println!("    signatures cut to {MAX_SIGNATURE_LEN}:    {short_secs:>10.4}s {short_bytes:>12} bytes");
+}
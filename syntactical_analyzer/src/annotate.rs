@@ -0,0 +1,82 @@
+//! # Annotate
+//!
+//! A side table of user data attached to the nodes of a parse tree, so that a
+//! pass (ex. a type checker) can record something about a node for a later
+//! pass, without the node structs having a field for it.
+//!
+//! A node is identified by its `NodeId`: its type, along with the positions of
+//! the tokens it covers (see `ParseDisplay::span`). A node which covers no
+//! tokens at all (ex. an empty list) records the position it was parsed at
+//! instead, so its id is assigned while parsing, and two empty lists of the
+//! same type are still two nodes. This is stable for as long as the token
+//! stream is: the same tokens parsed again are the same nodes.
+//! 
+//! Within a parsed tree, two nodes only share an id if they are of the same
+//! type and cover the same tokens, which no node of this grammar does. The
+//! exception is a node which covers no tokens and has no position (ex.
+//! `Eof`), which shares its annotation with every other node of its type.
+
+use std::{
+    any::TypeId, // The type of a node, as part of its `NodeId`.
+    collections::HashMap, // The annotation of every `NodeId`.
+};
+
+use crate::ParseDisplay;
+
+/// The identity of a node in a parse tree (see the `annotate` module).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    type_id: TypeId,
+    span: Option<(usize, usize)>,
+}
+impl NodeId {
+    /// The identity of some node.
+    pub fn of<N: ParseDisplay + 'static>(node: &N) -> NodeId {
+        NodeId {
+            type_id: TypeId::of::<N>(),
+            span: node.span(),
+        }
+    }
+}
+
+/// Some data of type `T` attached to any number of nodes, each by its `NodeId`.
+#[derive(Clone, Debug)]
+pub struct Annotations<T> {
+    annotations: HashMap<NodeId, T>,
+}
+impl<T> Default for Annotations<T> {
+    fn default() -> Self {
+        Annotations { annotations: HashMap::new() }
+    }
+}
+impl<T> Annotations<T> {
+    /// Creates a new table, with no node annotated.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The annotation of some node, if it has one.
+    pub fn get<N: ParseDisplay + 'static>(&self, node: &N) -> Option<&T> {
+        self.annotations.get(&NodeId::of(node))
+    }
+
+    /// Annotates some node, returning the annotation it replaced, if any.
+    pub fn set<N: ParseDisplay + 'static>(&mut self, node: &N, annotation: T) -> Option<T> {
+        self.annotations.insert(NodeId::of(node), annotation)
+    }
+
+    /// Removes the annotation of some node, returning it, if any.
+    pub fn remove<N: ParseDisplay + 'static>(&mut self, node: &N) -> Option<T> {
+        self.annotations.remove(&NodeId::of(node))
+    }
+
+    /// The number of annotated nodes.
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// If no node is annotated.
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+}
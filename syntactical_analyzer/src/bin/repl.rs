@@ -0,0 +1,67 @@
+//! An interactive parser: each line of stdin is parsed as an `Expression` (or
+//! the rule chosen with `--root <rule>`), printing its tree or its error,
+//! until the end of the input (ex. Ctrl-D).
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    process,
+};
+
+use q2_lib::{parse_rule_str, short_error, RULE_NAMES};
+
+/// Reads the rule every line is parsed as, from `--root <rule>`.
+fn root(mut args: impl Iterator<Item = String>) -> Result<String, String> {
+    let mut root = "Expression".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => {
+                root = args.next().ok_or("Expected a rule name after `--root`".to_string())?;
+                if !RULE_NAMES.contains(&root.as_str()) {
+                    Err(format!("Unknown rule `{root}`, expected one of: {}", RULE_NAMES.join(", ")))?
+                }
+            },
+            _ => Err(format!("Unknown option `{arg}`"))?,
+        }
+    }
+    Ok(root)
+}
+
+fn main() {
+    let root = match root(env::args().skip(1)) {
+        Ok(root) => root,
+        Err(err) => {
+            eprintln!("ERROR - {err}");
+            process::exit(1);
+        },
+    };
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("{root}> ");
+        io::stdout().flush().unwrap();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(err)) => {
+                eprintln!("ERROR - failed to read stdin: {err}");
+                process::exit(1);
+            },
+            // the end of the input (ex. Ctrl-D) ends the prompt's line too
+            None => {
+                println!();
+                return;
+            },
+        };
+
+        // an empty line is no input, rather than nothing to parse
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_rule_str(&root, &line) {
+            Ok(parsed) => print!("{}", parsed.render(0, None)),
+            Err(err) => println!("PARSE ERROR: {}", short_error(&err)),
+        }
+    }
+}
@@ -0,0 +1,999 @@
+//! # Parse Buffer
+//! 
+//! This module holds the `ParseBuffer`: the cheaply-forkable token stream that
+//! every `Parse` implementation consumes.
+
+use std::{
+    cell::{Cell, RefCell}, // Interior mutability of the shared lazy tokens and nesting error
+    collections::{BTreeMap, HashMap, HashSet, VecDeque}, // The live buffers and window of `LazyTokens`, the declared types of a `SymbolTable`, and the reserved words
+    rc::Rc, // Shared ownership of the tokens (and their lexemes) between forks and terminals
+};
+
+use q1_lib::lexer::{Location, LocatedToken, Symbol, Token};
+
+use crate::{
+    events::ParseEvent,
+    terminals::{Eof, Identifier, Type, TypeKind, RESERVED_WORDS},
+    input_tokens,
+    Parse,
+};
+
+/// The backtracking counters of a `ParseBuffer` and all of its forks.
+/// 
+/// Only available with the `profile` feature.
+#[cfg(feature = "profile")]
+#[derive(Debug, Default)]
+struct ProfileCounts {
+    /// The number of times `fork` was called.
+    forks: std::cell::Cell<usize>,
+    /// The number of forks dropped without being committed.
+    discards: std::cell::Cell<usize>,
+    /// The position of the furthest token any discarded fork read (peeked at
+    /// or consumed), if any fork read one.
+    deepest_discard: std::cell::Cell<Option<usize>>,
+}
+
+/// A token of a `ParseBuffer`, with its lexeme, which is shared with every
+/// terminal parsed from it (rather than copied into each).
+/// 
+/// The lexeme is an `Rc<String>` (rather than an `Rc<str>`), so that it is a
+/// thin pointer: every node holds its terminals, and is on the stack of the
+/// rule parsing it.
+pub type SharedToken = (Token, Rc<String>);
+
+/// Where the tokens of a `ParseBuffer` come from.
+#[derive(Clone)]
+enum TokenSource {
+    /// An already lexed token stream.
+    Lexed(Rc<[SharedToken]>),
+
+    /// A token stream which is lexed as the tokens are asked for, shared
+    /// between a buffer and all of its forks.
+    Lazy(Rc<RefCell<LazyTokens>>),
+}
+impl TokenSource {
+    /// The token at some position of the stream, if there is one.
+    fn get(&self, position: usize) -> Option<SharedToken> {
+        match self {
+            TokenSource::Lexed(tokens) => tokens.get(position).cloned(),
+            TokenSource::Lazy(lazy_tokens) => lazy_tokens.borrow_mut().get(position).map(|token| (*token).clone()),
+        }
+    }
+}
+
+/// How many tokens behind itself a buffer can still look at, see `ParseBuffer::explain_found`.
+const LOOKBEHIND: usize = 3;
+
+/// The tokens lexed so far from some lazy lexer, which a live buffer can still reach.
+struct LazyTokens {
+    /// The lexer yet to be finished.
+    lexer: Box<dyn Iterator<Item = Result<(Token, String), String>>>,
+    /// The tokens lexed so far, from `offset` on.
+    /// 
+    /// A token is dropped once no buffer can reach it anymore: only the
+    /// lexeme of a token consumed into a terminal lives on, in the terminal.
+    window: VecDeque<Rc<SharedToken>>,
+    /// The position of the first token of `window`.
+    offset: usize,
+    /// The position of every live buffer over these tokens, with how many buffers are at it.
+    live: BTreeMap<usize, usize>,
+    /// The lexical error that ended the lexer early, and its position, if any.
+    error: Option<(usize, String)>,
+}
+impl LazyTokens {
+    /// Lexes up to some position of the stream, if needed, returning if there is a token at it.
+    fn lex_to(&mut self, position: usize) -> bool {
+        while self.offset + self.window.len() <= position {
+            if self.error.is_some() {
+                return false;
+            }
+            match self.lexer.next() {
+                Some(Ok((token, lexeme))) => self.window.push_back(Rc::new((token, Rc::new(lexeme)))),
+                Some(Err(err)) => self.error = Some((self.offset + self.window.len(), err)),
+                None => return false,
+            }
+        }
+        position >= self.offset
+    }
+
+    /// The token at some position of the stream, lexing up to it if needed.
+    fn get(&mut self, position: usize) -> Option<Rc<SharedToken>> {
+        match self.lex_to(position) {
+            true => Some(self.window[position - self.offset].clone()),
+            false => None,
+        }
+    }
+
+    /// Moves a live buffer from one position to another (`None` being a
+    /// buffer created or dropped), then drops every token no live buffer can
+    /// reach anymore.
+    fn move_live(&mut self, from: Option<usize>, to: Option<usize>) {
+        if let Some(to) = to {
+            *self.live.entry(to).or_default() += 1;
+        }
+        if let Some(from) = from {
+            match self.live.get_mut(&from) {
+                Some(count) if *count > 1 => *count -= 1,
+                _ => _ = self.live.remove(&from),
+            }
+        }
+
+        let Some(&first_live) = self.live.keys().next() else {
+            return;
+        };
+        let reachable = first_live.saturating_sub(LOOKBEHIND);
+        while self.offset < reachable && self.window.pop_front().is_some() {
+            self.offset += 1;
+        }
+    }
+}
+
+/// A persistent stack: pushing onto (or popping off) a copy of a stack never
+/// changes the original, so copying one is as cheap as copying an `Rc`.
+struct Stack<T>(Option<Rc<(T, Stack<T>)>>);
+impl<T> Stack<T> {
+    /// This stack, with an item pushed on top.
+    fn push(&self, item: T) -> Self {
+        Stack(Some(Rc::new((item, self.clone()))))
+    }
+
+    /// This stack, without its top item.
+    fn pop(&self) -> Self {
+        self.0.as_ref().map_or(Stack(None), |node| node.1.clone())
+    }
+
+    /// Every item of this stack, from the top down.
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        std::iter::successors(self.0.as_deref(), |(_, below)| below.0.as_deref()).map(|(item, _)| item)
+    }
+}
+impl<T> Clone for Stack<T> {
+    fn clone(&self) -> Self {
+        Stack(self.0.clone())
+    }
+}
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Stack(None)
+    }
+}
+impl<T> Drop for Stack<T> {
+    // A stack is dropped item by item (rather than recursively), so a tall stack cannot overflow the stack.
+    fn drop(&mut self) {
+        let mut next = self.0.take();
+        while let Some(node) = next {
+            next = match Rc::try_unwrap(node) {
+                Ok((_, mut below)) => below.0.take(),
+                Err(_) => None, // the rest is still shared with another stack
+            };
+        }
+    }
+}
+
+/// An entry of the scopes of a `SymbolTable`.
+enum ScopeEntry {
+    /// The start of a scope (ex. a function), which every later declaration is in.
+    Start,
+    /// The declared type of an identifier.
+    Declaration(Rc<String>, TypeKind),
+}
+
+/// The symbols declared so far while parsing, and the diagnostics found
+/// with them, see `ParseBuffer::with_symbols`.
+/// 
+/// Both are persistent stacks, so a fork has its own table (discarded along
+/// with the fork) without ever copying it.
+#[derive(Clone)]
+struct SymbolTable {
+    /// The declared type of every identifier declared ahead of the parse, by the caller.
+    declared: Rc<HashMap<String, TypeKind>>,
+    /// Every scope entered, and every declaration in them, the latest on top.
+    scopes: Stack<ScopeEntry>,
+    /// Every semantic problem found so far, the latest on top.
+    diagnostics: Stack<String>,
+}
+
+/// The parse events a buffer emitted since it was forked, see `ParseBuffer::events`.
+#[derive(Default)]
+struct ForkEvents {
+    events: Vec<ParseEvent>,
+    /// The node the fork is the whole of, if any (see `ParseBuffer::fork_node`),
+    /// which is ended once the fork is committed.
+    node: Option<&'static str>,
+}
+
+/// The default `ParseBuffer::max_depth`.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// The default `ParseBuffer::max_error_levels`.
+pub const DEFAULT_MAX_ERROR_LEVELS: usize = 10;
+
+/// The settings of a `ParseBuffer`, each set by its builder method.
+#[derive(Clone)]
+struct Settings {
+    /// The most recursive rules that can be parsed at once.
+    max_depth: usize,
+    /// The most `While parsing <rule>...` levels an error keeps.
+    max_error_levels: usize,
+    /// The words of a custom keyword table, which cannot be identifiers
+    /// (see `with_keywords`), or `None` for the built-in `RESERVED_WORDS`.
+    reserved_words: Option<HashSet<String>>,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_error_levels: DEFAULT_MAX_ERROR_LEVELS,
+            reserved_words: None,
+        }
+    }
+}
+
+/// A cheaply-forkable iterator over a given token stream.
+pub struct ParseBuffer {
+    /// Some known list of tokens and strings.
+    tokens: TokenSource,
+    /// The position of the next token in the list.
+    position: usize,
+    /// The location of every token in the source text, if it is known (see `describe_position`).
+    locations: Option<Rc<[Location]>>,
+
+    /// How many recursive rules are currently being parsed.
+    depth: usize,
+    /// If `max_depth` was exceeded, shared between this buffer and all of its forks.
+    /// 
+    /// Until this is reset (see `take_nesting_error`), no more tokens can be
+    /// read, so that the whole parse fails with it.
+    too_deep: Rc<Cell<bool>>,
+
+    /// The settings of the buffer, shared (rather than copied) between this
+    /// buffer and all of its forks, as a buffer is on the stack of every rule
+    /// being parsed.
+    settings: Rc<Settings>,
+
+    /// The last token peeked at from a lazy token stream (before it was consumed), which `peek` lends out.
+    peeked: Option<Rc<SharedToken>>,
+
+    /// The symbol table, only while parsing with one (see `with_symbols`).
+    /// 
+    /// Unlike the other shared state, each fork has its own (persistent)
+    /// table, so that a declaration is discarded along with a discarded fork.
+    symbols: Option<SymbolTable>,
+
+    /// The parse events emitted since this buffer was forked, only while
+    /// emitting them (see `events::parse_events`).
+    /// 
+    /// Committing a fork appends its events to this buffer's, and the events
+    /// of a discarded fork are discarded along with it. This is boxed, as a
+    /// buffer is on the stack of every rule being parsed.
+    events: Option<Box<ForkEvents>>,
+
+    /// The counters shared between this buffer and all of its forks.
+    #[cfg(feature = "profile")]
+    profile: Rc<ProfileCounts>,
+    /// If this is a fork which has not (yet) been committed.
+    #[cfg(feature = "profile")]
+    loose: bool,
+    /// The number of tokens up to the furthest one this buffer read (peeked
+    /// at or consumed), or that a fork committed to it read: `0` if it read
+    /// none. This is not an `Option`, to keep the buffer small on the stack.
+    #[cfg(feature = "profile")]
+    reached: usize,
+}
+impl ParseBuffer {
+    /// Create a new `ParseBuffer` over a token stream.
+    /// 
+    /// This will be the token stream of the input file, the first CLI
+    /// argument, with every keyword case-sensitive (see `from_input`).
+    pub fn new() -> Self {
+        Self::from_input(false)
+    }
+
+    /// Create a new `ParseBuffer` over the token stream of the input file,
+    /// along with the location of each token, which is lexed again on every call.
+    /// 
+    /// Keywords are recognized in any case with `ci_keywords` (see
+    /// `Lexer::with_ci_keywords` in `Q1`). See `input_tokens` for more details.
+    pub fn from_input(ci_keywords: bool) -> Self {
+        Self::from_located_tokens(input_tokens(ci_keywords))
+    }
+
+    /// Create a new `ParseBuffer` over a caller-provided token stream.
+    /// 
+    /// The token stream is owned by this buffer and its forks, and dropped
+    /// along with the last of them: each terminal parsed from it only shares
+    /// its lexeme (see `SharedToken`).
+    pub fn from_tokens(tokens: Vec<(Token, String)>) -> Self {
+        let tokens = tokens.into_iter().map(|(token, lexeme)| (token, Rc::new(lexeme))).collect();
+        Self::over(TokenSource::Lexed(tokens))
+    }
+
+    /// Create a new `ParseBuffer` over a caller-provided token stream, along
+    /// with the location of each token (see `q1_lib::lex_with_locations`).
+    /// 
+    /// Unlike `from_tokens`, an error can then say where a token is in the
+    /// source text (ex. "opened at 2:5"), rather than only its position.
+    pub fn from_located_tokens(tokens: Vec<LocatedToken>) -> Self {
+        let (tokens, locations): (Vec<_>, Vec<_>) = tokens.into_iter()
+            .map(|(token, lexeme, location)| ((token, lexeme), location))
+            .unzip();
+        Self::from_tokens(tokens).with_locations(locations.into())
+    }
+
+    /// Create a new `ParseBuffer` over a lazy lexer (see `q1_lib::lexer::Lexer`),
+    /// which is only advanced as far as the parser asks for.
+    /// 
+    /// Unlike `new` and `from_tokens`, the input never has to be fully lexed
+    /// in memory before parsing, and only the tokens which this buffer (or
+    /// one of its forks) can still reach are kept. Only the lexemes of the
+    /// tokens consumed into a terminal are kept after that, by the parse tree.
+    /// 
+    /// A lexical error ends the token stream early: parsing at the error
+    /// (ex. `expect_eof`) fails with the lexical error, see `lexical_error`.
+    pub fn from_lexer(lexer: impl Iterator<Item = Result<(Token, String), String>> + 'static) -> Self {
+        let lazy_tokens = LazyTokens {
+            lexer: Box::new(lexer),
+            window: VecDeque::new(),
+            offset: 0,
+            live: BTreeMap::new(),
+            error: None,
+        };
+        Self::over(TokenSource::Lazy(Rc::new(RefCell::new(lazy_tokens))))
+    }
+
+    /// Create a new `ParseBuffer` at the start of some token stream.
+    fn over(tokens: TokenSource) -> Self {
+        let buffer = ParseBuffer {
+            tokens,
+            position: 0,
+            locations: None,
+            depth: 0,
+            too_deep: Default::default(),
+            settings: Default::default(),
+            peeked: None,
+            symbols: None,
+            events: None,
+            #[cfg(feature = "profile")]
+            profile: Default::default(),
+            #[cfg(feature = "profile")]
+            loose: false,
+            #[cfg(feature = "profile")]
+            reached: 0,
+        };
+        buffer.track(None, Some(buffer.position));
+        buffer
+    }
+
+    /// Sets the location of every token of the token stream.
+    fn with_locations(mut self, locations: Rc<[Location]>) -> Self {
+        self.locations = Some(locations);
+        self
+    }
+
+    /// Where the token at some position is, to report in an error: its line
+    /// and column (ex. `2:5`) if the locations of the tokens are known, or
+    /// otherwise only its position (ex. `token 7`).
+    pub fn describe_position(&self, position: usize) -> String {
+        match self.locations.as_ref().and_then(|locations| locations.get(position)) {
+            Some((line, column)) => format!("{line}:{column}"),
+            None => format!("token {position}"),
+        }
+    }
+
+    /// The next token of this buffer, as an error found it: its lexeme along
+    /// with where it is (ex. `` `y` at 2:5 ``, see `describe_position`), or
+    /// `nothing` at the end of the buffer.
+    pub(crate) fn describe_found(&mut self) -> String {
+        let position = self.describe_position(self.position);
+        match self.peek() {
+            Some((_, lexeme)) => format!("`{lexeme}` at {position}"),
+            None => "nothing".into(),
+        }
+    }
+
+    /// Moves this buffer among the live buffers of a lazy token stream (see
+    /// `LazyTokens::move_live`), so that the tokens it can reach are kept.
+    fn track(&self, from: Option<usize>, to: Option<usize>) {
+        if let TokenSource::Lazy(lazy_tokens) = &self.tokens {
+            lazy_tokens.borrow_mut().move_live(from, to);
+        }
+    }
+
+    /// Set the most recursive rules (ex. expressions nested in expressions)
+    /// that can be parsed at once, before failing. The default is `DEFAULT_MAX_DEPTH`.
+    /// 
+    /// This guards against overflowing the stack on pathologically nested input.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        Rc::make_mut(&mut self.settings).max_depth = max_depth;
+        self
+    }
+
+    /// The most recursive rules that can be parsed at once.
+    pub fn max_depth(&self) -> usize {
+        self.settings.max_depth
+    }
+
+    /// Reserve the words of the custom keyword table the tokens were lexed
+    /// with (see `q1_lib::lex_with_keywords`), rather than `RESERVED_WORDS`.
+    /// 
+    /// A built-in keyword missing from the table (ex. `return`, if only `fn` is
+    /// the return keyword) is then an identifier like any other word.
+    pub fn with_keywords(mut self, keywords: &HashMap<String, Token>) -> Self {
+        Rc::make_mut(&mut self.settings).reserved_words = Some(keywords.keys().cloned().collect());
+        self
+    }
+
+    /// If a word is reserved, and so can never be an identifier, following `with_keywords`.
+    pub fn is_reserved_word(&self, word: &str) -> bool {
+        match self.settings.reserved_words {
+            Some(ref reserved_words) => reserved_words.contains(word),
+            None => RESERVED_WORDS.contains(&word),
+        }
+    }
+
+    /// Parses some recursive rule one level deeper, failing instead if that
+    /// would exceed `max_depth`.
+    /// 
+    /// Every recursive rule wraps its parse in this. Exceeding `max_depth` is
+    /// not backtracked: every rule it is nested in fails with the same error,
+    /// whatever the parse of the rule returned.
+    pub(crate) fn nested<T>(&mut self, parse: impl FnOnce(&mut ParseBuffer) -> Result<T, String>) -> Result<T, String> {
+        if self.depth >= self.settings.max_depth {
+            self.too_deep.set(true);
+        }
+        self.check_nesting()?;
+
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        self.check_nesting()?;
+        parsed
+    }
+
+    /// Fails with the error for exceeding `max_depth`, if it was exceeded.
+    /// 
+    /// A rule which succeeds by stopping at a failed parse (ex. a list ending
+    /// at the first item which fails) checks this first, so that it never
+    /// succeeds with only what was parsed before something nested too deeply.
+    pub(crate) fn check_nesting(&self) -> Result<(), String> {
+        match self.nesting_error() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// The error for exceeding `max_depth`, if it was exceeded by this
+    /// buffer or any of its forks (since it was last taken, see `take_nesting_error`).
+    /// 
+    /// Since a failed parse attempt is usually backtracked (and its error
+    /// replaced), this is how the error is reported cleanly.
+    pub fn nesting_error(&self) -> Option<String> {
+        match self.too_deep.get() {
+            true => Some(format!("Expression or statement nested too deeply (the limit is {} levels)", self.settings.max_depth)),
+            false => None,
+        }
+    }
+
+    /// Takes the error for exceeding `max_depth` (see `nesting_error`),
+    /// resetting it, so that the buffer can be parsed from again.
+    pub fn take_nesting_error(&mut self) -> Option<String> {
+        let err = self.nesting_error();
+        self.too_deep.set(false);
+        err
+    }
+
+    /// Set the most `While parsing <rule>...` levels (see `context_error`)
+    /// a parse error keeps, before the levels in between are truncated.
+    /// The default is `DEFAULT_MAX_ERROR_LEVELS`.
+    /// 
+    /// The levels are truncated from the inside: the outermost level is always
+    /// kept, as is the innermost one, unless only a single level is kept.
+    pub fn with_max_error_levels(mut self, max_error_levels: usize) -> Self {
+        Rc::make_mut(&mut self.settings).max_error_levels = max_error_levels;
+        self
+    }
+
+    /// The most `While parsing <rule>...` levels a parse error keeps.
+    pub fn max_error_levels(&self) -> usize {
+        self.settings.max_error_levels
+    }
+
+    /// Prefixes an error with the rule it happened while parsing
+    /// (`While parsing <rule>...`).
+    /// 
+    /// If the error already starts with the same rule (ex. a list nested in a
+    /// list of the same type), the two are collapsed into a single
+    /// `While parsing <rule> (nested N deep)...`.
+    /// 
+    /// If the error then has more than `max_error_levels` of these levels,
+    /// the innermost ones past the limit (except the very innermost one, if
+    /// there is room for it) are replaced with a single `... (N more levels)`.
+    pub(crate) fn context_error(&self, rule: String, err: String) -> String {
+        let (first, rest) = err.split_once('\n').unwrap_or((&err, ""));
+        let nested = match first.strip_prefix(&format!("While parsing {rule}")) {
+            Some("...") => Some(2),
+            Some(deep) => deep.strip_prefix(" (nested ")
+                .and_then(|deep| deep.strip_suffix(" deep)..."))
+                .and_then(|deep| deep.parse::<usize>().ok())
+                .map(|deep| deep + 1),
+            None => None,
+        };
+        let err = match nested {
+            Some(deep) => format!("While parsing {rule} (nested {deep} deep)...\n{rest}"),
+            None => format!("While parsing {rule}...\n    {err}"),
+        };
+        let lines = err.lines()
+            .map(|line| line.strip_prefix("    ").unwrap_or(line))
+            .collect::<Vec<_>>();
+        let is_context = |line: &str| line.starts_with("While parsing ");
+        let truncated_count = |line: &str| {
+            line.strip_prefix("... (")
+                .and_then(|rest| rest.strip_suffix(" more levels)"))
+                .and_then(|count| count.parse::<usize>().ok())
+        };
+
+        let levels = lines.iter().filter(|line| is_context(line)).count();
+        if levels <= self.settings.max_error_levels {
+            return err;
+        }
+
+        // keep the outer levels, and the innermost one if there is room for more than one level
+        let innermost = lines.iter().rposition(|line| is_context(line)).unwrap();
+        let reserved = usize::from(self.settings.max_error_levels > 1); // the levels kept for the innermost one
+        let mut kept = vec![];
+        let mut kept_levels = 0;
+        let mut truncated = 0;
+        for (i, line) in lines.into_iter().enumerate() {
+            if let Some(count) = truncated_count(line) {
+                truncated += count;
+                continue;
+            }
+            if is_context(line) && (i != innermost || reserved == 0) {
+                if kept_levels + reserved >= self.settings.max_error_levels {
+                    truncated += 1;
+                    continue;
+                }
+                kept_levels += 1;
+            }
+            if truncated > 0 {
+                kept.push(format!("... ({truncated} more levels)"));
+                truncated = 0;
+            }
+            kept.push(line.to_string());
+        }
+        kept.join("\n    ")
+    }
+
+    /// Parses something within a budget of `max_tokens` tokens, failing if
+    /// the parse consumed any token past the budget.
+    /// 
+    /// The budget only counts the tokens the parse consumes, not those it
+    /// *looks* at (ex. the `;` peeked after the expression `a + b`), or those
+    /// consumed by a parse attempt which was then abandoned.
+    pub(crate) fn limited<T>(&mut self, max_tokens: usize, parse: impl FnOnce(&mut ParseBuffer) -> Result<T, String>) -> Result<T, String> {
+        let mut fork = self.fork(); // this is to make parse attempts without modifying the original buffer
+        let parsed = parse(&mut fork)?;
+        if fork.position > self.position + max_tokens {
+            Err(format!("Token budget exceeded (the limit is {max_tokens} tokens)"))?
+        }
+        self.commit(fork); // parse was successful: committing the fork to the buffer
+        Ok(parsed)
+    }
+
+    /// Parses something while consulting and updating a symbol table: the
+    /// declared type of every identifier.
+    /// 
+    /// Every `FunctionParameter` declares its identifier, and every assignment
+    /// (an `AssignmentStatement` or an `AssignmentExpression`) to an identifier
+    /// which is not declared in any enclosing scope is recorded as a
+    /// diagnostic, which does not fail the parse.
+    /// 
+    /// A function and a block are each a scope of their own, whose
+    /// declarations (ex. the parameters of a function) are only visible
+    /// within it. `symbols` is the outermost scope: the declarations of the
+    /// parse outside of any other scope are added to it, and the diagnostics
+    /// are returned alongside the parsed item.
+    pub(crate) fn with_symbols<T>(&mut self, symbols: &mut HashMap<String, TypeKind>, parse: impl FnOnce(&mut ParseBuffer) -> Result<T, String>) -> Result<(T, Vec<String>), String> {
+        let outer_symbols = self.symbols.replace(SymbolTable {
+            declared: Rc::new(std::mem::take(symbols)),
+            scopes: Stack::default(),
+            diagnostics: Stack::default(),
+        });
+        let parsed = parse(self);
+        let SymbolTable { declared, scopes, diagnostics } = std::mem::replace(&mut self.symbols, outer_symbols).unwrap();
+
+        *symbols = Rc::unwrap_or_clone(declared);
+        let mut declarations = scopes.iter().collect::<Vec<_>>();
+        declarations.reverse(); // in order, so a later declaration replaces an earlier one
+        for entry in declarations {
+            if let ScopeEntry::Declaration(identifier, kind) = entry {
+                symbols.insert(identifier.to_string(), *kind);
+            }
+        }
+        let mut diagnostics = diagnostics.iter().cloned().collect::<Vec<_>>();
+        diagnostics.reverse();
+        parsed.map(|parsed| (parsed, diagnostics))
+    }
+
+    /// Enters a new scope, if parsing with a symbol table.
+    pub(crate) fn enter_scope(&mut self) {
+        if let Some(ref mut table) = self.symbols {
+            table.scopes = table.scopes.push(ScopeEntry::Start);
+        }
+    }
+
+    /// Exits the innermost scope, discarding its declarations, if parsing with a symbol table.
+    pub(crate) fn exit_scope(&mut self) {
+        if let Some(ref mut table) = self.symbols {
+            let mut scopes = table.scopes.clone();
+            while matches!(scopes.iter().next(), Some(ScopeEntry::Declaration(..))) {
+                scopes = scopes.pop();
+            }
+            table.scopes = scopes.pop(); // the start of the scope itself
+        }
+    }
+
+    /// Declares an identifier of some type in the innermost scope, if parsing with a symbol table.
+    pub(crate) fn declare(&mut self, identifier: &Identifier, type_: &Type) {
+        if let Some(ref mut table) = self.symbols {
+            table.scopes = table.scopes.push(ScopeEntry::Declaration(identifier.lexeme.clone(), type_.kind()));
+        }
+    }
+
+    /// Records a diagnostic if an identifier assigned to is not declared in
+    /// any enclosing scope, if parsing with a symbol table.
+    pub(crate) fn check_assigned(&mut self, identifier: &Identifier) {
+        let at = self.describe_position(identifier.position);
+        if let Some(ref mut table) = self.symbols {
+            let declared = table.declared.contains_key(&*identifier.lexeme)
+                || table.scopes.iter().any(|entry| matches!(entry, ScopeEntry::Declaration(declared, _) if *declared == identifier.lexeme));
+            if !declared {
+                let diagnostic = format!("Assignment to the undeclared variable `{}` (at {at})", identifier.lexeme);
+                table.diagnostics = table.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    /// If the next token can be read, which no token can once `max_depth` was exceeded.
+    fn can_read(&self) -> bool {
+        !self.too_deep.get()
+    }
+
+    /// The position (index) of the next token in the token stream.
+    /// 
+    /// Every terminal records the position it was parsed at.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// See if there is a "next" item, without actually consuming.
+    pub fn peek(&mut self) -> Option<&SharedToken> {
+        if !self.can_read() {
+            return None;
+        }
+        self.note_read();
+        match &self.tokens {
+            TokenSource::Lexed(tokens) => tokens.get(self.position),
+            TokenSource::Lazy(lazy_tokens) => {
+                self.peeked = Some(lazy_tokens.borrow_mut().get(self.position)?);
+                self.peeked.as_deref()
+            },
+        }
+    }
+
+    /// The lexical error which ended a lazy token stream early, if any.
+    /// 
+    /// This is only known once the parser has reached the error.
+    pub fn lexical_error(&self) -> Option<String> {
+        match &self.tokens {
+            TokenSource::Lexed(_) => None,
+            TokenSource::Lazy(lazy_tokens) => lazy_tokens.borrow().error.as_ref().map(|(_position, err)| err.clone()),
+        }
+    }
+
+    /// The error which ended the tokens of this buffer early, if any: the
+    /// error to fail with, instead of finding nothing.
+    /// 
+    /// This is either exceeding `max_depth`, or the lexical error which ended a
+    /// lazy token stream, if this buffer is right at it.
+    pub(crate) fn early_end_error(&self) -> Option<String> {
+        if let Some(err) = self.nesting_error() {
+            return Some(err);
+        }
+        match &self.tokens {
+            TokenSource::Lexed(_) => None,
+            TokenSource::Lazy(lazy_tokens) => lazy_tokens.borrow().error.as_ref()
+                .filter(|(position, _err)| *position == self.position)
+                .map(|(_position, err)| err.clone()),
+        }
+    }
+
+    /// Cheaply clone the buffer iterator at the buffer's current state.
+    pub fn fork(&self) -> Self {
+        #[cfg(feature = "profile")]
+        self.profile.forks.set(self.profile.forks.get() + 1);
+
+        let fork = ParseBuffer {
+            tokens: self.tokens.clone(),
+            position: self.position,
+            locations: self.locations.clone(),
+            depth: self.depth,
+            too_deep: self.too_deep.clone(),
+            settings: self.settings.clone(),
+            peeked: None,
+            symbols: self.symbols.clone(),
+            events: self.events.as_ref().map(|_| Box::default()),
+            #[cfg(feature = "profile")]
+            profile: self.profile.clone(),
+            #[cfg(feature = "profile")]
+            loose: true,
+            #[cfg(feature = "profile")]
+            reached: 0,
+        };
+        fork.track(None, Some(fork.position));
+        fork
+    }
+
+    /// Replace this buffer with a (successfully parsed) fork of it.
+    /// 
+    /// This is the same as `*buffer = fork`, except that the fork is not counted
+    /// as discarded with the `profile` feature, and that the parse events of
+    /// the fork (if any) are appended to this buffer's.
+    pub fn commit(&mut self, mut fork: ParseBuffer) {
+        #[cfg(feature = "profile")]
+        {
+            fork.loose = self.loose; // the fork takes over this buffer's place
+            self.loose = false; // the replaced buffer is not discarded
+            fork.reached = fork.reached.max(self.reached); // nor is what it read before the fork
+        }
+
+        // the fork's events (and the end of its node) follow this buffer's own
+        let mut events = self.events.take();
+        if let (Some(events), Some(fork_events)) = (&mut events, fork.events.take()) {
+            events.events.extend(fork_events.events);
+            events.events.extend(fork_events.node.map(ParseEvent::EndNode));
+        }
+        fork.events = events;
+
+        *self = fork;
+    }
+
+    /// Forks this buffer (see `fork`) to parse a whole node, which starts in
+    /// the fork's parse events, and ends once the fork is committed.
+    pub(crate) fn fork_node(&self, kind: &'static str) -> Self {
+        let mut fork = self.fork();
+        if let Some(events) = &mut fork.events {
+            events.node = Some(kind);
+        }
+        fork.emit(ParseEvent::StartNode(kind));
+        fork
+    }
+
+    /// Parses a whole node with some parser (ex. a list, which is not its own
+    /// node wherever it is parsed, like `FunctionParameters`), in a node fork
+    /// (see `fork_node`) which is only committed if it parses.
+    pub(crate) fn parse_node<T>(&mut self, kind: &'static str, parse: impl FnOnce(&mut ParseBuffer) -> Result<T, String>) -> Result<T, String> {
+        let mut fork = self.fork_node(kind);
+        let node = parse(&mut fork)?;
+        self.commit(fork);
+        Ok(node)
+    }
+
+    /// Emits a parse event, only while emitting them.
+    pub(crate) fn emit(&mut self, event: ParseEvent) {
+        if let Some(events) = &mut self.events {
+            events.events.push(event);
+        }
+    }
+
+    /// Where the next parse event of this buffer will be, to wrap every event
+    /// from there in a node once it is known (see `wrap_node`).
+    pub(crate) fn node_mark(&self) -> usize {
+        self.events.as_ref().map_or(0, |events| events.events.len())
+    }
+
+    /// Wraps every parse event since a `node_mark` in a node (ex. an already
+    /// parsed condition, once it is known to be the start of a conditional).
+    pub(crate) fn wrap_node(&mut self, mark: usize, kind: &'static str) {
+        if let Some(events) = &mut self.events {
+            events.events.insert(mark, ParseEvent::StartNode(kind));
+            events.events.push(ParseEvent::EndNode(kind));
+        }
+    }
+
+    /// Starts emitting parse events (see `events::parse_events`), which are
+    /// kept in this buffer until taken.
+    pub(crate) fn start_events(&mut self) {
+        self.events = Some(Box::default());
+    }
+
+    /// Stops emitting parse events, taking every one that was committed to this buffer.
+    pub(crate) fn take_events(&mut self) -> Vec<ParseEvent> {
+        self.events.take().map(|events| events.events).unwrap_or_default()
+    }
+
+    /// The number of times this buffer, or any buffer it was forked from or
+    /// into, was forked.
+    #[cfg(feature = "profile")]
+    pub fn fork_count(&self) -> usize {
+        self.profile.forks.get()
+    }
+
+    /// The number of forks that were discarded (dropped without a `commit`),
+    /// out of `fork_count`.
+    /// 
+    /// Every discarded fork is a parse failure which was backtracked.
+    #[cfg(feature = "profile")]
+    pub fn discard_count(&self) -> usize {
+        self.profile.discards.get()
+    }
+
+    /// The position of the furthest token that any discarded fork read before
+    /// it was backtracked (ex. the token a terminal was rejected at), or `None`
+    /// if no discarded fork read any token.
+    /// 
+    /// This is where the parser speculated the furthest before giving up on
+    /// an alternative: where the grammar was the most ambiguous for the input.
+    #[cfg(feature = "profile")]
+    pub fn deepest_reach(&self) -> Option<usize> {
+        self.profile.deepest_discard.get()
+    }
+
+    /// `deepest_reach`, described along with the lexeme at it, ex.
+    /// "deepest speculative reach: token 3 ('+')".
+    /// 
+    /// The tokens are counted from 1 here (as in `highlight`), where
+    /// `deepest_reach` is a position from 0.
+    #[cfg(feature = "profile")]
+    pub fn describe_deepest_reach(&self) -> Option<String> {
+        let position = self.deepest_reach()?;
+        let lexeme = match self.tokens.get(position) {
+            Some((_token, lexeme)) => format!("'{lexeme}'"),
+            None => "the end of input".to_string(),
+        };
+        Some(format!("deepest speculative reach: token {} ({lexeme})", position + 1))
+    }
+
+    /// Notes that the token at this buffer's position was read, with the
+    /// `profile` feature (see `deepest_reach`).
+    fn note_read(&mut self) {
+        #[cfg(feature = "profile")]
+        {
+            self.reached = self.reached.max(self.position + 1);
+        }
+    }
+
+    /// Skips every token up to (but not including) the `}` closing some `{`
+    /// already parsed, without parsing any of them: only the curlies nested
+    /// between the two are counted, to find the one closing it.
+    /// 
+    /// If the `{` is never closed, this skips to the end of the input.
+    pub(crate) fn skip_to_closing_curly(&mut self) {
+        let mut depth = 0; // how many nested `{` are still open
+        while let Some((token, _lexeme)) = self.peek() {
+            match token {
+                Token::Symbol(Symbol::LeftCurly) => depth += 1,
+                Token::Symbol(Symbol::RightCurly) if depth == 0 => return,
+                Token::Symbol(Symbol::RightCurly) => depth -= 1,
+                _ => (),
+            }
+            self.next();
+        }
+    }
+
+    /// Skips the next token, along with everything up to (and including) the
+    /// bracket closing it, if it is an opening bracket (`(` or `{`): the
+    /// brackets nested between the two are counted, to find the one closing
+    /// it, so a bracketed group is skipped as a whole.
+    /// 
+    /// If the bracket is never closed, this skips to the end of the input.
+    pub(crate) fn skip_bracketed(&mut self) {
+        let mut depth = 0; // how many brackets are still open
+        for (token, _lexeme) in self.by_ref() {
+            match token {
+                Token::Symbol(Symbol::LeftParen | Symbol::LeftCurly) => depth += 1,
+                Token::Symbol(Symbol::RightParen | Symbol::RightCurly) if depth > 0 => depth -= 1,
+                _ => (),
+            }
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    /// Explains a parse error found at the next token, if the next token is
+    /// unexpected for a reason the grammar cannot tell on its own.
+    /// 
+    /// - A period is only ever part of a float literal: a stray `.` (ex. the
+    ///   member access `a.b`) is never parsed at all.
+    /// - The `(name)` right before an identifier (ex. `(foo)bar`) could either
+    ///   be a typecast of the identifier to the type `name`, or the grouping
+    ///   `(name)` followed by another identifier. Only `int` and `float` are
+    ///   types, so the parser always chose the grouping, and then could not
+    ///   parse the identifier.
+    pub(crate) fn explain_found(&self, err: String) -> String {
+        let token = |back: usize| self.position.checked_sub(back).and_then(|position| self.tokens.get(position));
+        if let Some((Token::Symbol(Symbol::Period), _)) = token(0) {
+            return format!("{err} (a period can only be part of a float literal, ex. `1.5`, as there is no member access, ex. `a.b`)");
+        }
+        let (Some(left_paren), Some(name), Some(right_paren), Some(operand)) = (token(3), token(2), token(1), token(0)) else {
+            return err;
+        };
+        match (left_paren.0, &name, right_paren.0, &operand) {
+            (
+                Token::Symbol(Symbol::LeftParen),
+                (Token::Identifier, name),
+                Token::Symbol(Symbol::RightParen),
+                (Token::Identifier, operand),
+            ) => format!(
+                "{err} (warning: `({name}){operand}` is ambiguous between a typecast of `{operand}` to the type `{name}`, and the grouping `({name})` followed by `{operand}`: it was parsed as the grouping, since `{name}` is not a type)"
+            ),
+            _ => err,
+        }
+    }
+
+    /// Expect that there is at least one token left in the buffer.
+    /// 
+    /// Over a whole input, this tells a blank input (only whitespace, or a
+    /// shebang line) apart from a malformed one, before anything is parsed.
+    pub fn expect_tokens(&mut self) -> Result<(), String> {
+        if self.peek().is_some() {
+            return Ok(());
+        }
+        match self.early_end_error() {
+            Some(err) => Err(err),
+            None if self.position == 0 => Err("The input contains no tokens (only whitespace, or a shebang line)".to_string()),
+            None => Err("There are no more tokens left in the input".to_string()),
+        }
+    }
+
+    /// Expect that there are no more tokens left in the buffer.
+    /// 
+    /// Returns an error naming the next lexeme otherwise.
+    /// 
+    /// The parseable form of this is the `Eof` terminal, see `terminals::Eof`.
+    pub fn expect_eof(&mut self) -> Result<(), String> {
+        match self.peek() {
+            None => match self.early_end_error() {
+                Some(err) => Err(err),
+                None => Ok(()),
+            },
+            Some(_) => {
+                let err = format!("Expected `{}`, but found {} instead", Eof::parse_label(), self.describe_found());
+                Err(self.explain_found(err))
+            },
+        }
+    }
+}
+impl Drop for ParseBuffer {
+    fn drop(&mut self) {
+        self.track(Some(self.position), None);
+
+        #[cfg(feature = "profile")]
+        if self.loose {
+            self.profile.discards.set(self.profile.discards.get() + 1);
+            // including the token it failed on, even if it only peeked at it
+            let deepest = self.profile.deepest_discard.get().max(self.reached.checked_sub(1));
+            self.profile.deepest_discard.set(deepest);
+        }
+    }
+}
+impl Default for ParseBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Iterator for ParseBuffer {
+    type Item = SharedToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.can_read() {
+            return None;
+        }
+        self.note_read();
+        let next = self.tokens.get(self.position)?;
+        self.track(Some(self.position), Some(self.position + 1));
+        self.position += 1;
+        Some(next)
+    }
+}
\ No newline at end of file
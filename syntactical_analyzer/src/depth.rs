@@ -0,0 +1,72 @@
+//! # Depth
+//! 
+//! The maximum nesting depth of an expression (`Expression::max_depth`), as a
+//! measure of how complex it is.
+//! 
+//! Only arithmetic nests, and each level is either
+//! - an operator, over everything it applies to: `a + b` is 1 level, and `a + b * c`
+//!   (or `a + b + c`, as `(a + b) + c`) is 2,
+//! - a parenthesis, over the expression within it: `(a)` is 1 level.
+//! 
+//! A lone identifier or literal is 0 levels. An assignment, conditional, or
+//! typecast adds no level of its own, so each is as deep as its deepest part.
+//! 
+//! For example, `((a + b) * c) + d` is 5 levels: `a + b`, its parenthesis,
+//! the `* c`, its parenthesis, then the `+ d`.
+
+use crate::non_terminals::*;
+
+impl Expression {
+    /// The maximum nesting depth of this expression (see the `depth` module).
+    pub fn max_depth(&self) -> usize {
+        match self {
+            Expression::Assignment(assignment_expression) => assignment_expression.value.max_depth(),
+            Expression::Conditional(conditional_expression) => conditional_expression.condition.max_depth()
+                .max(conditional_expression.then_expression.max_depth())
+                .max(conditional_expression.else_expression.max_depth()),
+            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.max_depth(),
+            Expression::Typecast(_) => 0,
+        }
+    }
+}
+
+impl ArithmeticExpression {
+    /// The maximum nesting depth of this expression, where each `+` or `-` is
+    /// a level over every term before it and the term after it.
+    pub fn max_depth(&self) -> usize {
+        let mut depth = self.lhs_term.max_depth();
+        let mut extend = self.extend.as_deref();
+        while let Some(term_extend) = extend {
+            depth = 1 + depth.max(term_extend.term.max_depth());
+            extend = term_extend.extend.as_deref();
+        }
+        depth
+    }
+}
+
+impl Term {
+    /// The maximum nesting depth of this term, where each `*` or `/` is a level
+    /// over every factor before it and the factor after it.
+    pub fn max_depth(&self) -> usize {
+        let mut depth = self.factor.max_depth();
+        let mut extend = self.extend.as_deref();
+        while let Some(factor_extend) = extend {
+            depth = 1 + depth.max(factor_extend.factor.max_depth());
+            extend = factor_extend.extend.as_deref();
+        }
+        depth
+    }
+}
+
+impl Factor {
+    /// The maximum nesting depth of this factor: 0 for an identifier or a
+    /// literal, one more than the expression within a parenthesis, or one more
+    /// per prefix operator than the factor after them.
+    pub fn max_depth(&self) -> usize {
+        match self {
+            Factor::Identifier(_) | Factor::Literal(_) => 0,
+            Factor::Parenthesized(parenthesized_expression) => 1 + parenthesized_expression.expression.max_depth(),
+            Factor::Unary(unary_expression) => unary_expression.ops.len() + unary_expression.factor.max_depth(),
+        }
+    }
+}
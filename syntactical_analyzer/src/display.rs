@@ -0,0 +1,538 @@
+//! # Display Configuration
+//! 
+//! This module holds everything `ParseDisplay` writes into: the `TreeWriter`,
+//! walking every line of the tree into a `TreeSink`, and the `DisplayConfig`
+//! describing how the tree should look.
+//! 
+//! It also holds every other `OutputFormat` a tree can be rendered as (see
+//! `ParseDisplay::render_as`). Each of these is walked from the same nodes as
+//! the displayed tree, so every `ParseDisplay` implementation supports them for
+//! free. The same goes for an `OwnedTree`, a standalone copy of the displayed tree.
+//! 
+//! The displayed tree is walked once, in order, into a `TreeSink`, and each
+//! format (the indented tree itself included) is only a sink: the shape of
+//! the tree never depends on the format.
+
+use std::{
+    fmt::Write, // Used with the `writeln!` macro, to write into a `String`.
+    str::FromStr,
+};
+
+use crate::{make_indent, ParseDisplay};
+
+/// The style of a single level of indentation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// Four spaces per level (see `make_indent`).
+    #[default]
+    Spaces,
+
+    /// A single `\t` per level.
+    /// 
+    /// The displayed lexeme signatures are never padded to line up in a
+    /// column, so how aligned the tree looks is entirely up to the viewer's
+    /// tab width.
+    Tabs,
+}
+
+/// Every option for how a parse tree is displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DisplayConfig {
+    pub indent_style: IndentStyle,
+    /// Group each arithmetic chain under a single `Sum` (or `Product`) node,
+    /// and display a lone term (or factor) without its wrapping node.
+    pub compact_arithmetic: bool,
+    /// Display the parameters of a function sorted by their identifiers,
+    /// rather than in the order they were parsed (ex. to compare two
+    /// signatures with reordered parameters). The parsed tree is unchanged.
+    pub sort_parameters: bool,
+    /// The most bytes of a lexeme signature shown inline on a line of the tree,
+    /// cut short with `...` past it (see `ParseDisplay::short_signature`), or
+    /// `None` to always show the whole signature.
+    /// 
+    /// A long list (ex. a body of thousands of statements) then never builds
+    /// its whole signature only to display it.
+    pub max_signature_len: Option<usize>,
+    /// The depth the root of the tree is displayed at by
+    /// `ParseDisplay::render_as_with`, shifting the whole tree right by as many
+    /// levels (ex. to embed it inside a larger document).
+    /// 
+    /// Only the `OutputFormat::Tree` is indented.
+    pub base_depth: usize,
+}
+
+/// The output of `ParseDisplay::display_to`: the sink every line of the tree is
+/// walked into (see `TreeSink`), and the configuration to write it with.
+/// 
+/// Each line is written with its depth (see `line`), and is only passed to the
+/// sink once the next line shows whether it has children (an `enter`) or not
+/// (a `leaf`).
+pub struct TreeWriter<'s> {
+    sink: &'s mut dyn TreeSink,
+    config: DisplayConfig,
+    /// The last line written, which is not yet known to be a leaf or not.
+    pending: Option<(usize, String, Option<String>)>,
+    /// The depth and label of every line entered (and not yet left), the innermost last.
+    open: Vec<(usize, String)>,
+}
+impl<'s> TreeWriter<'s> {
+    /// Create a new output into some sink, with the given configuration.
+    /// 
+    /// The last line is only walked into the sink once the output is `finish`ed.
+    pub fn new(sink: &'s mut dyn TreeSink, config: DisplayConfig) -> Self {
+        TreeWriter { sink, config, pending: None, open: vec![] }
+    }
+
+    /// A getter to the configuration.
+    pub fn config(&self) -> &DisplayConfig {
+        &self.config
+    }
+
+    /// Writes a line of the tree: a label, with the lexeme signature after it if
+    /// there is one, under the last line written at a shallower `depth`.
+    pub fn line(&mut self, depth: usize, label: &str, signature: Option<String>) {
+        self.flush(Some(depth));
+        self.pending = Some((depth, label.to_string(), signature));
+    }
+
+    /// Walks the pending line into the sink, now that the depth of the next
+    /// line is known (or that there is none), then leaves every line the next
+    /// line is not under.
+    fn flush(&mut self, next_depth: Option<usize>) {
+        if let Some((depth, label, signature)) = self.pending.take() {
+            match next_depth {
+                Some(next_depth) if next_depth > depth => {
+                    self.sink.enter(&label, signature.as_deref());
+                    self.open.push((depth, label));
+                },
+                _ => self.sink.leaf(&label, signature.as_deref()),
+            }
+        }
+
+        while let Some((depth, _)) = self.open.last() {
+            if next_depth.is_some_and(|next_depth| next_depth > *depth) {
+                break;
+            }
+            let (_, label) = self.open.pop().unwrap();
+            self.sink.leave(&label);
+        }
+    }
+
+    /// Walks the last line into the sink, and leaves every line still entered.
+    pub fn finish(mut self) {
+        self.flush(None);
+    }
+
+    /// The lexeme signature of some node, to be shown inline on its line of
+    /// the tree, following `DisplayConfig::max_signature_len`.
+    pub fn signature<N: ParseDisplay + ?Sized>(&self, node: &N) -> String {
+        match self.config.max_signature_len {
+            Some(max_len) => node.short_signature(max_len),
+            None => node.lexeme_signature(),
+        }
+    }
+
+    /// A signature built some other way than `signature` (ex. of only part of
+    /// a node), cut short all the same, following `DisplayConfig::max_signature_len`.
+    pub fn cut_signature(&self, signature: String) -> String {
+        match self.config.max_signature_len {
+            Some(max_len) => truncate_signature(signature, max_len),
+            None => signature,
+        }
+    }
+}
+
+/// Every format a parse tree can be rendered as, see `ParseDisplay::render_as`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The indented tree, exactly as `ParseDisplay::render`.
+    #[default]
+    Tree,
+    /// A JSON object per node, as `{"label": _, "signature": _, "children": [_]}`.
+    Json,
+    /// A Graphviz `digraph`, with a graph node per tree node.
+    Dot,
+    /// An S-expression per node, as `(label child...)`, or `(label "lexemes")` for leaves.
+    Sexpr,
+    /// The source text, re-emitted with a canonical formatting (see `formatter.rs`).
+    /// 
+    /// Only a parsed tree has terminals to write: an `OwnedTree` is formatted as nothing.
+    Source,
+}
+impl OutputFormat {
+    /// The name of every format, as parsed by `FromStr`.
+    pub const NAMES: &[&str] = &["tree", "json", "dot", "sexpr", "source"];
+}
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "tree" => Ok(OutputFormat::Tree),
+            "json" => Ok(OutputFormat::Json),
+            "dot" => Ok(OutputFormat::Dot),
+            "sexpr" => Ok(OutputFormat::Sexpr),
+            "source" => Ok(OutputFormat::Source),
+            _ => Err(format!("Unknown output format `{name}`, expected one of: {}", Self::NAMES.join(", "))),
+        }
+    }
+}
+
+/// A signature cut short to at most `max_len` bytes (on a character
+/// boundary), then `...`, if it is any longer.
+pub(crate) fn truncate_signature(mut signature: String, max_len: usize) -> String {
+    if signature.len() > max_len {
+        let mut end = max_len;
+        while !signature.is_char_boundary(end) {
+            end -= 1;
+        }
+        signature.truncate(end);
+        signature.push_str("...");
+    }
+    signature
+}
+
+/// A standalone, owned copy of a displayed tree (see `ParseDisplay::to_owned_tree`):
+/// a displayed line of the tree, with every line displayed under it.
+/// 
+/// Every label and lexeme is copied into an owned `String`, so it borrows
+/// nothing from the token stream it was parsed from. It displays exactly as
+/// the tree it was copied from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnedTree {
+    pub label: String,
+    /// The lexeme signature after the label, if the line has one.
+    pub signature: Option<String>,
+    pub children: Vec<OwnedTree>,
+}
+impl OwnedTree {
+    /// Copies the tree displayed by some parsed node, walking its nodes (see
+    /// `ParseDisplay::visit`).
+    pub(crate) fn of(parsed: &(impl ParseDisplay + ?Sized)) -> Self {
+        let mut sink = OwnedTreeSink::default();
+        parsed.visit(&mut sink);
+        // every node displays exactly one root line
+        sink.roots.remove(0)
+    }
+
+    /// Every structural or lexeme difference from the `expected` tree, walking
+    /// both trees in parallel, or nothing if they are the same.
+    /// 
+    /// Each difference is reported at the deepest node it is found in, along
+    /// with the path of labels to it (ex. "Function Definition > ... > Operator:
+    /// expected `+`, got `-`"). A sibling is also given its index, if another
+    /// sibling has the same label. A child of a different label is reported at
+    /// its parent, and the nodes under it are not compared any further.
+    pub fn diff(&self, expected: &OwnedTree) -> Vec<String> {
+        if self.label != expected.label {
+            return vec![format!("expected `{}`, got `{}`", expected.label, self.label)];
+        }
+        let mut differences = vec![];
+        self.diff_into(expected, self.label.clone(), &mut differences);
+        differences
+    }
+
+    /// Adds the differences of two nodes of the same label, at `path`.
+    fn diff_into(&self, expected: &OwnedTree, path: String, differences: &mut Vec<String>) {
+        let found = differences.len();
+        for i in 0..self.children.len().max(expected.children.len()) {
+            match (self.children.get(i), expected.children.get(i)) {
+                (Some(child), Some(expected_child)) if child.label != expected_child.label => {
+                    differences.push(format!("{path}: expected `{}`, got `{}`", expected_child.label, child.label));
+                },
+                (Some(child), Some(expected_child)) => {
+                    let shared = self.children.iter().filter(|sibling| sibling.label == child.label).count() > 1;
+                    let path = match shared {
+                        true => format!("{path} > [{i}] {}", child.label),
+                        false => format!("{path} > {}", child.label),
+                    };
+                    child.diff_into(expected_child, path, differences);
+                },
+                (None, Some(expected_child)) => differences.push(format!("{path}: missing `{}`", expected_child.label)),
+                (Some(child), None) => differences.push(format!("{path}: unexpected `{}`", child.label)),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        // only a difference not already found in a child is reported here
+        if differences.len() == found && self.signature != expected.signature {
+            differences.push(format!(
+                "{path}: expected `{}`, got `{}`",
+                expected.signature.clone().unwrap_or_default(),
+                self.signature.clone().unwrap_or_default()
+            ));
+        }
+    }
+}
+
+/// The receiver of a walk over a displayed tree (see `ParseDisplay::visit`), in order.
+/// 
+/// Every node with children is an `enter`, its children, then a `leave`, and
+/// every other node is a single `leaf`. The signature of a line is `None` if
+/// the line has none (ex. `Statement:`).
+pub trait TreeSink {
+    fn enter(&mut self, label: &str, signature: Option<&str>);
+    fn leaf(&mut self, label: &str, signature: Option<&str>);
+    fn leave(&mut self, label: &str);
+}
+
+/// `OutputFormat::Tree`: each node on its own line, indented one level deeper
+/// than its parent.
+pub(crate) struct TextSink {
+    out: String,
+    indent_style: IndentStyle,
+    depth: usize,
+}
+impl TextSink {
+    /// A sink writing its root at some depth.
+    pub(crate) fn new(depth: usize, indent_style: IndentStyle) -> Self {
+        TextSink { out: String::new(), indent_style, depth }
+    }
+
+    /// Consistent indentation for the current depth, following the indent style.
+    fn indent(&self) -> String {
+        match self.indent_style {
+            IndentStyle::Spaces => make_indent(self.depth),
+            IndentStyle::Tabs => "\t".repeat(self.depth),
+        }
+    }
+
+    /// Everything written so far.
+    pub(crate) fn into_string(self) -> String {
+        self.out
+    }
+}
+impl TreeSink for TextSink {
+    fn enter(&mut self, label: &str, signature: Option<&str>) {
+        let indent = self.indent();
+        match signature {
+            Some(signature) => writeln!(self.out, "{indent}{label}: {signature}").unwrap(),
+            None => writeln!(self.out, "{indent}{label}:").unwrap(),
+        }
+        self.depth += 1;
+    }
+
+    fn leaf(&mut self, label: &str, signature: Option<&str>) {
+        let indent = self.indent();
+        match signature {
+            Some(signature) => writeln!(self.out, "{indent}{label}: {signature}").unwrap(),
+            None => writeln!(self.out, "{indent}{label}").unwrap(),
+        }
+    }
+
+    fn leave(&mut self, _label: &str) {
+        self.depth -= 1;
+    }
+}
+
+/// Copies every node walked into an `OwnedTree` (see `OwnedTree::of`).
+#[derive(Default)]
+struct OwnedTreeSink {
+    /// The nodes being walked, the innermost last, each with its children walked so far.
+    open: Vec<OwnedTree>,
+    roots: Vec<OwnedTree>,
+}
+impl OwnedTreeSink {
+    /// Adds a finished node under the node being walked, if any.
+    fn push(&mut self, node: OwnedTree) {
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.roots.push(node),
+        }
+    }
+}
+impl TreeSink for OwnedTreeSink {
+    fn enter(&mut self, label: &str, signature: Option<&str>) {
+        self.open.push(OwnedTree { label: label.to_string(), signature: signature.map(str::to_string), children: vec![] });
+    }
+
+    fn leaf(&mut self, label: &str, signature: Option<&str>) {
+        self.push(OwnedTree { label: label.to_string(), signature: signature.map(str::to_string), children: vec![] });
+    }
+
+    fn leave(&mut self, _label: &str) {
+        let node = self.open.pop().unwrap();
+        self.push(node);
+    }
+}
+
+/// `OutputFormat::Json`: each node as `{"label": ..., "signature": ..., "children": [...]}`.
+#[derive(Default)]
+struct JsonSink {
+    out: String,
+    /// If the next node is the first of its list.
+    first: bool,
+}
+impl JsonSink {
+    fn start(&mut self, label: &str, signature: Option<&str>) {
+        if !std::mem::take(&mut self.first) && !self.out.is_empty() {
+            self.out.push_str(", ");
+        }
+        let signature = signature.map_or("null".to_string(), json_string);
+        self.out.push_str(&format!("{{\"label\": {}, \"signature\": {signature}, \"children\": [", json_string(label)));
+    }
+}
+impl TreeSink for JsonSink {
+    fn enter(&mut self, label: &str, signature: Option<&str>) {
+        self.start(label, signature);
+        self.first = true;
+    }
+
+    fn leaf(&mut self, label: &str, signature: Option<&str>) {
+        self.start(label, signature);
+        self.out.push_str("]}");
+    }
+
+    fn leave(&mut self, _label: &str) {
+        self.out.push_str("]}");
+        self.first = false;
+    }
+}
+
+/// `OutputFormat::Dot`: each node numbered in order, with an edge from its parent.
+#[derive(Default)]
+struct DotSink {
+    out: String,
+    next_id: usize,
+    /// The numbers of the nodes being walked, the innermost last.
+    parents: Vec<usize>,
+}
+impl DotSink {
+    /// Writes a node, returning its number.
+    fn node(&mut self, label: &str, signature: Option<&str>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        let label = match signature {
+            Some(signature) => format!("{label}\n{signature}"),
+            None => label.to_string(),
+        };
+        self.out.push_str(&format!("    n{id} [label={}];\n", json_string(&label)));
+        id
+    }
+
+    /// Writes the edge to a node, after every node under it.
+    fn edge(&mut self, id: usize) {
+        if let Some(parent) = self.parents.last() {
+            self.out.push_str(&format!("    n{parent} -> n{id};\n"));
+        }
+    }
+}
+impl TreeSink for DotSink {
+    fn enter(&mut self, label: &str, signature: Option<&str>) {
+        let id = self.node(label, signature);
+        self.parents.push(id);
+    }
+
+    fn leaf(&mut self, label: &str, signature: Option<&str>) {
+        let id = self.node(label, signature);
+        self.edge(id);
+    }
+
+    fn leave(&mut self, _label: &str) {
+        let id = self.parents.pop().unwrap();
+        self.edge(id);
+    }
+}
+
+/// `OutputFormat::Sexpr`: each node as `(label child...)`, a leaf also with its signature.
+#[derive(Default)]
+struct SexprSink {
+    out: String,
+    depth: usize,
+}
+impl SexprSink {
+    fn start(&mut self, label: &str) {
+        if self.depth > 0 {
+            self.out.push('\n');
+        }
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push('(');
+        self.out.push_str(&label.to_lowercase().replace(' ', "-"));
+    }
+
+    fn end(&mut self) {
+        self.out.push(')');
+        if self.depth == 0 {
+            self.out.push('\n');
+        }
+    }
+}
+impl TreeSink for SexprSink {
+    fn enter(&mut self, label: &str, _signature: Option<&str>) {
+        self.start(label);
+        self.depth += 1;
+    }
+
+    fn leaf(&mut self, label: &str, signature: Option<&str>) {
+        self.start(label);
+        if let Some(signature) = signature {
+            self.out.push(' ');
+            self.out.push_str(&json_string(signature));
+        }
+        self.end();
+    }
+
+    fn leave(&mut self, _label: &str) {
+        self.depth -= 1;
+        self.end();
+    }
+}
+
+impl ParseDisplay for OwnedTree {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        let label = label.unwrap_or(self.label.clone());
+        let signature = self.signature.clone().map(|signature| out.cut_signature(signature));
+        out.line(depth, &label, signature);
+
+        for child in &self.children {
+            child.display_to(out, depth+1, None);
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        self.signature.clone().unwrap_or_default()
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        self.children.iter().map(|child| child as &dyn ParseDisplay).collect()
+    }
+}
+
+/// A double-quoted string, escaping as JSON does (which DOT and S-expressions also accept).
+pub(crate) fn json_string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Walks the nodes of a parsed tree into the sink of some format other than
+/// `OutputFormat::Tree` and `OutputFormat::Source` (see `ParseDisplay::render_as_with`).
+pub(crate) fn convert(parsed: &(impl ParseDisplay + ?Sized), format: OutputFormat, config: DisplayConfig) -> String {
+    match format {
+        OutputFormat::Json => {
+            let mut sink = JsonSink::default();
+            parsed.visit_with(&mut sink, None, config);
+            format!("{}\n", sink.out)
+        },
+        OutputFormat::Dot => {
+            let mut sink = DotSink::default();
+            parsed.visit_with(&mut sink, None, config);
+            format!("digraph parse_tree {{\n{}}}\n", sink.out)
+        },
+        OutputFormat::Sexpr => {
+            let mut sink = SexprSink::default();
+            parsed.visit_with(&mut sink, None, config);
+            sink.out
+        },
+        OutputFormat::Tree | OutputFormat::Source => unreachable!("`{format:?}` is not converted from a walk"),
+    }
+}
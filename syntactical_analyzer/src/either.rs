@@ -0,0 +1,114 @@
+//! # Either
+//! 
+//! A generic two-way alternation, for building grammars on top of this crate.
+//! 
+//! The grammar of this crate names every alternation as its own enum (ex.
+//! `Statement`, `Expression`), but a simple `<A> | <B>` can instead use
+//! `Either<A, B>`, without writing out the same parse pattern again.
+
+use q1_lib::lexer::Token; // The first and last token of the parsed alternative.
+
+use crate::{
+    display::TreeWriter,
+    owned::IntoOwned,
+    Parse,
+    ParseBuffer,
+    ParseDisplay,
+};
+
+/// Either an `A` or a `B`, trying to parse `A` first.
+/// 
+/// # BNF
+/// ```text
+/// <EITHER> -> <A>
+///           | <B>
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+impl<A: Parse, B: Parse> Parse for Either<A, B> {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        if let Ok(a) = A::parse(&mut fork) {
+            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+            return Ok(Either::Left(a));
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        if let Ok(b) = B::parse(&mut fork) {
+            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+            return Ok(Either::Right(b));
+        }
+
+        Err(format!("Expected either `{} {}`, but found something else instead", A::parse_label(), B::parse_label()))
+    }
+
+    fn parse_label() -> String {
+        format!("{} or {}", A::parse_label(), B::parse_label())
+    }
+}
+impl<A: IntoOwned, B: IntoOwned> IntoOwned for Either<A, B> {
+    fn into_owned(self) -> Self {
+        match self {
+            Either::Left(a) => Either::Left(a.into_owned()),
+            Either::Right(b) => Either::Right(b.into_owned()),
+        }
+    }
+}
+impl<A: ParseDisplay, B: ParseDisplay> ParseDisplay for Either<A, B> {
+    /// Displays exactly as whichever alternative was parsed.
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        match self {
+            Either::Left(a) => a.display_to(out, depth, label),
+            Either::Right(b) => b.display_to(out, depth, label),
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        match self {
+            Either::Left(a) => a.lexeme_signature(),
+            Either::Right(b) => b.lexeme_signature(),
+        }
+    }
+
+    fn full_signature(&self) -> String {
+        match self {
+            Either::Left(a) => a.full_signature(),
+            Either::Right(b) => b.full_signature(),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        match self {
+            Either::Left(a) => a.children(),
+            Either::Right(b) => b.children(),
+        }
+    }
+
+    fn span(&self) -> Option<(usize, usize)> {
+        match self {
+            Either::Left(a) => a.span(),
+            Either::Right(b) => b.span(),
+        }
+    }
+
+    fn first_token(&self) -> Option<(Token, &str)> {
+        match self {
+            Either::Left(a) => a.first_token(),
+            Either::Right(b) => b.first_token(),
+        }
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        match self {
+            Either::Left(a) => a.last_token(),
+            Either::Right(b) => b.last_token(),
+        }
+    }
+}
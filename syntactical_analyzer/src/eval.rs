@@ -0,0 +1,192 @@
+//! # Eval
+//!
+//! The constant evaluation of an expression (`Expression::eval`), where every
+//! factor is a literal: an identifier has no value to evaluate to.
+//!
+//! An int and a float in the same operation are evaluated as two floats. An
+//! int operation which overflows, or divides by zero, is an error.
+//!
+//! Whether `5 / 2` is `2` or `2.5` is a choice of semantics, which is made by
+//! the `DivisionMode` of an `EvalConfig`:
+//! - `Integer` rounds the quotient down (`-5 / 2` is `-3`),
+//! - `Truncating` rounds the quotient towards zero (`-5 / 2` is `-2`),
+//! - `Float` never rounds the quotient (`5 / 2` is `2.5`),
+//! - `ByOperands` (the default) follows the types of the operands, truncating
+//!   between two ints and otherwise not rounding, as in C.
+//!
+//! A rounded quotient keeps the type of its operands: it is only an int if
+//! both operands are.
+//!
+//! The condition of a conditional expression is true when it is not zero, and
+//! an assignment evaluates to the value assigned.
+
+use crate::{
+    non_terminals::*,
+    terminals::LiteralValue,
+};
+
+/// How a division is evaluated (see the `eval` module).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DivisionMode {
+    Integer,
+    Truncating,
+    Float,
+    #[default]
+    ByOperands,
+}
+
+/// The configuration of `Expression::eval`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EvalConfig {
+    pub division: DivisionMode,
+}
+
+impl Expression {
+    /// The constant value of this expression (see the `eval` module).
+    pub fn eval(&self, config: &EvalConfig) -> Result<LiteralValue, String> {
+        match self {
+            Expression::Assignment(assignment_expression) => assignment_expression.value.eval(config),
+            Expression::Conditional(conditional_expression) => {
+                let condition = match conditional_expression.condition.eval(config)? {
+                    LiteralValue::Int(int) => int != 0,
+                    LiteralValue::Float(float) => float != 0.0,
+                };
+                match condition {
+                    true => conditional_expression.then_expression.eval(config),
+                    false => conditional_expression.else_expression.eval(config),
+                }
+            },
+            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.eval(config),
+            Expression::Typecast(typecast_expression) => Err(not_constant(&typecast_expression.ident.lexeme)),
+        }
+    }
+}
+
+impl ArithmeticExpression {
+    /// The constant value of this expression, each term applied left to right.
+    pub fn eval(&self, config: &EvalConfig) -> Result<LiteralValue, String> {
+        let mut value = self.lhs_term.eval(config)?;
+        let mut extend = self.extend.as_deref();
+        while let Some(term_extend) = extend {
+            let rhs = term_extend.term.eval(config)?;
+            value = match term_extend.op {
+                TermOp::Add(_) => apply(value, rhs, "+", i64::checked_add, |lhs, rhs| lhs + rhs)?,
+                TermOp::Subtract(_) => apply(value, rhs, "-", i64::checked_sub, |lhs, rhs| lhs - rhs)?,
+            };
+            extend = term_extend.extend.as_deref();
+        }
+        Ok(value)
+    }
+}
+
+impl Term {
+    /// The constant value of this term, each factor applied left to right.
+    pub fn eval(&self, config: &EvalConfig) -> Result<LiteralValue, String> {
+        let mut value = self.factor.eval(config)?;
+        let mut extend = self.extend.as_deref();
+        while let Some(factor_extend) = extend {
+            let rhs = factor_extend.factor.eval(config)?;
+            value = match factor_extend.op {
+                FactorOp::Multiply(_) => apply(value, rhs, "*", i64::checked_mul, |lhs, rhs| lhs * rhs)?,
+                FactorOp::Divide(_) => divide(value, rhs, config.division)?,
+                FactorOp::Modulo(_) => apply(value, rhs, "%", i64::checked_rem, |lhs, rhs| lhs % rhs)
+                    .map_err(|err| by_zero(rhs, err))?,
+            };
+            extend = factor_extend.extend.as_deref();
+        }
+        Ok(value)
+    }
+}
+
+impl Factor {
+    /// The constant value of this factor, which cannot be an identifier.
+    pub fn eval(&self, config: &EvalConfig) -> Result<LiteralValue, String> {
+        match self {
+            Factor::Identifier(identifier) => Err(not_constant(&identifier.lexeme)),
+            Factor::Literal(literal) => literal.value(),
+            Factor::Parenthesized(parenthesized_expression) => parenthesized_expression.expression.eval(config),
+            Factor::Unary(unary_expression) => unary_expression.eval(config),
+        }
+    }
+}
+
+impl UnaryExpression {
+    /// The constant value of this expression, each operator applied right to
+    /// left (the one nearest the factor first).
+    pub fn eval(&self, config: &EvalConfig) -> Result<LiteralValue, String> {
+        let mut value = self.factor.eval(config)?;
+        for op in self.ops.iter().rev() {
+            value = match (op, value) {
+                (UnaryOp::Positive(_), value) => value,
+                (UnaryOp::Negative(_), LiteralValue::Int(int)) => int.checked_neg()
+                    .map(LiteralValue::Int)
+                    .ok_or(format!("The int `-{int}` overflows"))?,
+                (UnaryOp::Negative(_), LiteralValue::Float(float)) => LiteralValue::Float(-float),
+                // as in C, the negation of anything is the int `1` if it is zero, and `0` otherwise
+                (UnaryOp::Not(_), LiteralValue::Int(int)) => LiteralValue::Int((int == 0) as i64),
+                (UnaryOp::Not(_), LiteralValue::Float(float)) => LiteralValue::Int((float == 0.0) as i64),
+            };
+        }
+        Ok(value)
+    }
+}
+
+fn not_constant(identifier: &str) -> String {
+    format!("Cannot evaluate the identifier `{identifier}`, only literals are constant")
+}
+
+fn as_float(value: LiteralValue) -> f64 {
+    match value {
+        LiteralValue::Int(int) => int as f64,
+        LiteralValue::Float(float) => float,
+    }
+}
+
+/// Applies an operator, on two ints if both are, or otherwise on two floats.
+fn apply(
+    lhs: LiteralValue,
+    rhs: LiteralValue,
+    op: &str,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<LiteralValue, String> {
+    match (lhs, rhs) {
+        (LiteralValue::Int(lhs), LiteralValue::Int(rhs)) => int_op(lhs, rhs)
+            .map(LiteralValue::Int)
+            .ok_or(format!("The int `{lhs} {op} {rhs}` overflows")),
+        _ => Ok(LiteralValue::Float(float_op(as_float(lhs), as_float(rhs)))),
+    }
+}
+
+/// Divides by the given `DivisionMode`.
+fn divide(lhs: LiteralValue, rhs: LiteralValue, mode: DivisionMode) -> Result<LiteralValue, String> {
+    let both_ints = matches!((lhs, rhs), (LiteralValue::Int(_), LiteralValue::Int(_)));
+    match (mode, both_ints) {
+        (DivisionMode::Float, _) | (DivisionMode::ByOperands, false) => {
+            Ok(LiteralValue::Float(as_float(lhs) / as_float(rhs)))
+        },
+        (DivisionMode::Integer, true) => apply(lhs, rhs, "/", |lhs, rhs| {
+            let quotient = lhs.checked_div(rhs)?;
+            // a truncated negative quotient, with a remainder, is one above its floor
+            match lhs % rhs != 0 && (lhs < 0) != (rhs < 0) {
+                true => quotient.checked_sub(1),
+                false => Some(quotient),
+            }
+        }, |_, _| unreachable!()),
+        (DivisionMode::Truncating | DivisionMode::ByOperands, true) => {
+            apply(lhs, rhs, "/", i64::checked_div, |_, _| unreachable!())
+        },
+        (DivisionMode::Integer, false) => Ok(LiteralValue::Float((as_float(lhs) / as_float(rhs)).floor())),
+        (DivisionMode::Truncating, false) => Ok(LiteralValue::Float((as_float(lhs) / as_float(rhs)).trunc())),
+    }
+    .map_err(|err| by_zero(rhs, err))
+}
+
+/// The error of an int operation, which is a division by zero if its divisor is
+/// the int `0` (rather than the overflow `apply` reports for any `None`).
+fn by_zero(rhs: LiteralValue, err: String) -> String {
+    match rhs {
+        LiteralValue::Int(0) => "Cannot divide an int by zero".to_string(),
+        _ => err,
+    }
+}
@@ -0,0 +1,370 @@
+//! # Events
+//!
+//! A SAX-style stream of parse events, for consumers which would rather
+//! handle each node as a callback than walk the parse tree (ex. a transform
+//! written as a stream of tokens).
+//!
+//! Every node is a `StartNode` event, then the events of everything under it
+//! in source order, then an `EndNode` event. Every terminal under it is a
+//! `Token` event, including the punctuation the `Visitor` skips (ex. `;`).
+//! A node is named by its type (ex. `ReturnStatement`).
+//!
+//! `parse_events` has the parsers emit the events themselves as they parse
+//! (see `ParseBuffer::fork_node`), rather than walking a parsed tree. As the
+//! parser backtracks over forks, each fork buffers its own events: they are
+//! appended to the buffer the fork is committed to, and discarded along with
+//! a discarded fork, so only the events of the successful parse are handed
+//! over. An already parsed tree is emitted by walking it instead (see `Emit`),
+//! into exactly the same events.
+//!
+//! This is not a streaming parser: the whole tree is still parsed (and
+//! returned), and every event is buffered until the input has parsed, so it
+//! takes more memory than the tree alone, not less.
+
+use std::rc::Rc;
+
+use q1_lib::lexer::Token;
+
+use crate::{
+    expect_all,
+    non_terminals::*,
+    terminals::Terminal,
+    Parse,
+    ParseBuffer,
+    ParseDisplay,
+};
+
+/// A single event of a parse (see the `events` module).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseEvent {
+    /// The start of a node, by the name of its type.
+    StartNode(&'static str),
+    /// A terminal, with its lexeme (shared with the terminal parsed from it).
+    Token(Token, Rc<String>),
+    /// The end of a node, by the name of its type.
+    EndNode(&'static str),
+}
+
+/// A node which can be emitted as parse events.
+pub trait Emit {
+    /// Emits this (already parsed) node, and everything under it, to a handler.
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent));
+
+    /// Parses this node, which emits its events as it parses.
+    /// 
+    /// A list which is only a node by its name (ex. `CompoundStatements`) is
+    /// wrapped in its node here, as it is wherever else it is parsed.
+    fn parse_emitting(buffer: &mut ParseBuffer) -> Result<Self, String>
+    where Self: Parse {
+        Self::parse(buffer)
+    }
+}
+
+/// Parses `T` from a buffer (see `parse_all`), handing the parse events it
+/// emitted as it parsed to a handler (see the `events` module), then returning
+/// the parsed `T`.
+/// 
+/// The events committed to the buffer are only handed over once the whole
+/// input has parsed, so nothing is handed over for input which does not parse.
+/// Until then, every event is kept in memory along with the tree.
+pub fn parse_events<T: Parse + Emit>(buffer: &mut ParseBuffer, handler: impl FnMut(ParseEvent)) -> Result<T, String> {
+    let (parsed, events) = parse_with_events::<T>(buffer)?;
+    events.into_iter().for_each(handler);
+    Ok(parsed)
+}
+
+/// Parses `T` from a buffer (see `parse_all`), along with the parse events it
+/// emitted as it parsed.
+pub(crate) fn parse_with_events<T: Parse + Emit>(buffer: &mut ParseBuffer) -> Result<(T, Vec<ParseEvent>), String> {
+    buffer.start_events();
+    let parsed = expect_all(buffer, T::parse_emitting);
+    let events = buffer.take_events();
+    Ok((parsed?, events))
+}
+
+/// Emits the events of a node between its start and end.
+fn node(kind: &'static str, handler: &mut dyn FnMut(ParseEvent), children: impl FnOnce(&mut dyn FnMut(ParseEvent))) {
+    handler(ParseEvent::StartNode(kind));
+    children(handler);
+    handler(ParseEvent::EndNode(kind));
+}
+
+/// Emits a terminal.
+fn token(terminal: &impl Terminal, handler: &mut dyn FnMut(ParseEvent)) {
+    handler(ParseEvent::Token(terminal.token(), Rc::new(terminal.lexeme_str().to_string())));
+}
+
+/// Emits the terminal of an operator or qualifier, which is a node with no children.
+fn sole_token(node: &dyn ParseDisplay, handler: &mut dyn FnMut(ParseEvent)) {
+    if let Some((token, lexeme)) = node.first_token() {
+        handler(ParseEvent::Token(token, Rc::new(lexeme.to_string())));
+    }
+}
+
+impl Emit for Program {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("Program", handler, |handler| {
+            for function_definition in &self.function_definitions {
+                function_definition.emit(handler);
+            }
+        });
+    }
+}
+
+impl Emit for FunctionDefinition {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("FunctionDefinition", handler, |handler| {
+            for qualifier in &self.qualifiers {
+                sole_token(qualifier, handler);
+            }
+            token(&self.type_, handler);
+            token(&self.function_name, handler);
+            token(&self.left_paren, handler);
+            self.parameters.emit(handler);
+            token(&self.right_paren, handler);
+            token(&self.left_curly, handler);
+            self.compound_statements.emit(handler);
+            token(&self.right_curly, handler);
+        });
+    }
+}
+
+impl Emit for FunctionParameters {
+    fn parse_emitting(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        buffer.parse_node("FunctionParameters", Self::parse)
+    }
+
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("FunctionParameters", handler, |handler| {
+            for (parameter, comma) in self {
+                parameter.emit(handler);
+                if let Some(comma) = comma {
+                    token(comma, handler);
+                }
+            }
+        });
+    }
+}
+
+impl Emit for FunctionParameter {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("FunctionParameter", handler, |handler| {
+            for qualifier in &self.qualifiers {
+                sole_token(qualifier, handler);
+            }
+            token(&self.type_, handler);
+            token(&self.identifier, handler);
+        });
+    }
+}
+
+impl Emit for CompoundStatements {
+    fn parse_emitting(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        buffer.parse_node("CompoundStatements", Self::parse)
+    }
+
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("CompoundStatements", handler, |handler| {
+            for (statement, semicolon) in self {
+                statement.emit(handler);
+                token(semicolon, handler);
+            }
+        });
+    }
+}
+
+impl Emit for Statement {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("Statement", handler, |handler| match self {
+            Statement::Assignment(assignment_statement) => assignment_statement.emit(handler),
+            Statement::Return(return_statement) => return_statement.emit(handler),
+            Statement::If(if_statement) => if_statement.emit(handler),
+        });
+    }
+}
+
+impl Emit for AssignmentStatement {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("AssignmentStatement", handler, |handler| {
+            token(&self.lhs_identifier, handler);
+            token(&self.equals, handler);
+            self.expression.emit(handler);
+        });
+    }
+}
+
+impl Emit for ReturnStatement {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("ReturnStatement", handler, |handler| {
+            token(&self.return_, handler);
+            for (expression, comma) in &self.expressions {
+                expression.emit(handler);
+                if let Some(comma) = comma {
+                    token(comma, handler);
+                }
+            }
+        });
+    }
+}
+
+impl Emit for IfStatement {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("IfStatement", handler, |handler| {
+            token(&self.if_, handler);
+            token(&self.left_paren, handler);
+            self.condition.emit(handler);
+            token(&self.right_paren, handler);
+            self.block.emit(handler);
+            if let Some(ref else_tail) = self.else_tail {
+                else_tail.emit(handler);
+            }
+        });
+    }
+}
+
+impl Emit for ElseTail {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("ElseTail", handler, |handler| match self {
+            ElseTail::If(else_, if_statement) => {
+                token(else_, handler);
+                if_statement.emit(handler);
+            },
+            ElseTail::Block(else_, block) => {
+                token(else_, handler);
+                block.emit(handler);
+            },
+        });
+    }
+}
+
+impl Emit for Block {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("Block", handler, |handler| {
+            token(&self.left_curly, handler);
+            self.compound_statements.emit(handler);
+            token(&self.right_curly, handler);
+        });
+    }
+}
+
+impl Emit for Expression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("Expression", handler, |handler| match self {
+            Expression::Assignment(assignment_expression) => assignment_expression.emit(handler),
+            Expression::Conditional(conditional_expression) => conditional_expression.emit(handler),
+            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.emit(handler),
+            Expression::Typecast(typecast_expression) => typecast_expression.emit(handler),
+        });
+    }
+}
+
+impl Emit for AssignmentExpression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("AssignmentExpression", handler, |handler| {
+            token(&self.target, handler);
+            token(&self.equals, handler);
+            self.value.emit(handler);
+        });
+    }
+}
+
+impl Emit for ConditionalExpression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("ConditionalExpression", handler, |handler| {
+            self.condition.emit(handler);
+            token(&self.question, handler);
+            self.then_expression.emit(handler);
+            token(&self.colon, handler);
+            self.else_expression.emit(handler);
+        });
+    }
+}
+
+impl Emit for TypecastExpression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("TypecastExpression", handler, |handler| {
+            token(&self.left_paren, handler);
+            token(&self.type_, handler);
+            token(&self.right_paren, handler);
+            token(&self.ident, handler);
+        });
+    }
+}
+
+impl Emit for ArithmeticExpression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("ArithmeticExpression", handler, |handler| {
+            self.lhs_term.emit(handler);
+            if let Some(ref extend) = self.extend {
+                extend.emit(handler);
+            }
+        });
+    }
+}
+
+impl Emit for Term {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("Term", handler, |handler| {
+            self.factor.emit(handler);
+            if let Some(ref extend) = self.extend {
+                extend.emit(handler);
+            }
+        });
+    }
+}
+
+impl Emit for TermExtend {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("TermExtend", handler, |handler| {
+            sole_token(&self.op, handler);
+            self.term.emit(handler);
+            if let Some(ref extend) = self.extend {
+                extend.emit(handler);
+            }
+        });
+    }
+}
+
+impl Emit for Factor {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("Factor", handler, |handler| match self {
+            Factor::Identifier(identifier) => token(identifier, handler),
+            Factor::Literal(literal) => token(literal, handler),
+            Factor::Parenthesized(parenthesized_expression) => parenthesized_expression.emit(handler),
+            Factor::Unary(unary_expression) => unary_expression.emit(handler),
+        });
+    }
+}
+
+impl Emit for FactorExtend {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("FactorExtend", handler, |handler| {
+            sole_token(&self.op, handler);
+            self.factor.emit(handler);
+            if let Some(ref extend) = self.extend {
+                extend.emit(handler);
+            }
+        });
+    }
+}
+
+impl Emit for ParenthesizedExpression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("ParenthesizedExpression", handler, |handler| {
+            token(&self.left_paren, handler);
+            self.expression.emit(handler);
+            token(&self.right_paren, handler);
+        });
+    }
+}
+
+impl Emit for UnaryExpression {
+    fn emit(&self, handler: &mut dyn FnMut(ParseEvent)) {
+        node("UnaryExpression", handler, |handler| {
+            for op in &self.ops {
+                sole_token(op, handler);
+            }
+            self.factor.emit(handler);
+        });
+    }
+}
@@ -0,0 +1,101 @@
+//! # Formatter
+//!
+//! Re-emits a parse tree as source text, with a canonical formatting (see
+//! `OutputFormat::Source`), rather than displaying the tree itself:
+//! - one statement per line,
+//! - 4 spaces of indentation per nested block,
+//! - a single space around every binary operator (and `=`, `?`, `:`),
+//! - no space before a `;` or `,`, or inside parentheses,
+//! - a space before every `{`, where an empty block is `{}`.
+//!
+//! A typecast is kept tight (`(int)y`), as is the `(` of a function name
+//! (`f(int x)`), while every other `(` is spaced from what is before it (ex.
+//! `if (x)`, or `a * (b + c)`). A prefix `+` or `-` is kept tight to its operand
+//! (`a * -b`): it is prefix wherever it does not follow an operand.
+//!
+//! Only the terminals of the tree are written: formatting the formatted source
+//! again changes nothing.
+//!
+//! A raw token stream can be formatted all the same with `format_tokens`,
+//! without parsing it first, as a fallback for input which does not parse.
+
+use std::fmt::Write; // Used with the `write!` macro, to write a raw identifier.
+
+use q1_lib::lexer::{Symbol, Token}; // The terminals being formatted.
+
+use crate::{make_indent, ParseDisplay};
+
+/// The canonically formatted source text of a parse tree (see the `formatter` module).
+pub(crate) fn format_source<N: ParseDisplay + ?Sized>(node: &N) -> String {
+    let mut terminals = vec![];
+    collect_terminals(node, &mut terminals);
+    format_terminals(terminals)
+}
+
+/// A raw token stream (ex. from `q1_lib::lex`), formatted back into readable
+/// source with the same spacing as the `formatter` module.
+/// 
+/// Only the tokens themselves are looked at, never a parse tree, so even input
+/// which fails to parse (ex. a missing `;`) is formatted. Every line break is
+/// dropped, as lines are placed again.
+pub fn format_tokens(tokens: &[(Token, String)]) -> String {
+    let terminals = tokens.iter()
+        .filter(|(token, _)| *token != Token::Newline)
+        .map(|(token, lexeme)| (*token, lexeme.as_str()));
+    format_terminals(terminals)
+}
+
+/// Joins terminals, in order, with the separator each pair calls for.
+fn format_terminals<'t>(terminals: impl IntoIterator<Item = (Token, &'t str)>) -> String {
+    let mut out = String::new();
+    let mut depth: usize = 0;
+    let mut prev: Option<Token> = None;
+    let mut prev_prefix = false; // if the previous token was a prefix operator
+    for (token, lexeme) in terminals {
+        if token == Token::Symbol(Symbol::RightCurly) {
+            depth = depth.saturating_sub(1);
+        }
+        let after_operand = matches!(prev, Some(Token::Identifier | Token::RawIdentifier | Token::Literal(_) | Token::Symbol(Symbol::RightParen)));
+        let separator = match (prev, token) {
+            (None, _) => "".to_string(),
+            _ if prev_prefix => "".to_string(),
+            (Some(Token::Symbol(Symbol::LeftCurly)), Token::Symbol(Symbol::RightCurly)) => "".to_string(),
+            (Some(Token::Symbol(Symbol::Semicolon | Symbol::LeftCurly)), _)
+            | (_, Token::Symbol(Symbol::RightCurly)) => format!("\n{}", make_indent(depth)),
+            (_, Token::Symbol(Symbol::Semicolon | Symbol::Comma | Symbol::RightParen))
+            | (Some(Token::Symbol(Symbol::LeftParen)), _) => "".to_string(),
+            // a function name, or a typecast
+            (Some(Token::Identifier | Token::RawIdentifier), Token::Symbol(Symbol::LeftParen))
+            | (Some(Token::Symbol(Symbol::RightParen)), Token::Identifier | Token::RawIdentifier) => "".to_string(),
+            _ => " ".to_string(),
+        };
+        out.push_str(&separator);
+        match token {
+            // the backticks are not part of the lexeme, but keep it from being a keyword
+            Token::RawIdentifier => write!(out, "`{lexeme}`").unwrap(),
+            _ => out.push_str(lexeme),
+        }
+        if token == Token::Symbol(Symbol::LeftCurly) {
+            depth += 1;
+        }
+        prev_prefix = matches!(token, Token::Symbol(Symbol::Not))
+            || (!after_operand && matches!(token, Token::Symbol(Symbol::Plus | Symbol::Minus)));
+        prev = Some(token);
+    }
+    if prev.is_some() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Every terminal under some node, in order (see `ParseDisplay::first_token`).
+fn collect_terminals<'n, N: ParseDisplay + ?Sized>(node: &'n N, terminals: &mut Vec<(Token, &'n str)>) {
+    let children = node.children();
+    if children.is_empty() {
+        // a line break only separates items, which are already on their own lines
+        terminals.extend(node.first_token().filter(|(token, _)| *token != Token::Newline));
+    }
+    for child in children {
+        collect_terminals(child, terminals);
+    }
+}
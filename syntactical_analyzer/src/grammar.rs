@@ -0,0 +1,51 @@
+//! # Grammar
+//! 
+//! Every non-terminal documents its BNF production. This module makes those
+//! productions available at runtime through the `Bnf` trait, so that the
+//! complete grammar the parser implements can be printed at once.
+
+use crate::non_terminals::*;
+
+/// The BNF production(s) of a non-terminal.
+/// 
+/// This must be kept in sync with the type's `Parse` implementation (and its
+/// `# BNF` documentation). A parsed node gives its own type's production with
+/// `ParseDisplay::production`.
+pub trait Bnf {
+    /// The production(s) of this type, in the same notation as the documentation.
+    fn bnf() -> &'static str;
+}
+
+/// The complete grammar, in the order of the grammar (starting at the root).
+pub fn grammar() -> String {
+    let productions = [
+        FunctionDefinition::bnf(),
+        FunctionParameters::bnf(),
+        FunctionParameter::bnf(),
+        Qualifier::bnf(),
+        CompoundStatements::bnf(),
+        Statement::bnf(),
+        AssignmentStatement::bnf(),
+        ReturnStatement::bnf(),
+        ReturnExpressions::bnf(),
+        ExpressionList::bnf(),
+        IfStatement::bnf(),
+        ElseTail::bnf(),
+        Block::bnf(),
+        Expression::bnf(),
+        AssignmentExpression::bnf(),
+        ConditionalExpression::bnf(),
+        TypecastExpression::bnf(),
+        ArithmeticExpression::bnf(),
+        Term::bnf(),
+        TermExtend::bnf(),
+        Factor::bnf(),
+        ParenthesizedExpression::bnf(),
+        UnaryExpression::bnf(),
+        FactorExtend::bnf(),
+    ];
+
+    let mut grammar = productions.join("\n");
+    grammar.push('\n');
+    grammar
+}
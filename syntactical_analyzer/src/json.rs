@@ -0,0 +1,224 @@
+//! # JSON
+//!
+//! A lossless JSON form of a parse tree, which can be read back into the typed
+//! tree (ex. so that a tool can edit the JSON, and feed it back).
+//!
+//! Unlike `OutputFormat::Json`, which is the displayed tree (and so leaves out
+//! what the display does, ex. the `;` of each statement), this keeps every
+//! terminal. It is written from the parse events of a node (see the `events`
+//! module): every node is `{"node": "<type>", "children": [...]}`, and every
+//! terminal is `{"token": "<token>", "lexeme": "<lexeme>"}`, where the token is
+//! named as `q1_lib::tokens_from_json` reads it (ex. `Symbol(Plus)`).
+//!
+//! Reading the JSON back parses its terminals again, in order, then checks
+//! that they parsed into exactly the nodes of the JSON. Every invariant of the
+//! tree (ex. that only the last item of a `Delimited` has no delimiter) is
+//! then upheld by the parser itself, rather than trusted from the JSON. Every
+//! lexeme must also lex as its token (ex. `1` is never an `Identifier`), and
+//! the JSON can only be nested `MAX_JSON_DEPTH` nodes deep.
+
+use q1_lib::{json::Reader, lex, lexer::Token};
+
+use crate::{
+    display::json_string,
+    events::{parse_with_events, Emit, ParseEvent},
+    Parse,
+    ParseBuffer,
+};
+
+/// The most nodes a JSON tree can be nested in, past which reading it is an
+/// error, rather than overflowing the stack.
+pub const MAX_JSON_DEPTH: usize = 1024;
+
+/// The lossless JSON of a node (see the `json` module).
+pub fn to_json<T: Emit + ?Sized>(node: &T) -> String {
+    let mut json = String::new();
+    let mut first = true; // if the next value is the first of its array
+    node.emit(&mut |event| {
+        let separator = if first { "" } else { ", " };
+        match event {
+            ParseEvent::StartNode(name) => {
+                json.push_str(&format!("{separator}{{\"node\": {}, \"children\": [", json_string(name)));
+                first = true;
+            },
+            ParseEvent::Token(token, lexeme) => {
+                json.push_str(&format!("{separator}{{\"token\": {}, \"lexeme\": {}}}", json_string(&format!("{token:?}")), json_string(&lexeme)));
+                first = false;
+            },
+            ParseEvent::EndNode(_) => {
+                json.push_str("]}");
+                first = false;
+            },
+        }
+    });
+    json.push('\n');
+    json
+}
+
+/// Reads a node back from its lossless JSON (see the `json` module).
+/// 
+/// It is an error if the JSON is not a `T`, including if its terminals parse
+/// into different nodes than the JSON has (ex. a statement moved out of its
+/// list, without its `;`).
+pub fn from_json<T: Parse + Emit>(json: &str) -> Result<T, String> {
+    let mut reader = Reader::new(json);
+    let expected = read_tree(&mut reader)?;
+    reader.expect_end("tree")?;
+
+    // the terminals are parsed again, as a lazy token stream, so that only
+    // the tokens of the tree read back are kept (see `ParseBuffer::from_lexer`)
+    let tokens = expected.iter()
+        .filter_map(|event| match event {
+            JsonEvent::Token(token, lexeme) => Some(Ok((*token, lexeme.clone()))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let (parsed, events) = parse_with_events::<T>(&mut ParseBuffer::from_lexer(tokens.into_iter()))?;
+    let found = events.into_iter().map(JsonEvent::from).collect::<Vec<_>>();
+    if let Some(i) = (0..expected.len().max(found.len())).find(|&i| expected.get(i) != found.get(i)) {
+        let describe = |event: Option<&JsonEvent>| event.map_or("the end of the tree".to_string(), JsonEvent::describe);
+        Err(format!(
+            "The JSON tree does not match how its terminals parse: expected {}, but they parse into {} instead",
+            describe(expected.get(i)),
+            describe(found.get(i)),
+        ))?
+    }
+    Ok(parsed)
+}
+
+/// A parse event, owning its lexeme (see `ParseEvent`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum JsonEvent {
+    StartNode(String),
+    Token(Token, String),
+    EndNode,
+}
+impl JsonEvent {
+    fn describe(&self) -> String {
+        match self {
+            JsonEvent::StartNode(name) => format!("the start of `{name}`"),
+            JsonEvent::Token(_, lexeme) => format!("the terminal `{lexeme}`"),
+            JsonEvent::EndNode => "the end of a node".to_string(),
+        }
+    }
+}
+impl From<ParseEvent> for JsonEvent {
+    fn from(event: ParseEvent) -> Self {
+        match event {
+            ParseEvent::StartNode(name) => JsonEvent::StartNode(name.to_string()),
+            ParseEvent::Token(token, lexeme) => JsonEvent::Token(token, lexeme.to_string()),
+            ParseEvent::EndNode(_) => JsonEvent::EndNode,
+        }
+    }
+}
+
+/// A node or terminal object of a JSON tree, whose keys are still being read.
+struct OpenObject {
+    /// Where its parse events start, at a placeholder for its first event.
+    start: usize,
+    node: Option<String>,
+    /// If it has a list of children (which may be empty).
+    children: bool,
+    token: Option<Token>,
+    lexeme: Option<String>,
+}
+impl OpenObject {
+    /// An object after its `{`, whose events start at the end of `events`.
+    fn open(events: &mut Vec<JsonEvent>) -> Self {
+        events.push(JsonEvent::EndNode); // the placeholder, replaced once the object is closed
+        OpenObject { start: events.len() - 1, node: None, children: false, token: None, lexeme: None }
+    }
+
+    /// The object after its `}`, with its events (and those of its children) in place.
+    fn close(self, events: &mut Vec<JsonEvent>) -> Result<(), String> {
+        match (self.node, self.children, self.token, self.lexeme) {
+            (Some(node), _, None, None) => {
+                events[self.start] = JsonEvent::StartNode(node);
+                events.push(JsonEvent::EndNode);
+            },
+            (None, false, Some(token), Some(lexeme)) => {
+                expect_lexeme_of(token, &lexeme)?;
+                events[self.start] = JsonEvent::Token(token, lexeme);
+            },
+            _ => Err("Expected either a `node` (with `children`), or a `token` with its `lexeme`, in the JSON tree".to_string())?,
+        }
+        Ok(())
+    }
+}
+
+/// Reads a JSON tree as its parse events, in order.
+/// 
+/// The objects are read with a stack of those still open, rather than
+/// recursively, so that only `MAX_JSON_DEPTH` limits how deep a tree can be.
+fn read_tree(reader: &mut Reader) -> Result<Vec<JsonEvent>, String> {
+    let mut events = vec![];
+    reader.expect('{')?;
+    let mut open = vec![OpenObject::open(&mut events)];
+    let mut first_key = true; // if the next key is the first of the innermost open object
+    loop {
+        let object = open.last_mut().expect("an object is always open until the tree is read");
+        let closed = match first_key {
+            true => reader.next_is('}'),
+            false if reader.next_is(',') => false,
+            false => {
+                reader.expect('}')?;
+                true
+            },
+        };
+        if !closed {
+            let key = reader.string()?;
+            reader.expect(':')?;
+            first_key = false;
+            match key.as_str() {
+                "node" => object.node = Some(reader.string()?),
+                "token" => object.token = Some(reader.string()?.parse::<Token>()?),
+                "lexeme" => object.lexeme = Some(reader.string()?),
+                "children" => {
+                    object.children = true;
+                    reader.expect('[')?;
+                    if !reader.next_is(']') {
+                        open_child(reader, &mut open, &mut events)?;
+                        first_key = true;
+                    }
+                },
+                _ => Err(format!("Unknown key `{key}` in the JSON tree"))?,
+            }
+            continue;
+        }
+
+        open.pop().expect("the object was open").close(&mut events)?;
+        if open.is_empty() {
+            return Ok(events);
+        }
+        // the next child of the same list, or the end of the list
+        match reader.next_is(',') {
+            true => {
+                open_child(reader, &mut open, &mut events)?;
+                first_key = true;
+            },
+            false => {
+                reader.expect(']')?;
+                first_key = false;
+            },
+        }
+    }
+}
+
+/// Opens the object of the next child of the innermost open object.
+fn open_child(reader: &mut Reader, open: &mut Vec<OpenObject>, events: &mut Vec<JsonEvent>) -> Result<(), String> {
+    if open.len() >= MAX_JSON_DEPTH {
+        Err(format!("JSON tree nested too deeply (the limit is {MAX_JSON_DEPTH} levels)"))?
+    }
+    reader.expect('{')?;
+    open.push(OpenObject::open(events));
+    Ok(())
+}
+
+/// Expects a lexeme to lex as exactly one token, of its token kind in the JSON
+/// (ex. not `1` as an `Identifier`), as it would have been lexed from source.
+fn expect_lexeme_of(token: Token, lexeme: &str) -> Result<(), String> {
+    match lex(lexeme).as_deref() {
+        Ok([(lexed, _)]) if *lexed == token => Ok(()),
+        _ => Err(format!("The lexeme `{lexeme}` is not a `{token:?}` in the JSON tree")),
+    }
+}
@@ -6,7 +6,7 @@
 //! `Parse` is the main recursion, which this library has all implementations
 //! using left-recursion.
 //! 
-//! `ParseDisplay`, along with the helper function `make_indent`, leverages
+//! `ParseDisplay`, along with the `TreeWriter` it writes into, leverages
 //! recursion once again to automatically format and display a type's
 //! tree structure.
 //! 
@@ -14,14 +14,26 @@
 //! - `terminals`: All barebone token types from the lexical analysis (the primative structures).
 //! - `non-terminals`: All composite syntax structure (build off of more primative structures).
 //! - `modulars`: Automatic list-like syntax parsers.
+//! - `either`: A generic two-way alternation.
+//! - `tuples`: Tuples as generic sequences.
+//! - `display`: The configurable output of `ParseDisplay`.
+//! - `formatter`: The canonically formatted source text of a parse tree, or of a raw token stream (`format_tokens`).
+//! - `grammar`: The BNF of every non-terminal, as implemented.
+//! - `visit`: A read-only traversal over a parse tree.
+//! - `rewrite`: A rewriting traversal, producing a new parse tree.
+//! - `events`: A parse, emitted as a stream of parse events as it parses.
+//! - `json`: A parsed tree as lossless JSON, and read back from it.
+//! - `lint`: Post-parse warnings over a parse tree.
+//! - `simplify`: Removing redundant parentheses from an expression.
+//! - `depth`: The maximum nesting depth of an expression.
+//! - `eval`: The constant evaluation of an expression.
+//! - `annotate`: User data attached to the nodes of a parse tree, in a side table.
+//! - `operators`: The classification and precedence of every operator token.
+//! - `pratt`: An alternative precedence climbing parser for arithmetic (only with the `pratt` feature).
 
-use std::{
-    slice::Iter, // The iterator-type over slice structures
-    iter::Peekable, // When used on `Iter`, it allows to "peekahead", without consumption
-    sync::LazyLock // Used to safely use the `'static` lifetime, without having data as precondition.
-};
+use std::collections::HashMap; // The symbol table of `parse_with_symbols`.
 
-use q1_lib::lexer::Token; // Reusing the token type defined in the first problem.
+use q1_lib::lexer::{LocatedToken, Token, TriviaToken}; // Reusing the token types defined in the first problem.
 
 /// All parseable terminal tokens
 pub mod terminals;
@@ -29,15 +41,67 @@ pub mod terminals;
 pub mod non_terminals;
 /// All list-pattern abstractions.
 pub mod modulars;
+/// The generic two-way alternation.
+pub mod either;
+/// Tuples of parse types, parsed in sequence.
+mod tuples;
+/// All configuration and output of `ParseDisplay`.
+pub mod display;
+/// The canonical source formatter, of `OutputFormat::Source`.
+mod formatter;
+/// The BNF grammar the parser implements.
+pub mod grammar;
+/// The read-only parse tree visitor.
+pub mod visit;
+/// The parse tree rewriter.
+pub mod rewrite;
+/// The copying of a parse tree out of its token stream.
+pub mod owned;
+/// The SAX-style stream of parse events.
+pub mod events;
+/// The lossless JSON of a parse tree, which can be read back.
+pub mod json;
+/// Post-parse warnings.
+pub mod lint;
+/// The redundant parenthesis removal pass.
+mod simplify;
+/// The nesting depth of expressions.
+mod depth;
+/// The constant evaluation of expressions.
+pub mod eval;
+/// The side table of user data on parse tree nodes.
+pub mod annotate;
+/// The classification of operator tokens.
+pub mod operators;
+/// The precedence climbing arithmetic parser.
+#[cfg(feature = "pratt")]
+pub mod pratt;
+/// The forkable token stream that is parsed.
+mod buffer;
+/// The mapping from the source text to the token stream.
+pub mod source_map;
 
-/// The input token stream. This relies on the lexical analyzer from `Q1`.
+pub use buffer::{ParseBuffer, DEFAULT_MAX_DEPTH, DEFAULT_MAX_ERROR_LEVELS};
+pub use formatter::format_tokens;
+
+use display::{DisplayConfig, OutputFormat, OwnedTree, TextSink, TreeSink, TreeWriter};
+use source_map::SourceMap;
+use terminals::TypeKind;
+
+/// The input token stream, along with the location of every token in the
+/// input file. This relies on the lexical analyzer from `Q1`.
 /// 
-/// The LazyLock guarentees the existance of `Vec<_>` at the static variable's
-/// first use, and then keeping it immutable for the program's lifetime.
-/// This allows the implementation to depend on the `'static` lifetime.
+/// Keywords are only recognized in any case with `ci_keywords` (see
+/// `Lexer::with_ci_keywords` in `Q1`), which the binary passes for `--ci-keywords`.
 /// 
-/// For more details on how the `Vec<_>` is obtained, see `q1_lib` in `Q1`.
-static TOKEN_STREAM: LazyLock<Vec<(Token, String)>> = LazyLock::new(|| q1_lib::get_lexemes());
+/// For more details on how the tokens are obtained, see `q1_lib` in `Q1`.
+/// Unlike `Q1`, a lexical error exits with `1`, exactly like a parse error.
+pub(crate) fn input_tokens(ci_keywords: bool) -> Vec<LocatedToken> {
+    q1_lib::try_get_located_lexemes(ci_keywords).unwrap_or_else(|err| {
+        eprintln!("LEXICAL ERROR: {err}");
+        std::process::exit(1)
+    })
+}
 
 /// A helper function to make consistent indentation for a specified depth.
 pub fn make_indent(depth: usize) -> String {
@@ -49,6 +113,181 @@ pub fn make_indent(depth: usize) -> String {
     indent
 }
 
+/// Lexes and parses some in-memory source text as `T`, expecting the entire
+/// source to be consumed.
+/// 
+/// This is the library-usable counterpart of the binary: no CLI arguments or
+/// files are involved, and a lexical error is returned as a parse error.
+pub fn parse_str<T: Parse>(source: &str) -> Result<T, String> {
+    let tokens = q1_lib::lex_with_locations(source)?;
+    parse_all(&mut ParseBuffer::from_located_tokens(tokens))
+}
+
+/// Lexes and parses some in-memory source text as the rule (by its type name,
+/// see `RULE_NAMES`) chosen at runtime, expecting the entire source to be
+/// consumed.
+/// 
+/// This is `parse_str`, for a rule which is only known at runtime.
+pub fn parse_rule_str(name: &str, source: &str) -> Result<Box<dyn ParseDisplay>, String> {
+    let tokens = q1_lib::lex_with_locations(source)?;
+    parse_rule_all(name, &mut ParseBuffer::from_located_tokens(tokens))
+}
+
+/// Parses `T` from a buffer, expecting the rest of the buffer to be consumed.
+/// 
+/// If the nesting limit of the buffer was exceeded, that is the error
+/// reported, rather than whatever error it caused.
+pub fn parse_all<T: Parse>(buffer: &mut ParseBuffer) -> Result<T, String> {
+    expect_all(buffer, T::parse)
+}
+
+/// Parses the rule (by its type name, see `RULE_NAMES`) chosen at runtime,
+/// expecting the rest of the buffer to be consumed (see `parse_all`).
+pub fn parse_rule_all(name: &str, buffer: &mut ParseBuffer) -> Result<Box<dyn ParseDisplay>, String> {
+    expect_all(buffer, |buffer| parse_rule(name, buffer))
+}
+
+/// Parses something from a buffer, expecting the rest of the buffer to be consumed.
+pub(crate) fn expect_all<T>(buffer: &mut ParseBuffer, parse: impl FnOnce(&mut ParseBuffer) -> Result<T, String>) -> Result<T, String> {
+    let parsed = parse(buffer).and_then(|parsed| {
+        buffer.expect_eof()?;
+        Ok(parsed)
+    });
+
+    // exceeding the nesting limit takes precedence over whatever error it caused
+    match buffer.take_nesting_error() {
+        Some(err) => Err(err),
+        None => parsed,
+    }
+}
+
+/// Parses `T` from a buffer (see `parse_all`), and prints its tree on success.
+/// 
+/// This is the whole "parse the root, then print the tree or the error" flow
+/// of the binary, where the error is returned for the caller to report.
+pub fn parse_and_display<T: Parse>(buffer: &mut ParseBuffer) -> Result<(), String> {
+    parse_all::<T>(buffer)?.display(0, None);
+    Ok(())
+}
+
+/// Parses `T` from a buffer, consuming at most `max_tokens` tokens.
+/// 
+/// Unlike `parse_str`, the rest of the buffer is left as is (ex. to parse only
+/// the first expression of a long line). The parse can still look past the
+/// budget (ex. at the `;` after an expression), but if it consumes more than
+/// `max_tokens` tokens, it fails with a token budget error instead, and
+/// nothing is consumed.
+pub fn parse_limited<T: Parse>(buffer: &mut ParseBuffer, max_tokens: usize) -> Result<T, String> {
+    buffer.limited(max_tokens, T::parse)
+}
+
+/// Parses the next statement from a buffer, along with the `;` terminating
+/// it, or `None` once there is nothing left in the buffer.
+/// 
+/// Unlike `CompoundStatements`, only a single statement is consumed, so that a
+/// line-oriented tool can handle each statement as soon as it is parsed. On
+/// success, the buffer is left right after the `;`, ready for the next call;
+/// on an error, nothing is consumed.
+pub fn parse_next_statement(buffer: &mut ParseBuffer) -> Option<Result<non_terminals::Statement, String>> {
+    buffer.peek()?;
+    let parsed = <(non_terminals::Statement, terminals::Semicolon)>::parse(buffer);
+    Some(parsed.map(|(statement, _semicolon)| statement))
+}
+
+/// Parses `T` from a buffer, while consulting and updating a symbol table of
+/// the declared type of every identifier.
+/// 
+/// Parsing succeeds regardless of the symbols: the semantic problems found
+/// with them (ex. an assignment to an undeclared variable) are returned as
+/// diagnostics alongside the parsed item. `symbols` is the outermost scope:
+/// only the declarations outside of any function or block are added to it.
+pub fn parse_with_symbols<T: Parse>(buffer: &mut ParseBuffer, symbols: &mut HashMap<String, TypeKind>) -> Result<(T, Vec<String>), String> {
+    buffer.with_symbols(symbols, T::parse)
+}
+
+/// A concise rendering of a parse error: only the outermost rule being parsed,
+/// and the innermost (most specific) error, skipping every rule in between.
+/// 
+/// Every rule which adds context to an error prefixes it with
+/// `While parsing <rule>...`, so a deep failure can get long. An error without
+/// any such context is returned as is.
+pub fn short_error(err: &str) -> String {
+    let lines = err.lines().map(str::trim).collect::<Vec<_>>();
+    let is_context = |line: &str| line.starts_with("While parsing ");
+
+    let Some(outermost) = lines.iter().find(|line| is_context(line)) else {
+        return err.to_string();
+    };
+    // the innermost error is the first one under the innermost context
+    let innermost_context = lines.iter().rposition(|line| is_context(line)).unwrap();
+    match lines.get(innermost_context + 1) {
+        Some(innermost) => format!("{outermost}\n    {innermost}"),
+        None => err.to_string(),
+    }
+}
+
+/// The names of every rule `parse_rule` can parse, in the order of the grammar.
+pub const RULE_NAMES: &[&str] = &[
+    "Program",
+    "FunctionDefinition",
+    "FunctionSignature",
+    "FunctionParameters",
+    "FunctionParameter",
+    "CompoundStatements",
+    "Statement",
+    "AssignmentStatement",
+    "ReturnStatement",
+    "ExpressionList",
+    "IfStatement",
+    "Block",
+    "Expression",
+    "AssignmentExpression",
+    "ConditionalExpression",
+    "TypecastExpression",
+    "ArithmeticExpression",
+    "Term",
+    "Factor",
+    "ParenthesizedExpression",
+    "UnaryExpression",
+];
+
+/// Parses the rule (by its type name, see `RULE_NAMES`) chosen at runtime.
+/// 
+/// The parsed rule is boxed, and can only be displayed.
+pub fn parse_rule(name: &str, buffer: &mut ParseBuffer) -> Result<Box<dyn ParseDisplay>, String> {
+    use non_terminals::*;
+
+    /// Parses as `T`, boxing the result.
+    fn boxed<T: Parse + 'static>(buffer: &mut ParseBuffer) -> Result<Box<dyn ParseDisplay>, String> {
+        Ok(Box::new(T::parse(buffer)?))
+    }
+
+    match name {
+        "Program" => boxed::<Program>(buffer),
+        "FunctionDefinition" => boxed::<FunctionDefinition>(buffer),
+        "FunctionSignature" => boxed::<FunctionSignature>(buffer),
+        "FunctionParameters" => boxed::<FunctionParameters>(buffer),
+        "FunctionParameter" => boxed::<FunctionParameter>(buffer),
+        "CompoundStatements" => boxed::<CompoundStatements>(buffer),
+        "Statement" => boxed::<Statement>(buffer),
+        "AssignmentStatement" => boxed::<AssignmentStatement>(buffer),
+        "ReturnStatement" => boxed::<ReturnStatement>(buffer),
+        "ExpressionList" => boxed::<ExpressionList>(buffer),
+        "IfStatement" => boxed::<IfStatement>(buffer),
+        "Block" => boxed::<Block>(buffer),
+        "Expression" => boxed::<Expression>(buffer),
+        "AssignmentExpression" => boxed::<AssignmentExpression>(buffer),
+        "ConditionalExpression" => boxed::<ConditionalExpression>(buffer),
+        "TypecastExpression" => boxed::<TypecastExpression>(buffer),
+        "ArithmeticExpression" => boxed::<ArithmeticExpression>(buffer),
+        "Term" => boxed::<Term>(buffer),
+        "Factor" => boxed::<Factor>(buffer),
+        "ParenthesizedExpression" => boxed::<ParenthesizedExpression>(buffer),
+        "UnaryExpression" => boxed::<UnaryExpression>(buffer),
+        _ => Err(format!("Unknown rule `{name}`, expected one of: {}", RULE_NAMES.join(", "))),
+    }
+}
+
 /// The skeleton of this library.
 pub trait Parse<T = Self>
 where Self: Sized + ParseDisplay {
@@ -69,15 +308,15 @@ where Self: Sized + ParseDisplay {
     /// Nearly all implementations follow a similar forking pattern.
     /// 
     /// Here's a dummy example
-    /// ```
+    /// ```ignore
     /// impl Parse for YourType {
     ///     fn parse(buffer: &mut ParseBuffer) -> Result<YourType, String> {
     ///         let mut fork = buffer.fork();
     ///
     ///         todo!("... attempt to parse on the fork ...");
     ///         
-    ///         // parse was successful, so modify the buffer before returning
-    ///         *buffer = fork;
+    ///         // parse was successful, so commit the fork to the buffer before returning
+    ///         buffer.commit(fork);
     ///         return Ok(YourType);
     ///         
     ///         // or
@@ -100,13 +339,85 @@ where Self: Sized + ParseDisplay {
 /// An important tool for a parse tree to recursively display itself with correct
 /// indenting.
 pub trait ParseDisplay {
-    /// The tool to print to stdout.
+    /// The tool to write the parse tree into some output.
     /// 
-    /// `depth` describes how deep the indentation should be. It is recommended
-    /// to use `make_indent` to get correct indentation.
+    /// `depth` describes how deep the line of this node is, and every line of
+    /// its children should be written one level deeper (see `TreeWriter::line`).
     /// 
     /// It is up to the implementor if the label will be used, or not, or at all.
-    fn display(&self, depth: usize, label: Option<String>);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>);
+
+    /// Walks the displayed tree, in order, into a sink (see `TreeSink`).
+    /// 
+    /// Every output format is a sink over this one walk, including the
+    /// displayed tree itself (see `render_with`).
+    fn visit(&self, sink: &mut dyn TreeSink) {
+        self.visit_with(sink, None, DisplayConfig::default());
+    }
+
+    /// Walks the displayed tree into a sink (see `visit`), as it would be
+    /// displayed with some label and configuration.
+    fn visit_with(&self, sink: &mut dyn TreeSink, label: Option<String>, config: DisplayConfig) {
+        let mut out = TreeWriter::new(sink, config);
+        self.display_to(&mut out, 0, label);
+        out.finish();
+    }
+
+    /// The parse tree, as it would be displayed with some configuration, in a singular string.
+    /// 
+    /// See `display_to` for more details.
+    fn render_with(&self, depth: usize, label: Option<String>, config: DisplayConfig) -> String {
+        let mut sink = TextSink::new(depth, config.indent_style);
+        self.visit_with(&mut sink, label, config);
+        sink.into_string()
+    }
+
+    /// The parse tree, as it would be displayed, in a singular string.
+    /// 
+    /// See `display_to` for more details.
+    fn render(&self, depth: usize, label: Option<String>) -> String {
+        self.render_with(depth, label, DisplayConfig::default())
+    }
+
+    /// The parse tree in some output format, in a singular string.
+    /// 
+    /// Every format other than `OutputFormat::Tree` is walked from the same
+    /// nodes as the displayed tree (see `visit`), or, for `OutputFormat::Source`,
+    /// written from its terminals, so implementors never have to support them directly.
+    fn render_as(&self, format: OutputFormat) -> String {
+        self.render_as_with(format, DisplayConfig::default())
+    }
+
+    /// The parse tree in some output format, displayed with some configuration.
+    /// 
+    /// The indent style (and base depth) of the configuration only applies to
+    /// `OutputFormat::Tree`.
+    fn render_as_with(&self, format: OutputFormat, config: DisplayConfig) -> String {
+        match format {
+            OutputFormat::Tree => self.render_with(config.base_depth, None, config),
+            OutputFormat::Source => formatter::format_source(self),
+            format => display::convert(self, format, config),
+        }
+    }
+
+    /// A standalone copy of the displayed tree, owning every label and lexeme
+    /// (see `OwnedTree`), which can outlive the token stream it was parsed from.
+    fn to_owned_tree(&self) -> OwnedTree {
+        OwnedTree::of(self)
+    }
+
+    /// Every difference of this tree from an `expected` tree, along with where
+    /// it is (see `OwnedTree::diff`), or nothing if they display the same.
+    fn diff(&self, expected: &dyn ParseDisplay) -> Vec<String> {
+        self.to_owned_tree().diff(&expected.to_owned_tree())
+    }
+
+    /// The tool to print to stdout.
+    /// 
+    /// See `display_to` for more details.
+    fn display(&self, depth: usize, label: Option<String>) {
+        print!("{}", self.render(depth, label));
+    }
 
     /// The signature of all terminal lexemes, in-order, in a singular string.
     /// 
@@ -114,37 +425,129 @@ pub trait ParseDisplay {
     /// If it is too verbose to include in `display`, still implement but disregard in
     /// the display.
     fn lexeme_signature(&self) -> String;
-}
 
-/// A cheaply-forkable iterator over a given token stream.
-pub struct ParseBuffer {
-    /// A peekable iterator over some known list of tokens and strings.
-    buffer: Peekable<Iter<'static, (Token, String)>>
-}
-impl ParseBuffer {
-    /// Create a new `ParseBuffer` over a token stream.
+    /// The lexeme signature (see `lexeme_signature`), cut short to at most
+    /// `max_len` bytes, then `...`, if it is any longer.
     /// 
-    /// This will be the static token stream from the input file `TOKEN_STREAM`.
+    /// Lists override this to stop building their signature as soon as it is
+    /// cut, so that a signature which is mostly cut is never fully built.
+    fn short_signature(&self, max_len: usize) -> String {
+        display::truncate_signature(self.lexeme_signature(), max_len)
+    }
+
+    /// The signature of all terminal lexemes, in-order, in a singular string,
+    /// without eliding anything.
     /// 
-    /// See `TOKEN_STREAM` for more details.
-    pub fn new() -> Self {
-        ParseBuffer { buffer: TOKEN_STREAM.iter().peekable() }
+    /// Unlike `lexeme_signature`, bodies (like `{....}`) are written out in full.
+    /// Only types which elide (or contain something which elides) need to
+    /// override the default.
+    fn full_signature(&self) -> String {
+        self.lexeme_signature()
     }
 
-    /// See if there is a "next" item, without actually consuming.
-    pub fn peek(&mut self) -> Option<&(Token, String)> {
-        self.buffer.peek().map(|&var| var)
+    /// The exact source text of this node, byte-for-byte with its original
+    /// spacing, unlike `full_signature` (which spaces every lexeme the same).
+    /// 
+    /// `tokens` must be the tokens this tree was parsed from, lexed with their
+    /// leading trivia (see `q1_lib::lex_with_trivia`). The source text is each
+    /// token's trivia followed by its lexeme, including the trivia before the
+    /// very first token. The trailing trivia, after the very last token, is
+    /// before no token, so it is never part of a node.
+    fn to_source_exact(&self, tokens: &[TriviaToken]) -> String {
+        let Some((start, end)) = self.span() else {
+            return String::new();
+        };
+        tokens[start..end].iter().map(|(_token, lexeme, trivia)| format!("{trivia}{lexeme}")).collect()
+    }
+
+    /// Every direct child of this node in the parse tree, in order.
+    /// 
+    /// Terminals have no children. Every composite type overrides this, as
+    /// `span` and `highlight` depend on it.
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![]
+    }
+
+    /// The positions of the tokens this node covers (see `ParseBuffer::position`),
+    /// as `(start, end)` with an exclusive end.
+    /// 
+    /// An empty list covers no tokens, but is still where it was parsed, as the
+    /// empty span `(position, position)`. This is `None` for anything else
+    /// which covers no tokens at all (ex. `Eof`, or a `Program` of nothing).
+    fn span(&self) -> Option<(usize, usize)> {
+        let mut spans = self.children().into_iter().filter_map(|child| child.span());
+        let (start, mut end) = spans.next()?;
+        for (_start, child_end) in spans {
+            end = child_end;
+        }
+        Some((start, end))
+    }
+
+    /// The first token this node covers, with its lexeme (ex. the `x` of
+    /// `x = a + b`), or `None` if it covers no tokens at all.
+    /// 
+    /// This is the leftmost terminal of the subtree, giving where the node
+    /// starts in the source text without needing its positions (see `span`).
+    fn first_token(&self) -> Option<(Token, &str)> {
+        self.children().into_iter().find_map(|child| child.first_token())
+    }
+
+    /// The last token this node covers, with its lexeme (ex. the `b` of
+    /// `x = a + b`), or `None` if it covers no tokens at all.
+    /// 
+    /// This is the rightmost terminal of the subtree (see `first_token`).
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.children().into_iter().rev().find_map(|child| child.last_token())
+    }
+
+    /// The smallest subtree covering the `token_number`th token, counted from 1
+    /// (ex. 3 is the `a` of `x = a + b`), or `None` if this node does not cover it.
+    /// 
+    /// The `token_number`th token is the one at the position `token_number - 1`
+    /// (see `ParseBuffer::position`, which counts from 0).
+    /// 
+    /// Terminals are never returned themselves (unless this is a terminal): the
+    /// smallest subtree of a token is the non-terminal directly holding it.
+    fn highlight(&self, token_number: usize) -> Option<&dyn ParseDisplay>
+    where Self: Sized {
+        smallest_covering(self, token_number.checked_sub(1)?)
+    }
+
+    /// The smallest subtree at some line and column of the source text (both
+    /// counted from 1), the same as `highlight` for the token there.
+    /// 
+    /// This is `None` between tokens (ex. on whitespace), rather than the node
+    /// enclosing it: there is no token there to find the subtree of.
+    fn node_at(&self, source_map: &SourceMap, line: usize, column: usize) -> Option<&dyn ParseDisplay>
+    where Self: Sized {
+        self.highlight(source_map.token_at(line, column)? + 1)
     }
 
-    /// Cheaply clone the buffer iterator at the buffer's current state.
-    pub fn fork(&self) -> Self {
-        ParseBuffer { buffer: self.buffer.clone() }
+    /// The BNF production(s) of this node's own type (see `grammar::Bnf`), ex.
+    /// for a tool showing the grammar rule of a node found with `node_at`.
+    /// 
+    /// Every non-terminal with a production of its own overrides this, and it
+    /// is `None` for anything else (ex. a terminal, or a list).
+    fn production(&self) -> Option<&'static str> {
+        None
     }
 }
-impl Iterator for ParseBuffer {
-    type Item = &'static (Token, String);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.buffer.next()
+/// See `ParseDisplay::highlight`.
+fn smallest_covering(node: &dyn ParseDisplay, target_position: usize) -> Option<&dyn ParseDisplay> {
+    let (start, end) = node.span()?;
+    if !(start..end).contains(&target_position) {
+        return None;
     }
-}
\ No newline at end of file
+
+    for child in node.children() {
+        // a terminal child means this is the smallest subtree
+        if child.children().is_empty() {
+            continue;
+        }
+        if let Some(smallest) = smallest_covering(child, target_position) {
+            return Some(smallest);
+        }
+    }
+    Some(node)
+}
@@ -0,0 +1,104 @@
+//! # Lints
+//! 
+//! A post-parse pass over a successfully parsed tree, flagging patterns which
+//! parse, but are obviously dead or mistaken. Lints never fail a parse: they
+//! are only reported as warnings.
+
+use crate::{
+    non_terminals::*,
+    visit::{self, Visitor},
+    ParseDisplay,
+};
+
+/// The default `LintConfig::max_statements`.
+pub const DEFAULT_MAX_STATEMENTS: usize = 50;
+
+/// The default `LintConfig::max_parameters`.
+pub const DEFAULT_MAX_PARAMETERS: usize = 8;
+
+/// Every configurable threshold of the lints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LintConfig {
+    /// The most statements a function can have (counting those nested in
+    /// blocks too) before it is flagged as too long.
+    pub max_statements: usize,
+    /// The most parameters a function can have before it is flagged as taking too many.
+    pub max_parameters: usize,
+}
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            max_statements: DEFAULT_MAX_STATEMENTS,
+            max_parameters: DEFAULT_MAX_PARAMETERS,
+        }
+    }
+}
+
+/// Lints a function definition, returning every warning in source order.
+pub fn lint(function_definition: &FunctionDefinition) -> Vec<String> {
+    lint_with(function_definition, LintConfig::default())
+}
+
+/// Lints a function definition with some configuration, returning every warning in source order.
+pub fn lint_with(function_definition: &FunctionDefinition, config: LintConfig) -> Vec<String> {
+    let mut linter = Linter { config, warnings: vec![], statements: 0 };
+    linter.visit_function_definition(function_definition);
+    linter.warnings
+}
+
+/// The visitor collecting every lint warning.
+struct Linter {
+    config: LintConfig,
+    warnings: Vec<String>,
+    /// The number of statements visited so far.
+    statements: usize,
+}
+impl<'ast> Visitor<'ast> for Linter {
+    fn visit_function_definition(&mut self, node: &'ast FunctionDefinition) {
+        let name = node.function_name.lexeme_signature();
+
+        // too many parameters (a duplicate parameter is a parse error instead)
+        let parameters = node.parameters.items().len();
+        if parameters > self.config.max_parameters {
+            self.warnings.push(format!(
+                "Function `{name}` has {parameters} parameters, more than the limit of {}",
+                self.config.max_parameters
+            ));
+        }
+
+        // empty body
+        if node.compound_statements.items().is_empty() {
+            self.warnings.push(format!("Function `{name}` has an empty body"));
+        }
+
+        visit::walk_function_definition(self, node);
+
+        // too many statements, only known once the whole body is visited
+        if self.statements > self.config.max_statements {
+            self.warnings.push(format!(
+                "Function `{name}` has {} statements, more than the limit of {}",
+                self.statements,
+                self.config.max_statements
+            ));
+        }
+    }
+
+    fn visit_statement(&mut self, node: &'ast Statement) {
+        self.statements += 1;
+        visit::walk_statement(self, node);
+    }
+
+    fn visit_compound_statements(&mut self, node: &'ast CompoundStatements) {
+        // every statement after the first return is unreachable
+        let first_return = node.items()
+            .iter()
+            .position(|(statement, _semicolon)| matches!(statement, Statement::Return(_)));
+        if let Some(first_return) = first_return {
+            for (statement, _semicolon) in &node.items()[first_return + 1..] {
+                self.warnings.push(format!("Statement `{}` is unreachable after a return", statement.lexeme_signature()));
+            }
+        }
+
+        visit::walk_compound_statements(self, node);
+    }
+}
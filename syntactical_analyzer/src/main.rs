@@ -1,21 +1,178 @@
-use std::process;
+use std::{env, process};
 
 use q2_lib::{
-    Parse,
+    parse_all,
+    parse_rule_all,
+    short_error,
     ParseBuffer,
     ParseDisplay,
+    RULE_NAMES,
+    display::{DisplayConfig, OutputFormat},
+    grammar::grammar,
+    lint::{lint_with, LintConfig},
     non_terminals::FunctionDefinition
 };
 
+/// Every option which can follow the input path on the command line.
+struct Options {
+    /// `--root <rule>`: the rule the whole input is parsed as (see `RULE_NAMES`).
+    root: String,
+    /// `--format <tree|json|dot|sexpr|source>`: how the parse tree is printed.
+    format: OutputFormat,
+    /// `--verbose`: print the full context of a parse error, rather than only its endpoints.
+    verbose: bool,
+    /// `--sort-params`: display the parameters of a function sorted by name (see `DisplayConfig::sort_parameters`).
+    sort_parameters: bool,
+    /// `--max-statements <N>`: the most statements a function can have before it is linted as too long.
+    max_statements: usize,
+    /// `--max-params <N>`: the most parameters a function can have before it is linted as taking too many.
+    max_parameters: usize,
+    /// `--check`: print nothing on success, and only a one-line error on failure, for the exit code alone.
+    check: bool,
+    /// `--reach`: also print the deepest token the parser speculated to before backtracking (see `ParseBuffer::deepest_reach`).
+    reach: bool,
+    /// `--base-indent <N>`: the levels the whole tree is shifted right by (see `DisplayConfig::base_depth`).
+    base_indent: usize,
+    /// `--max-signature <N>`: the most bytes of a lexeme signature shown inline (see `DisplayConfig::max_signature_len`).
+    max_signature_len: Option<usize>,
+    /// `--ci-keywords`: recognize a keyword spelled in any case (see `Lexer::with_ci_keywords` in `Q1`).
+    ci_keywords: bool,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            root: "FunctionDefinition".into(),
+            format: OutputFormat::default(),
+            verbose: false,
+            sort_parameters: false,
+            max_statements: LintConfig::default().max_statements,
+            max_parameters: LintConfig::default().max_parameters,
+            check: false,
+            base_indent: 0,
+            reach: false,
+            max_signature_len: None,
+            ci_keywords: false,
+        }
+    }
+}
+impl Options {
+    /// Parses the options from the arguments following the input path.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    let format = args.next().ok_or("Expected an output format after `--format`".to_string())?;
+                    options.format = format.parse()?;
+                },
+                "--root" => {
+                    let root = args.next().ok_or("Expected a rule name after `--root`".to_string())?;
+                    if !RULE_NAMES.contains(&root.as_str()) {
+                        Err(format!("Unknown rule `{root}`, expected one of: {}", RULE_NAMES.join(", ")))?
+                    }
+                    options.root = root;
+                },
+                "--verbose" => options.verbose = true,
+                "--sort-params" => options.sort_parameters = true,
+                "--max-statements" => {
+                    let max_statements = args.next().ok_or("Expected a number after `--max-statements`".to_string())?;
+                    options.max_statements = max_statements.parse()
+                        .map_err(|err| format!("Invalid `--max-statements` number `{max_statements}`: {err}"))?;
+                },
+                "--max-params" => {
+                    let max_parameters = args.next().ok_or("Expected a number after `--max-params`".to_string())?;
+                    options.max_parameters = max_parameters.parse()
+                        .map_err(|err| format!("Invalid `--max-params` number `{max_parameters}`: {err}"))?;
+                },
+                "--check" => options.check = true,
+                "--base-indent" => {
+                    let base_indent = args.next().ok_or("Expected a number after `--base-indent`".to_string())?;
+                    options.base_indent = base_indent.parse()
+                        .map_err(|err| format!("Invalid `--base-indent` number `{base_indent}`: {err}"))?;
+                },
+                "--max-signature" => {
+                    let max_signature_len = args.next().ok_or("Expected a number after `--max-signature`".to_string())?;
+                    options.max_signature_len = Some(max_signature_len.parse()
+                        .map_err(|err| format!("Invalid `--max-signature` number `{max_signature_len}`: {err}"))?);
+                },
+                "--reach" if cfg!(feature = "profile") => options.reach = true,
+                "--reach" => Err("`--reach` is only available with the `profile` feature".to_string())?,
+                "--ci-keywords" => options.ci_keywords = true,
+                _ => Err(format!("Unknown option `{arg}`"))?,
+            }
+        }
+        Ok(options)
+    }
+}
+
 fn main() {
+    // Print the grammar instead of parsing anything, if asked to.
+    if env::args().nth(1).as_deref() == Some("--grammar") {
+        print!("{}", grammar());
+        return;
+    }
+
+    // Every option follows the input path.
+    let options = match Options::parse(env::args().skip(2)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("ERROR - {err}");
+            process::exit(1);
+        },
+    };
+
     // Get an original parse buffer at the start of the token stream.
-    let mut parse_buffer = ParseBuffer::new();
+    let mut parse_buffer = ParseBuffer::from_input(options.ci_keywords);
+
+    // Expect the root rule (a function definition, unless chosen with `--root`)
+    // as the root structure. Try to parse it.
+    // Nothing is expected after the root structure.
+    let blank = parse_buffer.expect_tokens().err();
+    let parsed = match options.root.as_str() {
+        // Only a function definition is linted.
+        "FunctionDefinition" => parse_all::<FunctionDefinition>(&mut parse_buffer)
+            .map(|function_definition| {
+                let warnings = lint_with(&function_definition, LintConfig { max_statements: options.max_statements, max_parameters: options.max_parameters });
+                (Box::new(function_definition) as Box<dyn ParseDisplay>, warnings)
+            }),
+        root => parse_rule_all(root, &mut parse_buffer).map(|parsed| (parsed, vec![])),
+    };
+    // A blank input is an error of its own (rather than a malformed root
+    // structure), unless the root can be empty (ex. an `ExpressionList`).
+    let parsed = parsed.map_err(|err| blank.unwrap_or(err));
+
+    // Where the parser backtracked from the furthest, whether it parsed or not.
+    #[cfg(feature = "profile")]
+    if options.reach {
+        if let Some(reach) = parse_buffer.describe_deepest_reach() {
+            eprintln!("{reach}");
+        }
+    }
 
-    // Expect a function definition as the root structure. Try to parse it.
-    match FunctionDefinition::parse(&mut parse_buffer) {
+    // Only the exit code matters when checking: the innermost error is its one line.
+    if options.check {
+        if let Err(err) = parsed {
+            eprintln!("PARSE ERROR: {}", err.lines().last().unwrap_or_default().trim());
+            process::exit(1);
+        }
+        return;
+    }
+
+    match parsed {
         // PARSE SUCCESS! Print it out!
-        Ok(function_definition) => {
-            function_definition.display(0, None);
+        Ok((parsed, warnings)) => {
+            let config = DisplayConfig {
+                sort_parameters: options.sort_parameters,
+                base_depth: options.base_indent,
+                max_signature_len: options.max_signature_len,
+                ..Default::default()
+            };
+            print!("{}", parsed.render_as_with(options.format, config));
+
+            // It parsed, but it may still be mistaken.
+            for warning in warnings {
+                eprintln!("WARNING: {warning}");
+            }
         },
 
         // Something is wrong...
@@ -23,7 +180,10 @@ fn main() {
         // Hopefully the input text!
         Err(err) => {
             eprintln!("PARSE ERROR:");
-            eprintln!("{err}");
+            match options.verbose {
+                true => eprintln!("{err}"),
+                false => eprintln!("{}", short_error(&err)),
+            }
             process::exit(1);
         },
     }
@@ -2,7 +2,9 @@
 //! 
 //! This library stores the "modular" tokens.
 //! 
-//! This inludes `Delimited` and `Terminated`.
+//! This inludes `Delimited` and `Terminated` (and `LineDelimited`, a
+//! `Delimited` by either a comma or a line break), along with `Repeated`, a
+//! fixed number of items.
 //! 
 //! These types abstract-away a particular type
 //! of BNF implementation.
@@ -25,13 +27,16 @@
 //! 
 //! Where `e` and `d` are each the `Expected` item in the list and the `Delimiter` of the list.
 
-use std::{
-    io::Write, // Used with the `writeln!` and `write!` macros. Similar to sprintf in c.
-    slice::Iter // The standard iterator type over slices.
-};
+// The signatures are built with `extend(_.chars())`, as in `non_terminals`.
+#![allow(clippy::string_extend_chars)]
+
+use std::slice::Iter; // The standard iterator type over slices.
 
 use crate::{
-    make_indent,
+    display::{truncate_signature, TreeWriter},
+    either::Either,
+    owned::IntoOwned,
+    terminals::{Comma, Newline},
     Parse,
     ParseDisplay
 };
@@ -48,9 +53,10 @@ use crate::{
 /// ```
 /// 
 /// #### Object Structure
-/// ```
+/// ```ignore
 /// pub struct Delimited<Expected: Parse, Delimiter: Parse> {
-///     items: Vec<(Expected, Option<Delimiter>)>
+///     items: Vec<(Expected, Option<Delimiter>)>,
+///     position: usize,
 /// }
 /// ```
 /// 
@@ -59,55 +65,108 @@ use crate::{
 /// 
 /// If it is non-empty, then only the very last tuple of the list will contain
 /// `None`, rather than `Some`. This implementation guarentees it.
-#[derive(Clone)]
+/// 
+/// ##### `position: usize`
+/// The position the list was parsed at (see `ParseBuffer::position`), which
+/// is where an empty list is (see `ParseDisplay::span`).
+#[derive(Clone, PartialEq)]
 pub struct Delimited<Expected: Parse, Delimiter: Parse> {
-    items: Vec<(Expected, Option<Delimiter>)>
+    items: Vec<(Expected, Option<Delimiter>)>,
+    position: usize,
 }
 impl<E: Parse, D: Parse> Delimited<E, D> {
     /// A getter to the delimited items.
     pub fn items(&self) -> &Vec<(E, Option<D>)> {
         &self.items
     }
-}
-impl<'d, E: Parse, D: Parse> IntoIterator for &'d Delimited<E, D> {
-    type Item = &'d (E, Option<D>);
 
-    type IntoIter = Iter<'d, (E, Option<D>)>;
+    /// If there are no delimited items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.items.iter() // get the iterator directly from the internal items
+    /// Takes the delimited items out of the list (see the `From` impl to put them back).
+    pub fn into_items(self) -> Vec<(E, Option<D>)> {
+        self.items
     }
-}
-/// DO NOT USE THIS UNLESS YOU KNOW WHAT YOU ARE DOING!
-/// 
-/// To use this safely, you must guarentee that:
-/// - for all items in the list, only the last contains `None` as the tuple's second variant.
-impl<E: Parse, D: Parse> From<Vec<(E, Option<D>)>> for Delimited<E, D> {
-    fn from(items: Vec<(E, Option<D>)>) -> Self {
-        Delimited {
-            items
+
+    /// If only the very last item has no delimiter (or there are no items at
+    /// all), as every parsed list does.
+    /// 
+    /// A list built by hand (see the `From` impl) may not be.
+    pub fn is_well_formed(&self) -> bool {
+        Self::first_malformed(&self.items).is_none()
+    }
+
+    /// Builds a list from its delimited items, like the `From` impl, but
+    /// returns an error if they are not well-formed (see `is_well_formed`).
+    pub fn try_from_items(items: Vec<(E, Option<D>)>) -> Result<Self, String> {
+        match Self::first_malformed(&items) {
+            Some(i) if i + 1 == items.len() => Err(format!("The last item (item {}) of a `{}` has a delimiter after it", i + 1, Self::parse_label()))?,
+            Some(i) => Err(format!("Item {} of a `{}` has no delimiter after it, but only the last item can be without one", i + 1, Self::parse_label()))?,
+            None => Ok(items.into()),
         }
     }
-}
-impl<E: Parse, D: Parse> Parse for Delimited<E, D> {
-    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+
+    /// The index of the first item which breaks the structure's guarantee: a
+    /// delimiter after the last item, or none after any other item.
+    fn first_malformed(items: &[(E, Option<D>)]) -> Option<usize> {
+        let last = items.len().saturating_sub(1);
+        items.iter().enumerate().position(|(i, (_, maybe_d))| maybe_d.is_none() != (i == last))
+    }
+
+    /// A copy of the list with its items sorted (stably) by some key.
+    /// 
+    /// Only the items are reordered: each delimiter stays where it was, so the
+    /// last item is still the only one without a delimiter.
+    pub fn sorted_by_key<K: Ord>(&self, key: impl FnMut(&E) -> K) -> Self
+    where
+        E: Clone,
+        D: Clone,
+    {
+        let mut expected = self.items.iter().map(|(e, _)| e.clone()).collect::<Vec<_>>();
+        expected.sort_by_key(key);
+        let items = expected.into_iter()
+            .zip(self.items.iter().map(|(_, d)| d.clone()))
+            .collect::<Vec<_>>();
+        Delimited { items, position: self.position }
+    }
+
+    /// Parses like `parse`, for a list always followed by `F` (ex. the
+    /// `RightParen` after the function parameters).
+    /// 
+    /// A delimiter right before `F` (ex. the trailing comma of `f(int x,)`) is
+    /// still an error, but one naming the trailing delimiter, rather than the
+    /// item missing after it.
+    pub fn parse_followed_by<F: Parse>(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+        Self::parse_until(buffer, |buffer| F::parse(&mut buffer.fork()).is_ok())
+    }
+
+    /// The shared implementation of `parse` and `parse_followed_by`, where
+    /// `at_follow` is if the list is followed by what follows it here.
+    fn parse_until(buffer: &mut crate::ParseBuffer, at_follow: impl Fn(&mut crate::ParseBuffer) -> bool) -> Result<Self, String> {
         // INITIALIZATION
         let mut items = vec![];
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let position = fork.position();
 
         // ATTEMPT TO GET THE FIRST EXPECTED
         //
         // Empty list is a success or no delimiter is a success.
         let e = match E::parse(&mut fork) {
             Ok(e) => e,
-            Err(_) => return Ok(items.into()),
+            Err(_) => {
+                fork.check_nesting()?;
+                return Ok(Delimited { items, position });
+            },
         };
         match D::parse(&mut fork) {
             Ok(d) => items.push((e, Some(d))),
             Err(_) => {
+                fork.check_nesting()?;
                 items.push((e, None));
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                return Ok(items.into());
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Delimited { items, position });
             },
         }
 
@@ -117,13 +176,17 @@ impl<E: Parse, D: Parse> Parse for Delimited<E, D> {
             let e = match E::parse(&mut fork) {
                 Ok(e) => e,
                 Err(err) => {
-                    // construct error message
-                    let mut err_msg = Vec::new();
-                    writeln!(&mut err_msg, "While parsing {}...", Self::parse_label()).unwrap();
-                    write!(&mut err_msg, "    {err}").unwrap();
+                    // a delimiter right before the follow is a trailing delimiter, rather than a missing item
+                    let err = match items.last() {
+                        Some((_, Some(d))) if at_follow(&mut fork) => {
+                            let follow = fork.peek().map(|(_, lexeme)| lexeme.clone()).unwrap_or_default();
+                            format!("Unexpected trailing `{}` before `{follow}`, remove the trailing delimiter", d.lexeme_signature())
+                        },
+                        _ => err,
+                    };
 
-                    // return error
-                    return Err(String::from_utf8(err_msg).unwrap());
+                    // construct and return the error message
+                    return Err(buffer.context_error(Self::parse_label(), err));
                 },
             };
 
@@ -131,14 +194,135 @@ impl<E: Parse, D: Parse> Parse for Delimited<E, D> {
             match D::parse(&mut fork) {
                 Ok(d) => items.push((e, Some(d))),
                 Err(_) => {
+                    fork.check_nesting()?;
                     items.push((e, None));
-                    *buffer = fork; // parse was successful: setting the buffer to the fork
-                    return Ok(items.into());
+                    buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                    return Ok(Delimited { items, position });
                 },
             }
         }
     }
-    
+
+    /// Parses like `parse`, but recovering from items which fail to parse,
+    /// rather than failing the whole list.
+    /// 
+    /// The error of a failed item is recorded, and every token up to the next
+    /// delimiter is skipped, to continue with the next item. A bracketed group
+    /// (ex. the `(b, c)` of `a(b, c)`) is skipped as a whole, so neither a
+    /// delimiter nor the follow within it ends the skip. A failed item is
+    /// dropped from the list, along with its delimiter (or the delimiter
+    /// before it, if it was the last item). An item followed by anything other
+    /// than a delimiter is kept, but what follows it is skipped all the same.
+    /// 
+    /// `F` is what follows the list (ex. the `RightParen` after the function
+    /// parameters), which is never skipped: the list ends at `F`, or at the
+    /// end of the input, if there is no delimiter before either.
+    pub fn parse_recovering<F: Parse>(buffer: &mut crate::ParseBuffer) -> (Self, Vec<String>) {
+        /// If the list can end here: at its follow, or at the end of the input.
+        fn at_end<F: Parse>(buffer: &mut crate::ParseBuffer) -> bool {
+            buffer.peek().is_none() || F::parse(&mut buffer.fork()).is_ok()
+        }
+
+        let mut items = vec![];
+        let mut errors = vec![];
+        let position = crate::ParseBuffer::position(buffer); // not `Iterator::position` of the `&mut` buffer
+        loop {
+            // Empty list is a success
+            if items.is_empty() && errors.is_empty() && at_end::<F>(buffer) {
+                break;
+            }
+
+            // EXPECT THE EXPECTED, THEN ITS DELIMITER
+            let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+            let err = match E::parse(&mut fork) {
+                Ok(e) => {
+                    buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                    let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+                    match D::parse(&mut fork) {
+                        Ok(d) => {
+                            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                            items.push((e, Some(d)));
+                            continue;
+                        },
+                        Err(err) => {
+                            items.push((e, None));
+                            // no delimiter is a success, if this is the end of the list
+                            if at_end::<F>(buffer) {
+                                break;
+                            }
+                            err
+                        },
+                    }
+                },
+                Err(err) => err,
+            };
+            errors.push(err);
+
+            // SKIP TO THE NEXT DELIMITER
+            loop {
+                if at_end::<F>(buffer) {
+                    // the failed item was the last one: the delimiter before it is dropped
+                    if let Some((_, d)) = items.last_mut() {
+                        *d = None;
+                    }
+                    return (Delimited { items, position }, errors);
+                }
+
+                let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+                if let Ok(d) = D::parse(&mut fork) {
+                    buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                    // an item followed by something else keeps the delimiter after it
+                    if let Some((_, last_d @ None)) = items.last_mut() {
+                        *last_d = Some(d);
+                    }
+                    break;
+                }
+                buffer.skip_bracketed(); // a delimiter (or the follow) within brackets is not the list's own
+            }
+        }
+
+        (Delimited { items, position }, errors)
+    }
+}
+impl<'d, E: Parse, D: Parse> IntoIterator for &'d Delimited<E, D> {
+    type Item = &'d (E, Option<D>);
+
+    type IntoIter = Iter<'d, (E, Option<D>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter() // get the iterator directly from the internal items
+    }
+}
+/// DO NOT USE THIS UNLESS YOU KNOW WHAT YOU ARE DOING!
+/// 
+/// To use this safely, you must guarentee that:
+/// - for all items in the list, only the last contains `None` as the tuple's second variant.
+/// 
+/// A list breaking this is never a panic, but displays exactly as its items are
+/// (ex. `a b` for two items without a delimiter between them). See
+/// `Delimited::try_from_items` to check the items instead.
+/// 
+/// The list is at the position of its first item, or at `0` if it is empty.
+impl<E: Parse, D: Parse> From<Vec<(E, Option<D>)>> for Delimited<E, D> {
+    fn from(items: Vec<(E, Option<D>)>) -> Self {
+        let position = list_position(items.first().map(|(e, _)| e as &dyn ParseDisplay));
+        Delimited {
+            items,
+            position,
+        }
+    }
+}
+impl<E: Parse + IntoOwned, D: Parse + IntoOwned> IntoOwned for Delimited<E, D> {
+    fn into_owned(self) -> Self {
+        let items = self.items.into_iter().map(|(e, maybe_d)| (e.into_owned(), maybe_d.into_owned()));
+        Delimited { items: items.collect(), position: self.position }
+    }
+}
+impl<E: Parse, D: Parse> Parse for Delimited<E, D> {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+        Self::parse_until(buffer, |_| false)
+    }
+
     fn parse_label() -> String {
         format!("Delimited Sequence of `{}` by `{}`", E::parse_label(), D::parse_label())
     }
@@ -149,47 +333,72 @@ where
     D: Parse
 {
     /// Label is recommended...
-    fn display(&self, depth: usize, label: Option<String>) {
-        let indent = make_indent(depth);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
         let label = label.unwrap_or(Self::parse_label());
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
+
+        // an empty list would otherwise display as a label with nothing after it
+        if self.is_empty() {
+            out.line(depth, &label, Some("(empty)".into()));
+            return;
+        }
+
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
 
         for (e, _d) in self {
-            e.display(depth+1, None);
+            e.display_to(out, depth+1, None);
         }
     }
 
     fn lexeme_signature(&self) -> String {
         let mut sigg = String::new();
         
-        let mut iter = self.items.iter().peekable();
-        
-        // if the list is empty, return the empty string
-        if iter.peek().is_none() {
-            return "".into();
-        }
-        
-        // otherwise, list out all of the tokens, leveraging assumptions made about the structure of the items
-        loop {
-            let (e, maybe_d) = iter.next().unwrap();
-            
+        let mut iter = self.into_iter().peekable(); // a raw *peekable* iterator over the items
+        while let Some((e, maybe_d)) = iter.next() {
+            // each item is followed by its delimiter, if any; a list built by hand
+            // (see the `From` impl) may break the structure's guarentee, but is
+            // still listed out as it is, rather than panicking
             sigg.extend(e.lexeme_signature().chars());
-            
             if let Some(d) = maybe_d {
-                assert!(iter.peek().is_some()); // guarentees we must adhere to
                 sigg.extend(d.lexeme_signature().chars());
+            }
+            
+            // only if there will be a next item, include a space
+            if iter.peek().is_some() {
                 sigg.extend(" ".chars());
-            } else {
-                assert!(iter.peek().is_none()); // guarentees we must adhere to
-                break; // No more items, exit out of loop
             }
         }
-
         sigg
     }
+
+    fn short_signature(&self, max_len: usize) -> String {
+        let items = self.items.iter().map(|(e, maybe_d)| (e as &dyn ParseDisplay, maybe_d.as_ref().map(|d| d as &dyn ParseDisplay)));
+        short_list_signature(items, max_len)
+    }
+
+    fn span(&self) -> Option<(usize, usize)> {
+        list_span(self.children(), self.position)
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![];
+        for (e, d) in self {
+            children.push(e);
+            if let Some(d) = d {
+                children.push(d);
+            }
+        }
+        children
+    }
 }
 
+/// A list of items, each delimited by either a comma or a line break.
+/// 
+/// Line breaks are only in the token stream of a lexer that keeps them (see
+/// `q1_lib::lexer::Lexer::with_newlines`), otherwise this is the same as
+/// `Delimited<E, Comma>`.
+pub type LineDelimited<E> = Delimited<E, Either<Comma, Newline>>;
+
 /// Parses expecting a list of items, each terminated by a delimiter.
 /// 
 /// This struct completely encapsulates the implementation of the following BNF
@@ -200,23 +409,90 @@ where
 /// ```
 /// 
 /// #### Object Structure
-/// ```
+/// ```ignore
 /// pub struct Terminated<Expected: Parse, Delimiter: Parse> {
 ///     items: Vec<(Expected, Delimiter)>,
+///     position: usize,
 /// }
 /// ```
 /// 
 /// ##### `items: Vec<(Expected, Delimiter)>`
 /// This will be a list of objects, which can be empty.
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Terminated<Expected: Parse, Delimiter: Parse> {
     items: Vec<(Expected, Delimiter)>,
+    position: usize,
 }
-impl<'t, E: Parse, D: Parse> Terminated<E, D> {
+impl<E: Parse, D: Parse> Terminated<E, D> {
     /// A getter for the terminating items
     pub fn items(&self) -> &Vec<(E, D)> {
         &self.items
     }
+
+    /// If there are no terminated items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Takes the terminated items out of the list (see the `From` impl to put them back).
+    pub fn into_items(self) -> Vec<(E, D)> {
+        self.items
+    }
+}
+impl<E: Parse, D: Parse> Terminated<E, D> {
+    /// Parses like `parse`, for a list always followed by `F` (ex. the
+    /// `RightCurly` after the statements of a block).
+    /// 
+    /// A delimiter where an item was expected (ex. the second `;` of `x = 1;;`)
+    /// ends the list early, right before it. If `F` then does not follow, that
+    /// is an error naming the empty item, rather than the delimiter left to be
+    /// reported as something unexpected after the list.
+    pub fn parse_followed_by<F: Parse>(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let terminated = Self::parse(&mut fork)?;
+        if F::parse(&mut fork.fork()).is_err() {
+            if let Some(err) = Self::empty_item(&fork) {
+                return Err(buffer.context_error(Self::parse_label(), err));
+            }
+        }
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        Ok(terminated)
+    }
+
+    /// The error of a delimiter where an item was expected, if there is one.
+    fn empty_item(buffer: &crate::ParseBuffer) -> Option<String> {
+        let mut fork = buffer.fork();
+        let d = D::parse(&mut fork).ok()?;
+        Some(format!(
+            "Unexpected `{}` where a `{}` was expected, an empty `{}` is not allowed",
+            d.lexeme_signature(),
+            E::parse_label(),
+            E::parse_label(),
+        ))
+    }
+
+    /// The error of a delimiter missing after an item, naming the item.
+    /// 
+    /// Only built once the delimiter failed, and never in place of the error
+    /// which ended the tokens early (ex. a lexical error), which the delimiter
+    /// failed with.
+    fn missing_delimiter(e: &E, buffer: &mut crate::ParseBuffer) -> String {
+        if let Some(err) = buffer.early_end_error() {
+            return err;
+        }
+
+        // the innermost node (or terminal) that is the whole item names it best
+        let mut item = e.to_owned_tree();
+        while item.signature.is_none() && item.children.len() == 1 {
+            item = item.children.remove(0);
+        }
+        let item = match item.children.is_empty() {
+            true => format!("`{}`", item.signature.unwrap_or_default()),
+            false => format!("the {} `{}`", item.label, item.signature.unwrap_or_default()),
+        };
+        let err = format!("Expected `{}` after {item}, but found {} instead", D::parse_label(), buffer.describe_found());
+        buffer.explain_found(err)
+    }
 }
 impl<'t, E: Parse, D: Parse> IntoIterator for &'t Terminated<E, D> {
     type Item = &'t (E, D);
@@ -228,58 +504,51 @@ impl<'t, E: Parse, D: Parse> IntoIterator for &'t Terminated<E, D> {
     }
 }
 /// Would not recommend using, but fine nonetheless
+/// 
+/// The list is at the position of its first item, or at `0` if it is empty.
 impl<E: Parse, D: Parse> From<Vec<(E, D)>> for Terminated<E, D> {
     fn from(items: Vec<(E, D)>) -> Self {
+        let position = list_position(items.first().map(|(e, _)| e as &dyn ParseDisplay));
         Terminated {
             items,
+            position,
         }
     }
 }
+impl<E: Parse + IntoOwned, D: Parse + IntoOwned> IntoOwned for Terminated<E, D> {
+    fn into_owned(self) -> Self {
+        let items = self.items.into_iter().map(|(e, d)| (e.into_owned(), d.into_owned()));
+        Terminated { items: items.collect(), position: self.position }
+    }
+}
 impl<E: Parse, D: Parse> Parse for Terminated<E, D> {
     fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
         // INITALIZATION
         let mut items = vec![];
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-
-        // ATTEMPT TO GET THE FIRST EXPECTED AND DELIMITED
-        // Empty list (no first expected) is a success
-        let e = match E::parse(&mut fork) {
-            Ok(e) => e,
-            Err(_) => return Ok(items.into()),
-        };
-        match D::parse(&mut fork) {
-            Ok(d) => items.push((e, d)),
-            Err(err) => {
-                let mut err_msg = Vec::new();
-                writeln!(&mut err_msg, "While parsing {}...", Self::parse_label()).unwrap();
-                write!(&mut err_msg, "    {err}").unwrap();
-                return Err(String::from_utf8(err_msg).unwrap());
-            },
-        }
+        let position = fork.position();
 
         // CONSUME UNTIL SATISFIED
         loop {
             // ATTEMPT TO GET THE NEXT EXPECTED AND DELIMITED
-            // Return at first failed expected,
+            // Return at first failed expected (an empty list is a success),
             // but error at first failed delimiter
             let e = match E::parse(&mut fork) {
                 Ok(e) => e,
                 Err(_) => return {
-                    *buffer = fork; // parse was successful: setting the buffer to the fork
-                    Ok(items.into())
+                    fork.check_nesting()?;
+                    buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                    Ok(Terminated { items, position })
                 },
             };
             match D::parse(&mut fork) {
                 Ok(d) => items.push((e, d)), // store, and parse again
                 
                 // a delimiter is non-optional: failure at first parse
-                Err(err) => {
+                Err(_) => {
                     // create the error message
-                    let mut err_msg = Vec::new();
-                    writeln!(&mut err_msg, "While parsing {}...", Self::parse_label()).unwrap();
-                    write!(&mut err_msg, "    {err}").unwrap();
-                    
-                    return Err(String::from_utf8(err_msg).unwrap());
+                    let err = Self::missing_delimiter(&e, &mut fork);
+                    return Err(buffer.context_error(Self::parse_label(), err));
                 },
             }
         }
@@ -295,15 +564,21 @@ where
     D: Parse
 {
     /// A label is recommended...
-    fn display(&self, depth: usize, label: Option<String>) {
-        let indent = make_indent(depth);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
         let label = label.unwrap_or(Self::parse_label());
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
+
+        // an empty list would otherwise display as a label with nothing after it
+        if self.is_empty() {
+            out.line(depth, &label, Some("(empty)".into()));
+            return;
+        }
+
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
 
         // displays each expected item, ignoring the delimiter as redundant
         for (e, _d) in self {
-            e.display(depth+1, None);
+            e.display_to(out, depth+1, None);
         }
     }
 
@@ -323,4 +598,192 @@ where
         }
         sigg
     }
-}
\ No newline at end of file
+
+    fn short_signature(&self, max_len: usize) -> String {
+        let items = self.items.iter().map(|(e, d)| (e as &dyn ParseDisplay, Some(d as &dyn ParseDisplay)));
+        short_list_signature(items, max_len)
+    }
+
+    fn full_signature(&self) -> String {
+        let mut sigg = String::new();
+        
+        let mut iter = self.into_iter().peekable(); // a raw *peekable* iterator over the items
+        while let Some((e, d)) = iter.next() {
+            // always include the expected and delimited
+            sigg.extend(e.full_signature().chars());
+            sigg.extend(d.full_signature().chars());
+            
+            // only if there will be a next item, include a space
+            if iter.peek().is_some() {
+                sigg.extend(" ".chars());
+            }
+        }
+        sigg
+    }
+
+    fn span(&self) -> Option<(usize, usize)> {
+        list_span(self.children(), self.position)
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![];
+        for (e, d) in self {
+            children.push(e);
+            children.push(d);
+        }
+        children
+    }
+}
+
+/// Parses expecting exactly `N` items in sequence, with nothing between them,
+/// for grammar constructs of a fixed arity.
+/// 
+/// Unlike `Delimited` and `Terminated`, this is never open-ended: fewer than
+/// `N` items is a parse error, and nothing after the `N`th item is parsed.
+/// 
+/// #### Object Structure
+/// ```ignore
+/// pub struct Repeated<Expected: Parse, const N: usize> {
+///     items: Vec<Expected>,
+///     position: usize,
+/// }
+/// ```
+/// 
+/// ##### `items: Vec<Expected>`
+/// This will always be a list of exactly `N` objects.
+/// 
+/// ##### `position: usize`
+/// The position the list was parsed at, as of `Delimited`.
+#[derive(Clone, PartialEq)]
+pub struct Repeated<Expected: Parse, const N: usize> {
+    items: Vec<Expected>,
+    position: usize,
+}
+impl<E: Parse, const N: usize> Repeated<E, N> {
+    /// A getter for the repeated items, of which there are exactly `N`.
+    pub fn items(&self) -> &Vec<E> {
+        &self.items
+    }
+}
+impl<'r, E: Parse, const N: usize> IntoIterator for &'r Repeated<E, N> {
+    type Item = &'r E;
+
+    type IntoIter = Iter<'r, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter() // get the iterator directly from the internal items
+    }
+}
+impl<E: Parse + IntoOwned, const N: usize> IntoOwned for Repeated<E, N> {
+    fn into_owned(self) -> Self {
+        Repeated { items: self.items.into_owned(), position: self.position }
+    }
+}
+impl<E: Parse, const N: usize> Parse for Repeated<E, N> {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+        if N > 0 && buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let position = fork.position();
+        let mut items = Vec::with_capacity(N);
+        for _ in 0..N {
+            // every item is non-optional: failure at the first one missing
+            match E::parse(&mut fork) {
+                Ok(e) => items.push(e),
+                Err(err) => {
+                    return Err(buffer.context_error(Self::parse_label(), err));
+                },
+            }
+        }
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        Ok(Repeated { items, position })
+    }
+
+    fn parse_label() -> String {
+        format!("Repeated Sequence of {N} `{}`", E::parse_label())
+    }
+}
+impl<E: Parse, const N: usize> ParseDisplay for Repeated<E, N> {
+    /// A label is recommended...
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        let label = label.unwrap_or(Self::parse_label());
+
+        // an empty list would otherwise display as a label with nothing after it
+        if self.items.is_empty() {
+            out.line(depth, &label, Some("(empty)".into()));
+            return;
+        }
+
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
+
+        for e in self {
+            e.display_to(out, depth+1, None);
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let signatures = self.items.iter().map(|e| e.lexeme_signature());
+        signatures.collect::<Vec<_>>().join(" ")
+    }
+
+    fn short_signature(&self, max_len: usize) -> String {
+        short_list_signature(self.items.iter().map(|e| (e as &dyn ParseDisplay, None)), max_len)
+    }
+
+    fn full_signature(&self) -> String {
+        let signatures = self.items.iter().map(|e| e.full_signature());
+        signatures.collect::<Vec<_>>().join(" ")
+    }
+
+    fn span(&self) -> Option<(usize, usize)> {
+        list_span(self.children(), self.position)
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        self.items.iter().map(|e| e as &dyn ParseDisplay).collect()
+    }
+}
+
+/// The position of a list built by hand: that of its first item, or `0` if
+/// it has none (or the first item covers no tokens).
+fn list_position(first: Option<&dyn ParseDisplay>) -> usize {
+    first.and_then(|e| e.span()).map_or(0, |(start, _)| start)
+}
+
+/// The span of a list (see `ParseDisplay::span`): that of its items, or the
+/// empty span at its position, if it covers no tokens at all.
+fn list_span(children: Vec<&dyn ParseDisplay>, position: usize) -> Option<(usize, usize)> {
+    let mut spans = children.into_iter().filter_map(|child| child.span());
+    let Some((start, mut end)) = spans.next() else {
+        return Some((position, position));
+    };
+    for (_start, child_end) in spans {
+        end = child_end;
+    }
+    Some((start, end))
+}
+
+/// The signature of a list: each item followed by its delimiter (if any),
+/// joined by spaces, and cut short to `max_len` bytes (see
+/// `ParseDisplay::short_signature`).
+/// 
+/// No item is added once the signature is already cut.
+fn short_list_signature<'l>(items: impl Iterator<Item = (&'l dyn ParseDisplay, Option<&'l dyn ParseDisplay>)>, max_len: usize) -> String {
+    let mut sigg = String::new();
+    for (i, (e, maybe_d)) in items.enumerate() {
+        if sigg.len() > max_len {
+            break;
+        }
+        if i > 0 {
+            sigg.push(' ');
+        }
+        sigg.push_str(&e.short_signature(max_len));
+        if let Some(d) = maybe_d {
+            sigg.push_str(&d.short_signature(max_len));
+        }
+    }
+    truncate_signature(sigg, max_len)
+}
@@ -18,19 +18,35 @@
 //! 
 //! Another abstraction is optionality. If the enum (let's call it `T`) is only expected optionaly,
 //! then the `Parse` trait implementation signature will be
-//! ```
+//! ```ignore
 //! impl Parse<Option<Self>> for T
 //! ```
 //! rather than its usual
-//! ```
+//! ```ignore
 //! impl Parse for T
 //! ```
 //! 
 //! This is to avoid adding an `Empty` variant to each of these enums, and enfore
 //! its optionality in parent composite types.
 
+// The explicit `return`, `extend(_.chars())`, `format!`, and `match` styles
+// are used throughout, to keep each parse implementation reading the same.
+#![allow(
+    clippy::needless_return,
+    clippy::string_extend_chars,
+    clippy::useless_format,
+    clippy::single_match
+)]
+
+use std::collections::HashSet; // The parameters already declared in a function.
+
+use q1_lib::lexer::Token; // The first and last token of an operator or qualifier.
+
 use crate::{
-    make_indent,
+    display::TreeWriter,
+    events::ParseEvent,
+    grammar::Bnf,
+    operators::binary_precedence,
     Parse,
     ParseBuffer,
     ParseDisplay,
@@ -38,14 +54,113 @@ use crate::{
     modulars::*,
 };
 
+/// A Program: every function definition until the end of the input.
+///
+/// This is not part of the grammar (whose root is a single `FunctionDefinition`),
+/// but a unit of analysis over several definitions, such as several parsed files
+/// combined with `Program::merge`.
+#[derive(Clone, PartialEq)]
+pub struct Program {
+    pub function_definitions: Vec<FunctionDefinition>,
+}
+impl Program {
+    /// Appends the function definitions of `other` after those of `self`.
+    pub fn merge(mut self, other: Program) -> Program {
+        self.function_definitions.extend(other.function_definitions);
+        self
+    }
+
+    /// The same as `merge`, but fails if both programs define a function of the same name.
+    pub fn try_merge(self, other: Program) -> Result<Program, String> {
+        let names = self.function_names();
+        if let Some(clash) = other.function_names().into_iter().find(|name| names.contains(name)) {
+            Err(format!("The function `{clash}` is defined in both programs"))?
+        }
+        Ok(self.merge(other))
+    }
+
+    /// The name of every function definition, in order.
+    pub fn function_names(&self) -> Vec<&str> {
+        self.function_definitions
+            .iter()
+            .map(|function_definition| function_definition.function_name.lexeme_str())
+            .collect()
+    }
+
+    /// Every statement of every function body, in source order.
+    ///
+    /// This only yields the statements directly in each body, and not those
+    /// nested within them (ex. in the block of an if statement).
+    pub fn statements(&self) -> impl Iterator<Item = &Statement> {
+        self.function_definitions
+            .iter()
+            .flat_map(|function_definition| function_definition.compound_statements.items())
+            .map(|(statement, _)| statement)
+    }
+}
+impl Parse for Program {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("Program"); // this is to make parse attempts without modifying the original buffer
+        let mut function_definitions = vec![];
+        while fork.peek().is_some() {
+            function_definitions.push(FunctionDefinition::parse(&mut fork)?);
+        }
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(Program { function_definitions });
+    }
+
+    fn parse_label() -> String {
+        format!("Program")
+    }
+}
+impl ParseDisplay for Program {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Program";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        for function_definition in &self.function_definitions {
+            function_definition.display_to(out, depth+1, None);
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let signatures = self.function_definitions
+            .iter()
+            .map(|function_definition| function_definition.lexeme_signature())
+            .collect::<Vec<_>>();
+        signatures.join(" ")
+    }
+
+    fn full_signature(&self) -> String {
+        let signatures = self.function_definitions
+            .iter()
+            .map(|function_definition| function_definition.full_signature())
+            .collect::<Vec<_>>();
+        signatures.join(" ")
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        self.function_definitions
+            .iter()
+            .map(|function_definition| function_definition as &dyn ParseDisplay)
+            .collect()
+    }
+}
+
 /// A Function Definition
 /// 
 /// # BNF
 /// ```text
-/// <FUNCTION DEFINITION> -> type identifier (<FUNCTION PARAMETERS>){<COMPOUND STATEMENTS>}
+/// <FUNCTION DEFINITION> -> <QUALIFIERS>type identifier (<FUNCTION PARAMETERS>){<COMPOUND STATEMENTS>}
 /// ``` 
-#[derive(Clone)] // We cannot derive `Copy` due to modulars, but we can clone
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to modulars, but we can clone
 pub struct FunctionDefinition {
+    pub qualifiers: Vec<Qualifier>,
     pub type_: Type,
     pub function_name: Identifier,
     pub left_paren: LeftParen,
@@ -55,24 +170,42 @@ pub struct FunctionDefinition {
     pub compound_statements: CompoundStatements,
     pub right_curly: RightCurly,
 }
+impl Bnf for FunctionDefinition {
+    fn bnf() -> &'static str {
+        "<FUNCTION DEFINITION> -> <QUALIFIERS>type identifier (<FUNCTION PARAMETERS>){<COMPOUND STATEMENTS>}"
+    }
+}
 impl Parse for FunctionDefinition {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut fork = buffer.fork_node("FunctionDefinition"); // this is to make parse attempts without modifying the original buffer
+        let qualifiers = parse_qualifiers(&mut fork);
+        let type_ = Type::parse(&mut fork)?;
+        let function_name = Identifier::parse(&mut fork)?;
+        let left_paren = LeftParen::parse(&mut fork)?;
+        fork.enter_scope(); // the parameters are only declared within the function
+        let parameters = fork.parse_node("FunctionParameters", FunctionParameters::parse_followed_by::<RightParen>)?;
+        check_parameters(buffer, &parameters, &function_name)?;
+        let right_paren = parse_closing(&mut fork, &left_paren.lexeme, left_paren.position)?;
+        let left_curly = LeftCurly::parse(&mut fork)?;
+        let compound_statements = fork.parse_node("CompoundStatements", CompoundStatements::parse_followed_by::<RightCurly>)?;
+        let right_curly = parse_body_closing(&mut fork, &left_curly)?;
+        fork.exit_scope();
         let function_parameter = FunctionDefinition {
-            type_: Type::parse(&mut fork)?,
-            function_name: Identifier::parse(&mut fork)?,
-            left_paren: LeftParen::parse(&mut fork)?,
-            parameters: FunctionParameters::parse(&mut fork)?,
-            right_paren: RightParen::parse(&mut fork)?,
-            left_curly: LeftCurly::parse(&mut fork)?,
-            compound_statements: CompoundStatements::parse(&mut fork)?,
-            right_curly: RightCurly::parse(&mut fork)?
+            qualifiers,
+            type_,
+            function_name,
+            left_paren,
+            parameters,
+            right_paren,
+            left_curly,
+            compound_statements,
+            right_curly,
         };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
         return Ok(function_parameter);
     }
 
@@ -80,38 +213,182 @@ impl Parse for FunctionDefinition {
         format!("Function Definition")
     }
 }
-impl ParseDisplay for FunctionDefinition {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
+impl FunctionDefinition {
+    /// Displays the definition exactly as parsed, regardless of `DisplayConfig::sort_parameters`.
+    fn display_as_parsed(&self, out: &mut TreeWriter, depth: usize) {
         let label = "Function Definition";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        display_qualifiers(&self.qualifiers, out, depth+1, "Function Return Qualifiers");
+        self.type_.display_to(out, depth+1, Some("Funtion Return Type".into()));
+        self.function_name.display_to(out, depth+1, Some("Function Identifier".into()));
+        self.left_paren.display_to(out, depth+1, Some("Left Paren".into()));
+        self.parameters.display_to(out, depth+1, Some("Function Parameters".into()));
+        self.right_paren.display_to(out, depth+1, Some("Right Paren".into()));
+        self.left_curly.display_to(out, depth+1, Some("Left Curly".into()));
+        self.compound_statements.display_to(out, depth+1, Some("Compound Statements".into()));
+        self.right_curly.display_to(out, depth+1, Some("Right Curly".into()));
+    }
+}
+impl ParseDisplay for FunctionDefinition {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        if !out.config().sort_parameters {
+            return self.display_as_parsed(out, depth);
+        }
 
-        self.type_.display(depth+1, Some("Funtion Return Type".into()));
-        self.function_name.display(depth+1, Some("Function Identifier".into()));
-        self.left_paren.display(depth+1, Some("Left Paren".into()));
-        self.parameters.display(depth+1, Some("Function Parameters".into()));
-        self.right_paren.display(depth+1, Some("Right Paren".into()));
-        self.left_curly.display(depth+1, Some("Left Curly".into()));
-        self.compound_statements.display(depth+1, Some("Compound Statements".into()));
-        self.right_curly.display(depth+1, Some("Right Curly".into()));
+        // only a displayed copy is sorted, never the parsed tree itself
+        let sorted = FunctionDefinition {
+            parameters: self.parameters.sorted_by_key(|parameter| parameter.identifier.lexeme.clone()),
+            ..self.clone()
+        };
+        sorted.display_as_parsed(out, depth);
     }
 
     fn lexeme_signature(&self) -> String {
-        let mut sigg = String::new();
-        sigg.extend(self.type_.lexeme_signature().chars());
+        let mut sigg = qualifiers_signature(&self.qualifiers, Qualifier::lexeme_signature);
+        sigg.push_str(self.type_.lexeme_str());
         sigg.extend(" ".chars());
-        sigg.extend(self.function_name.lexeme_signature().chars());
+        sigg.push_str(self.function_name.lexeme_str());
         sigg.extend(" ".chars());
-        sigg.extend(self.left_paren.lexeme_signature().chars());
+        sigg.push_str(self.left_paren.lexeme_str());
         sigg.extend(self.parameters.lexeme_signature().chars());
-        sigg.extend(self.right_paren.lexeme_signature().chars());
+        sigg.push_str(self.right_paren.lexeme_str());
         sigg.extend(" ".chars());
-        sigg.extend(self.left_curly.lexeme_signature().chars());
+        sigg.push_str(self.left_curly.lexeme_str());
         sigg.extend("....".chars());
-        sigg.extend(self.right_curly.lexeme_signature().chars());
+        sigg.push_str(self.right_curly.lexeme_str());
+        sigg
+    }
+
+    fn full_signature(&self) -> String {
+        let mut sigg = qualifiers_signature(&self.qualifiers, Qualifier::full_signature);
+        sigg.push_str(self.type_.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.function_name.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.left_paren.lexeme_str());
+        sigg.extend(self.parameters.full_signature().chars());
+        sigg.push_str(self.right_paren.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.left_curly.lexeme_str());
+        sigg.extend(self.compound_statements.full_signature().chars());
+        sigg.push_str(self.right_curly.lexeme_str());
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = self.qualifiers.iter().map(|qualifier| qualifier as &dyn ParseDisplay).collect();
+        children.extend([&self.type_ as &dyn ParseDisplay, &self.function_name, &self.left_paren, &self.parameters, &self.right_paren, &self.left_curly, &self.compound_statements, &self.right_curly]);
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// The signature of a Function Definition: everything but its body, which is
+/// skipped rather than parsed (ex. to quickly index every function of a file).
+/// 
+/// This is not part of the grammar. The body is only checked to have balanced
+/// curlies, so a signature can be parsed from a definition whose body would
+/// fail to parse.
+/// 
+/// # BNF
+/// ```text
+/// <FUNCTION SIGNATURE> -> <QUALIFIERS>type identifier (<FUNCTION PARAMETERS>){....}
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to modulars, but we can clone
+pub struct FunctionSignature {
+    pub qualifiers: Vec<Qualifier>,
+    pub type_: Type,
+    pub function_name: Identifier,
+    pub left_paren: LeftParen,
+    pub parameters: FunctionParameters,
+    pub right_paren: RightParen,
+    pub left_curly: LeftCurly,
+    pub right_curly: RightCurly,
+}
+impl Bnf for FunctionSignature {
+    fn bnf() -> &'static str {
+        "<FUNCTION SIGNATURE> -> <QUALIFIERS>type identifier (<FUNCTION PARAMETERS>){....}"
+    }
+}
+impl Parse for FunctionSignature {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("FunctionSignature"); // this is to make parse attempts without modifying the original buffer
+        let qualifiers = parse_qualifiers(&mut fork);
+        let type_ = Type::parse(&mut fork)?;
+        let function_name = Identifier::parse(&mut fork)?;
+        let left_paren = LeftParen::parse(&mut fork)?;
+        fork.enter_scope(); // the parameters are only declared within the function
+        let parameters = fork.parse_node("FunctionParameters", FunctionParameters::parse_followed_by::<RightParen>)?;
+        check_parameters(buffer, &parameters, &function_name)?;
+        let right_paren = parse_closing(&mut fork, &left_paren.lexeme, left_paren.position)?;
+        let left_curly = LeftCurly::parse(&mut fork)?;
+        fork.skip_to_closing_curly(); // the body is never parsed
+        let right_curly = parse_body_closing(&mut fork, &left_curly)?;
+        fork.exit_scope();
+        let function_signature = FunctionSignature {
+            qualifiers,
+            type_,
+            function_name,
+            left_paren,
+            parameters,
+            right_paren,
+            left_curly,
+            right_curly,
+        };
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(function_signature);
+    }
+
+    fn parse_label() -> String {
+        format!("Function Signature")
+    }
+}
+impl ParseDisplay for FunctionSignature {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Function Signature";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        display_qualifiers(&self.qualifiers, out, depth+1, "Function Return Qualifiers");
+        self.type_.display_to(out, depth+1, Some("Funtion Return Type".into()));
+        self.function_name.display_to(out, depth+1, Some("Function Identifier".into()));
+        self.left_paren.display_to(out, depth+1, Some("Left Paren".into()));
+        self.parameters.display_to(out, depth+1, Some("Function Parameters".into()));
+        self.right_paren.display_to(out, depth+1, Some("Right Paren".into()));
+        self.left_curly.display_to(out, depth+1, Some("Left Curly".into()));
+        self.right_curly.display_to(out, depth+1, Some("Right Curly".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = qualifiers_signature(&self.qualifiers, Qualifier::lexeme_signature);
+        sigg.push_str(self.type_.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.function_name.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.left_paren.lexeme_str());
+        sigg.extend(self.parameters.lexeme_signature().chars());
+        sigg.push_str(self.right_paren.lexeme_str());
         sigg
     }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = self.qualifiers.iter().map(|qualifier| qualifier as &dyn ParseDisplay).collect();
+        children.extend([&self.type_ as &dyn ParseDisplay, &self.function_name, &self.left_paren, &self.parameters, &self.right_paren, &self.left_curly, &self.right_curly]);
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
 }
 
 /// A delimited list by Comma of Function Parameter
@@ -124,6 +401,16 @@ impl ParseDisplay for FunctionDefinition {
 ///                         | ε
 /// ```
 pub type FunctionParameters = Delimited<FunctionParameter, Comma>;
+impl Bnf for FunctionParameters {
+    fn bnf() -> &'static str {
+        concat!(
+            "<FUNCTION PARAMETERS> -> <FUNCTION PARAMETER><FUNCTION PARAMETERS'>\n",
+            "                       | ε\n",
+            "<FUNCTION PARAMETERS'> -> ,<FUNCTION PARAMETER><FUNCTION PARAMETERS'>\n",
+            "                        | ε",
+        )
+    }
+}
 
 /// A terminated list by Semicolon of Statement
 /// 
@@ -133,30 +420,46 @@ pub type FunctionParameters = Delimited<FunctionParameter, Comma>;
 ///                        | ε
 /// ```
 pub type CompoundStatements = Terminated<Statement, Semicolon>;
+impl Bnf for CompoundStatements {
+    fn bnf() -> &'static str {
+        concat!(
+            "<COMPOUND STATEMENTS> -> <STATEMENT>;<COMPOUND STATEMENTS>\n",
+            "                       | ε",
+        )
+    }
+}
 
 /// A Function Parameter
 /// 
 /// # BNF
 /// ```text
-/// <FUNCTION PARAMETER> -> type identifier
+/// <FUNCTION PARAMETER> -> <QUALIFIERS>type identifier
 /// ```
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the qualifiers, but we can clone
 pub struct FunctionParameter {
+    pub qualifiers: Vec<Qualifier>,
     pub type_ : Type,
     pub identifier: Identifier,
 }
+impl Bnf for FunctionParameter {
+    fn bnf() -> &'static str {
+        "<FUNCTION PARAMETER> -> <QUALIFIERS>type identifier"
+    }
+}
 impl Parse for FunctionParameter {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut fork = buffer.fork_node("FunctionParameter"); // this is to make parse attempts without modifying the original buffer
         let function_parameter = FunctionParameter {
+            qualifiers: parse_qualifiers(&mut fork),
             type_: Type::parse(&mut fork)?,
             identifier: Identifier::parse(&mut fork)?,
         };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
+        fork.declare(&function_parameter.identifier, &function_parameter.type_);
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
         return Ok(function_parameter);
     }
 
@@ -165,23 +468,161 @@ impl Parse for FunctionParameter {
     }
 }
 impl ParseDisplay for FunctionParameter {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
         let label = "Function Parameter";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
 
-        self.type_.display(depth+1, Some("Parameter Type".into()));
-        self.identifier.display(depth+1, Some("Parameter Identifier".into()));
+        display_qualifiers(&self.qualifiers, out, depth+1, "Parameter Qualifiers");
+        self.type_.display_to(out, depth+1, Some("Parameter Type".into()));
+        self.identifier.display_to(out, depth+1, Some("Parameter Identifier".into()));
     }
 
     fn lexeme_signature(&self) -> String {
-        let mut sigg = String::new();
-        sigg.extend(self.type_.lexeme_signature().chars());
+        let mut sigg = qualifiers_signature(&self.qualifiers, Qualifier::lexeme_signature);
+        sigg.push_str(self.type_.lexeme_str());
         sigg.extend(" ".chars());
-        sigg.extend(self.identifier.lexeme_signature().chars());
+        sigg.push_str(self.identifier.lexeme_str());
         sigg
     }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = self.qualifiers.iter().map(|qualifier| qualifier as &dyn ParseDisplay).collect();
+        children.extend([&self.type_ as &dyn ParseDisplay, &self.identifier]);
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// A qualifier of a type, any number of which can precede it (ex. `const int`).
+/// 
+/// # BNF
+/// ```text
+/// <QUALIFIERS> -> const<QUALIFIERS>
+///               | ε
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the lexemes of the terminals, but we can clone
+pub enum Qualifier {
+    Const(Const),
+}
+impl Qualifier {
+    /// The qualifier's terminal.
+    fn terminal(&self) -> &dyn ParseDisplay {
+        match self {
+            Qualifier::Const(const_) => const_,
+        }
+    }
+}
+impl Bnf for Qualifier {
+    fn bnf() -> &'static str {
+        concat!(
+            "<QUALIFIERS> -> const<QUALIFIERS>\n",
+            "              | ε",
+        )
+    }
+}
+impl Parse for Qualifier {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let qualifier = Qualifier::Const(Const::parse(&mut fork)?);
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(qualifier);
+    }
+
+    fn parse_label() -> String {
+        format!("Qualifier")
+    }
+}
+impl ParseDisplay for Qualifier {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        self.terminal().display_to(out, depth, Some(label.unwrap_or("Qualifier".into())));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        self.terminal().lexeme_signature()
+    }
+
+    // A qualifier is a terminal, so it has no children of its own
+    fn span(&self) -> Option<(usize, usize)> {
+        self.terminal().span()
+    }
+
+    fn first_token(&self) -> Option<(Token, &str)> {
+        self.terminal().first_token()
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.terminal().last_token()
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// Parses the `}` closing the body of a function (see `parse_closing`).
+/// 
+/// Reaching the end of input first is named an unclosed function body, since
+/// every block in the body was already closed (ex. `int f() {`).
+fn parse_body_closing(buffer: &mut ParseBuffer, left_curly: &LeftCurly) -> Result<RightCurly, String> {
+    let opened_at = buffer.describe_position(left_curly.position);
+    parse_closing(buffer, &left_curly.lexeme, left_curly.position).map_err(|err| match buffer.peek() {
+        Some(_) => err,
+        None => format!("Unclosed function body `{}` opened at {opened_at}, expected `{}` before the end of input", left_curly.lexeme, RightCurly::parse_label()),
+    })
+}
+
+/// Rejects a parameter declared more than once in the same function (ex. `f(int x, float x)`),
+/// which is always a mistake.
+fn check_parameters(buffer: &ParseBuffer, parameters: &FunctionParameters, function_name: &Identifier) -> Result<(), String> {
+    // only a duplicate looks back for where it was first declared
+    let mut declared = HashSet::new();
+    for (i, (parameter, _comma)) in parameters.items().iter().enumerate() {
+        let identifier = &parameter.identifier;
+        if !declared.insert(identifier.lexeme_str()) {
+            let (earlier, _comma) = parameters.items()[..i].iter().find(|(earlier, _comma)| earlier.identifier.lexeme == identifier.lexeme).unwrap();
+            Err(format!(
+                "Parameter `{}` is declared more than once in function `{}`, at {} and {}",
+                identifier.lexeme, function_name.lexeme,
+                buffer.describe_position(earlier.identifier.position), buffer.describe_position(identifier.position)
+            ))?
+        }
+    }
+    Ok(())
+}
+
+/// Parses every qualifier preceding a type, of which there may be none.
+fn parse_qualifiers(buffer: &mut ParseBuffer) -> Vec<Qualifier> {
+    let mut qualifiers = vec![];
+    while let Ok(qualifier) = Qualifier::parse(buffer) {
+        qualifiers.push(qualifier);
+    }
+    qualifiers
+}
+
+/// Displays the qualifiers of a type as a single labeled child, but only if there are any.
+fn display_qualifiers(qualifiers: &[Qualifier], out: &mut TreeWriter, depth: usize, label: &str) {
+    if qualifiers.is_empty() {
+        return;
+    }
+
+    let lexemes_label = qualifiers_signature(qualifiers, Qualifier::lexeme_signature);
+    out.line(depth, label, Some(out.cut_signature(lexemes_label.trim_end().to_string())));
+    for qualifier in qualifiers {
+        qualifier.display_to(out, depth+1, None);
+    }
+}
+
+/// The signature of the qualifiers of a type, each followed by a space (to precede the type).
+fn qualifiers_signature(qualifiers: &[Qualifier], signature: fn(&Qualifier) -> String) -> String {
+    qualifiers.iter().map(|qualifier| format!("{} ", signature(qualifier))).collect()
 }
 
 /// A Statement
@@ -190,11 +631,23 @@ impl ParseDisplay for FunctionParameter {
 /// ```text
 /// <STATEMENT> -> <ASSIGNMENT STATEMENT>
 ///              | <RETURN STATEMENT>
+///              | <IF STATEMENT>
 /// ```
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the blocks of `IfStatement`, but we can clone
+#[allow(clippy::large_enum_variant)] // an `IfStatement` is parsed in place, like every other statement
 pub enum Statement {
     Assignment(AssignmentStatement),
     Return(ReturnStatement),
+    If(IfStatement),
+}
+impl Bnf for Statement {
+    fn bnf() -> &'static str {
+        concat!(
+            "<STATEMENT> -> <ASSIGNMENT STATEMENT>\n",
+            "             | <RETURN STATEMENT>\n",
+            "             | <IF STATEMENT>",
+        )
+    }
 }
 impl Parse for Statement {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
@@ -202,25 +655,34 @@ impl Parse for Statement {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut fork = buffer.fork_node("Statement"); // this is to make parse attempts without modifying the original buffer
         match AssignmentStatement::parse(&mut fork) {
             Ok(assignment_statement) => {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
                 return Ok(Statement::Assignment(assignment_statement));
             },
             Err(_) => (),
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut fork = buffer.fork_node("Statement"); // this is to make parse attempts without modifying the original buffer
         match ReturnStatement::parse(&mut fork) {
             Ok(return_statement) => {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
                 return Ok(Statement::Return(return_statement));
             },
             Err(_) => (),
         }
 
-        Err(format!("Expected either `{} {}` for {}, but found something else instead", AssignmentStatement::parse_label(), ReturnStatement::parse_label(), Self::parse_label()))
+        let mut fork = buffer.fork_node("Statement"); // this is to make parse attempts without modifying the original buffer
+        match IfStatement::parse(&mut fork) {
+            Ok(if_statement) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Statement::If(if_statement));
+            },
+            Err(_) => (),
+        }
+
+        Err(format!("Expected either `{} {} {}` for {}, but found something else instead", AssignmentStatement::parse_label(), ReturnStatement::parse_label(), IfStatement::parse_label(), Self::parse_label()))
     }
 
     fn parse_label() -> String {
@@ -228,14 +690,14 @@ impl Parse for Statement {
     }
 }
 impl ParseDisplay for Statement {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
         let label = "Statement";
-        println!("{indent}{label}:");
+        out.line(depth, label, None);
         
         match self {
-            Statement::Assignment(assignment_statement) => assignment_statement.display(depth+1, None),
-            Statement::Return(return_statement) => return_statement.display(depth+1, None),
+            Statement::Assignment(assignment_statement) => assignment_statement.display_to(out, depth+1, None),
+            Statement::Return(return_statement) => return_statement.display_to(out, depth+1, None),
+            Statement::If(if_statement) => if_statement.display_to(out, depth+1, None),
         }
     }
 
@@ -243,35 +705,65 @@ impl ParseDisplay for Statement {
         match self {
             Statement::Assignment(assignment_statement) => assignment_statement.lexeme_signature(),
             Statement::Return(return_statement) => return_statement.lexeme_signature(),
+            Statement::If(if_statement) => if_statement.lexeme_signature(),
+        }
+    }
+
+    fn full_signature(&self) -> String {
+        match self {
+            Statement::Assignment(assignment_statement) => assignment_statement.full_signature(),
+            Statement::Return(return_statement) => return_statement.full_signature(),
+            Statement::If(if_statement) => if_statement.full_signature(),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        match self {
+            Statement::Assignment(assignment_statement) => vec![assignment_statement],
+            Statement::Return(return_statement) => vec![return_statement],
+            Statement::If(if_statement) => vec![if_statement],
         }
     }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
 }
 
 /// An Assignment Statement
 /// 
+/// The assigned expression may itself be an `AssignmentExpression`, so that
+/// `x = y = 0;` assigns to both.
+/// 
 /// # BNF
 /// ```text
 /// <ASSIGNMENT STATEMENT> -> identifier = <EXPRESSION>
 /// ```
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the boxes of `ConditionalExpression`, but we can clone
 pub struct AssignmentStatement {
     pub lhs_identifier: Identifier,
     pub equals: Equals,
     pub expression: Expression,
 }
+impl Bnf for AssignmentStatement {
+    fn bnf() -> &'static str {
+        "<ASSIGNMENT STATEMENT> -> identifier = <EXPRESSION>"
+    }
+}
 impl Parse for AssignmentStatement {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut fork = buffer.fork_node("AssignmentStatement"); // this is to make parse attempts without modifying the original buffer
         let assignment_statement = AssignmentStatement {
             lhs_identifier: Identifier::parse(&mut fork)?,
             equals: Equals::parse(&mut fork)?,
             expression: Expression::parse(&mut fork)?,
         };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
+        fork.check_assigned(&assignment_statement.lhs_identifier);
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
         return Ok(assignment_statement);
     }
 
@@ -280,38 +772,53 @@ impl Parse for AssignmentStatement {
     }
 }
 impl ParseDisplay for AssignmentStatement {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
         let label = "Assignment Statement";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
 
-        self.lhs_identifier.display(depth+1, Some("Identifier".into()));
-        self.equals.display(depth+1, Some("Equals".into()));
-        self.expression.display(depth+1, None);
+        self.lhs_identifier.display_to(out, depth+1, Some("Identifier".into()));
+        self.equals.display_to(out, depth+1, Some("Equals".into()));
+        self.expression.display_to(out, depth+1, None);
     }
 
     fn lexeme_signature(&self) -> String {
         let mut sigg = String::new();
-        sigg.extend(self.lhs_identifier.lexeme_signature().chars());
+        sigg.push_str(self.lhs_identifier.lexeme_str());
         sigg.extend(" ".chars());
-        sigg.extend(self.equals.lexeme_signature().chars());
+        sigg.push_str(self.equals.lexeme_str());
         sigg.extend(" ".chars());
         sigg.extend(self.expression.lexeme_signature().chars());
         sigg
     }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![&self.lhs_identifier, &self.equals, &self.expression]
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
 }
 
 /// A Return Statement
 /// 
+/// Any number of expressions (at least one) can be returned at once, each
+/// delimited by a comma (ex. `return a, b;`).
+/// 
 /// # BNF
 /// ```text
-/// <RETURN STATEMENT> -> return <EXPRESSION>
+/// <RETURN STATEMENT> -> return <RETURN EXPRESSIONS>
 /// ```
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the boxes of `ConditionalExpression`, but we can clone
 pub struct ReturnStatement {
     pub return_ : Return,
-    pub expression: Expression,
+    pub expressions: ReturnExpressions,
+}
+impl Bnf for ReturnStatement {
+    fn bnf() -> &'static str {
+        "<RETURN STATEMENT> -> return <RETURN EXPRESSIONS>"
+    }
 }
 impl Parse for ReturnStatement {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
@@ -319,12 +826,18 @@ impl Parse for ReturnStatement {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut fork = buffer.fork_node("ReturnStatement"); // this is to make parse attempts without modifying the original buffer
+        let return_ = Return::parse(&mut fork)?;
+        let expressions = ReturnExpressions::parse(&mut fork)?;
+        // an empty list is no expression at all
+        if expressions.is_empty() {
+            Err(format!("Expected `{}` after `return`, but found {} instead", Expression::parse_label(), fork.describe_found()))?
+        }
         let return_statement = ReturnStatement {
-            return_: Return::parse(&mut fork)?,
-            expression: Expression::parse(&mut fork)?,
+            return_,
+            expressions,
         };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
         return Ok(return_statement);
     }
 
@@ -333,85 +846,790 @@ impl Parse for ReturnStatement {
     }
 }
 impl ParseDisplay for ReturnStatement {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
         let label = "Return Statement";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
-
-        self.return_.display(depth+1, Some("Return".into()));
-        self.expression.display(depth+1, None);
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        self.return_.display_to(out, depth+1, Some("Return".into()));
+        // only several expressions are numbered, a single one is displayed as it always was
+        let numbered = self.expressions.items().len() > 1;
+        for (i, (expression, _comma)) in self.expressions.items().iter().enumerate() {
+            let label = numbered.then(|| format!("Expression {}", i+1));
+            expression.display_to(out, depth+1, label);
+        }
     }
 
     fn lexeme_signature(&self) -> String {
         let mut sigg = String::new();
-        sigg.extend(self.return_.lexeme_signature().chars());
-        sigg.extend(" ".chars());
-        sigg.extend(self.expression.lexeme_signature().chars());
+        sigg.push_str(self.return_.lexeme_str());
+        for (expression, comma) in &self.expressions {
+            sigg.extend(" ".chars());
+            sigg.push_str(&expression.lexeme_signature());
+            if let Some(comma) = comma {
+                sigg.push_str(comma.lexeme_str());
+            }
+        }
         sigg
     }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![&self.return_];
+        children.extend(self.expressions.children());
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
 }
 
-/// An Expression
+/// The expressions of a return statement, delimited by commas.
+/// 
+/// The list is never empty once parsed by a `ReturnStatement`.
 /// 
 /// # BNF
 /// ```text
-/// <EXPRESSION> -> <ARITHMETIC EXPRESSION>
-///               | <TYPECAST EXPRESSION>
+/// <RETURN EXPRESSIONS> -> <EXPRESSION><RETURN EXPRESSIONS'>
+/// <RETURN EXPRESSIONS'> -> ,<EXPRESSION><RETURN EXPRESSIONS'>
+///                        | ε
 /// ```
-#[derive(Clone, Copy)]
-pub enum Expression {
-    Arithmetic(ArithmeticExpression),
-    Typecast(TypecastExpression),
+pub type ReturnExpressions = Delimited<Expression, Comma>;
+impl Bnf for ReturnExpressions {
+    fn bnf() -> &'static str {
+        concat!(
+            "<RETURN EXPRESSIONS> -> <EXPRESSION><RETURN EXPRESSIONS'>\n",
+            "<RETURN EXPRESSIONS'> -> ,<EXPRESSION><RETURN EXPRESSIONS'>\n",
+            "                       | ε",
+        )
+    }
 }
-impl Parse for Expression {
-    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
-        if buffer.peek().is_none() {
-            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
-        }
-
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        match ArithmeticExpression::parse(&mut fork) {
-            Ok(arithmetic_expression) => {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                return Ok(Expression::Arithmetic(arithmetic_expression));
-            },
-            Err(_) => (),
-        }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        match TypecastExpression::parse(&mut fork) {
-            Ok(typecast_expression) => {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                return Ok(Expression::Typecast(typecast_expression));
-            },
-            Err(_) => (),
-        }
+/// A standalone list of expressions delimited by commas (ex. `1 + 2, a * b`),
+/// as a root of its own (ex. `--root ExpressionList`), for tools analyzing
+/// snippets of expressions.
+/// 
+/// This is the same list as `ReturnExpressions`, but it may be empty (ex. an
+/// empty input).
+/// 
+/// # BNF
+/// ```text
+/// <EXPRESSION LIST> -> <EXPRESSION><EXPRESSION LIST'>
+///                    | ε
+/// <EXPRESSION LIST'> -> ,<EXPRESSION><EXPRESSION LIST'>
+///                     | ε
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to modulars, but we can clone
+pub struct ExpressionList {
+    pub expressions: Delimited<Expression, Comma>,
+}
+impl ExpressionList {
+    /// Every expression, each with the comma after it (if any), in order.
+    pub fn items(&self) -> &[(Expression, Option<Comma>)] {
+        self.expressions.items()
+    }
 
-        Err(format!("Expected either `{} {}` for {}, but found something else instead", ArithmeticExpression::parse_label(), TypecastExpression::parse_label(), Self::parse_label()))
+    /// If there are no expressions in the list.
+    pub fn is_empty(&self) -> bool {
+        self.expressions.is_empty()
+    }
+}
+impl Bnf for ExpressionList {
+    fn bnf() -> &'static str {
+        concat!(
+            "<EXPRESSION LIST> -> <EXPRESSION><EXPRESSION LIST'>\n",
+            "                   | ε\n",
+            "<EXPRESSION LIST'> -> ,<EXPRESSION><EXPRESSION LIST'>\n",
+            "                    | ε",
+        )
+    }
+}
+impl Parse for ExpressionList {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        let mut fork = buffer.fork_node("ExpressionList"); // this is to make parse attempts without modifying the original buffer
+        let expressions = Delimited::parse(&mut fork)?;
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(ExpressionList { expressions });
     }
 
     fn parse_label() -> String {
-        format!("Expression")
+        format!("Expression List")
     }
-} 
-impl ParseDisplay for Expression {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
-        let label = "Expression";
-        println!("{indent}{label}:");
-
-        match self {
-            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.display(depth+1, None),
-            Expression::Typecast(typecast_expression) => typecast_expression.display(depth+1, None),
-        }
+}
+impl ParseDisplay for ExpressionList {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        self.expressions.display_to(out, depth, Some(Self::parse_label()));
     }
 
     fn lexeme_signature(&self) -> String {
-        match self {
-            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.lexeme_signature(),
-            Expression::Typecast(typecast_expression) => typecast_expression.lexeme_signature(),
-        }
+        self.expressions.lexeme_signature()
+    }
+
+    fn short_signature(&self, max_len: usize) -> String {
+        self.expressions.short_signature(max_len)
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        self.expressions.children()
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// An If Statement
+/// 
+/// Any chain of `else if` is kept flat: each `else if` is stored as an
+/// `ElseTail::If`, and is displayed as a sibling branch rather than as a nested
+/// statement.
+/// 
+/// # BNF
+/// ```text
+/// <IF STATEMENT> -> if (<EXPRESSION>)<BLOCK><ELSE TAIL>
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to modulars, but we can clone
+pub struct IfStatement {
+    pub if_: If,
+    pub left_paren: LeftParen,
+    pub condition: Expression,
+    pub right_paren: RightParen,
+    pub block: Block,
+    pub else_tail: Option<ElseTail>,
+}
+impl IfStatement {
+    /// Displays the `if (<EXPRESSION>)<BLOCK>` part of the statement as a single branch,
+    /// without the else tail.
+    fn display_branch(&self, out: &mut TreeWriter, depth: usize) {
+        self.if_.display_to(out, depth, Some("If".into()));
+        self.left_paren.display_to(out, depth, Some("Left Paren".into()));
+        self.condition.display_to(out, depth, Some("Condition".into()));
+        self.right_paren.display_to(out, depth, Some("Right Paren".into()));
+        self.block.display_to(out, depth, None);
+    }
+
+    /// The signature of the `if (<EXPRESSION>)<BLOCK>` part of the statement,
+    /// without the else tail.
+    fn branch_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(self.if_.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.left_paren.lexeme_str());
+        sigg.extend(self.condition.lexeme_signature().chars());
+        sigg.push_str(self.right_paren.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.extend(self.block.lexeme_signature().chars());
+        sigg
+    }
+
+    /// The same as `branch_signature`, but with the block in full.
+    fn full_branch_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(self.if_.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.left_paren.lexeme_str());
+        sigg.extend(self.condition.full_signature().chars());
+        sigg.push_str(self.right_paren.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.extend(self.block.full_signature().chars());
+        sigg
+    }
+}
+impl Bnf for IfStatement {
+    fn bnf() -> &'static str {
+        "<IF STATEMENT> -> if (<EXPRESSION>)<BLOCK><ELSE TAIL>"
+    }
+}
+impl Parse for IfStatement {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        // if statements nest within (the blocks and else tails of) if statements, so this is where the nesting limit is enforced
+        buffer.nested(|buffer| {
+            if buffer.peek().is_none() {
+                Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+            }
+
+            let mut fork = buffer.fork_node("IfStatement"); // this is to make parse attempts without modifying the original buffer
+            let if_ = If::parse(&mut fork)?;
+            let left_paren = LeftParen::parse(&mut fork)?;
+            let condition = Expression::parse(&mut fork)?;
+            let right_paren = parse_closing(&mut fork, &left_paren.lexeme, left_paren.position)?;
+            let if_statement = IfStatement {
+                if_,
+                left_paren,
+                condition,
+                right_paren,
+                block: Block::parse(&mut fork)?,
+                else_tail: ElseTail::parse(&mut fork)?,
+            };
+            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+            return Ok(if_statement);
+        })
+    }
+
+    fn parse_label() -> String {
+        format!("If Statement")
+    }
+}
+impl ParseDisplay for IfStatement {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "If Statement";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        // The first branch
+        out.line(depth+1, "If Branch", Some(out.cut_signature(self.branch_signature())));
+        self.display_branch(out, depth+2);
+
+        // Every following branch, flattened to the same depth as the first
+        let mut else_tail = self.else_tail.as_ref();
+        while let Some(tail) = else_tail {
+            match tail {
+                ElseTail::If(else_, if_statement) => {
+                    let signature = format!("{} {}", else_.lexeme_signature(), if_statement.branch_signature());
+                    out.line(depth+1, "Else If Branch", Some(out.cut_signature(signature)));
+                    else_.display_to(out, depth+2, Some("Else".into()));
+                    if_statement.display_branch(out, depth+2);
+                    else_tail = if_statement.else_tail.as_ref();
+                },
+                ElseTail::Block(else_, block) => {
+                    let signature = format!("{} {}", else_.lexeme_signature(), block.lexeme_signature());
+                    out.line(depth+1, "Else Branch", Some(out.cut_signature(signature)));
+                    else_.display_to(out, depth+2, Some("Else".into()));
+                    block.display_to(out, depth+2, None);
+                    else_tail = None;
+                },
+            }
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = self.branch_signature();
+        if let Some(ref else_tail) = self.else_tail {
+            sigg.push(' ');
+            sigg.extend(else_tail.lexeme_signature().chars());
+        }
+        sigg
+    }
+
+    fn full_signature(&self) -> String {
+        let mut sigg = self.full_branch_signature();
+        if let Some(ref else_tail) = self.else_tail {
+            sigg.push(' ');
+            sigg.extend(else_tail.full_signature().chars());
+        }
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![&self.if_, &self.left_paren, &self.condition, &self.right_paren, &self.block];
+        if let Some(ref else_tail) = self.else_tail {
+            children.push(else_tail);
+        }
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// An If Statement's Else Tail
+/// 
+/// # BNF
+/// ```text
+/// <ELSE TAIL> -> else <IF STATEMENT>
+///              | else <BLOCK>
+///              | ε
+/// ```
+/// 
+/// **Note:** the enum encapsulates the first two non-empty cases.
+/// The ε option is encapsulated as the `Option<Self>` in the `Parse` implementation
+/// signature
+/// ```ignore
+/// impl Parse<Option<Self>> for ElseTail
+/// ```
+#[derive(Clone, PartialEq)]
+pub enum ElseTail {
+    If(Else, Box<IfStatement>),
+    Block(Else, Block),
+}
+impl Bnf for ElseTail {
+    fn bnf() -> &'static str {
+        concat!(
+            "<ELSE TAIL> -> else <IF STATEMENT>\n",
+            "             | else <BLOCK>\n",
+            "             | ε",
+        )
+    }
+}
+impl Parse<Option<Self>> for ElseTail {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Option<Self>, String> {
+        if buffer.peek().is_none() {
+            return Ok(None);
+        }
+
+        let mut fork = buffer.fork_node("ElseTail"); // this is to make parse attempts without modifying the original buffer
+        let else_ = match Else::parse(&mut fork) {
+            Ok(else_) => else_,
+            Err(_) => return Ok(None),
+        };
+
+        // An `else` must be followed by something: try both, reporting both on failure
+        let if_err = match IfStatement::parse(&mut fork) {
+            Ok(if_statement) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Some(ElseTail::If(else_, Box::new(if_statement))));
+            },
+            Err(err) => err,
+        };
+
+        let block_err = match Block::parse(&mut fork) {
+            Ok(block) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Some(ElseTail::Block(else_, block)));
+            },
+            Err(err) => err,
+        };
+
+        Err(buffer.context_error(Self::parse_label(), format!("{if_err}\n    {block_err}")))
+    }
+
+    fn parse_label() -> String {
+        format!("Else Tail")
+    }
+}
+impl ParseDisplay for ElseTail {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Else Tail";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        match self {
+            ElseTail::If(else_, if_statement) => {
+                else_.display_to(out, depth+1, Some("Else".into()));
+                if_statement.display_to(out, depth+1, None);
+            },
+            ElseTail::Block(else_, block) => {
+                else_.display_to(out, depth+1, Some("Else".into()));
+                block.display_to(out, depth+1, None);
+            },
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        match self {
+            ElseTail::If(else_, if_statement) => {
+                sigg.push_str(else_.lexeme_str());
+                sigg.extend(" ".chars());
+                sigg.extend(if_statement.lexeme_signature().chars());
+            },
+            ElseTail::Block(else_, block) => {
+                sigg.push_str(else_.lexeme_str());
+                sigg.extend(" ".chars());
+                sigg.extend(block.lexeme_signature().chars());
+            },
+        };
+        sigg
+    }
+
+    fn full_signature(&self) -> String {
+        let mut sigg = String::new();
+        match self {
+            ElseTail::If(else_, if_statement) => {
+                sigg.push_str(else_.lexeme_str());
+                sigg.extend(" ".chars());
+                sigg.extend(if_statement.full_signature().chars());
+            },
+            ElseTail::Block(else_, block) => {
+                sigg.push_str(else_.lexeme_str());
+                sigg.extend(" ".chars());
+                sigg.extend(block.full_signature().chars());
+            },
+        };
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        match self {
+            ElseTail::If(else_, if_statement) => vec![else_, if_statement.as_ref()],
+            ElseTail::Block(else_, block) => vec![else_, block],
+        }
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// A Block of statements
+/// 
+/// # BNF
+/// ```text
+/// <BLOCK> -> {<COMPOUND STATEMENTS>}
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to modulars, but we can clone
+pub struct Block {
+    pub left_curly: LeftCurly,
+    pub compound_statements: CompoundStatements,
+    pub right_curly: RightCurly,
+}
+impl Bnf for Block {
+    fn bnf() -> &'static str {
+        "<BLOCK> -> {<COMPOUND STATEMENTS>}"
+    }
+}
+impl Parse for Block {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("Block"); // this is to make parse attempts without modifying the original buffer
+        let left_curly = LeftCurly::parse(&mut fork)?;
+        fork.enter_scope();
+        let compound_statements = fork.parse_node("CompoundStatements", CompoundStatements::parse_followed_by::<RightCurly>)?;
+        let right_curly = parse_closing(&mut fork, &left_curly.lexeme, left_curly.position)?;
+        fork.exit_scope();
+        let block = Block {
+            left_curly,
+            compound_statements,
+            right_curly,
+        };
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(block);
+    }
+
+    fn parse_label() -> String {
+        format!("Block")
+    }
+}
+impl ParseDisplay for Block {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Block";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        self.left_curly.display_to(out, depth+1, Some("Left Curly".into()));
+        self.compound_statements.display_to(out, depth+1, Some("Compound Statements".into()));
+        self.right_curly.display_to(out, depth+1, Some("Right Curly".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(self.left_curly.lexeme_str());
+        sigg.extend("....".chars());
+        sigg.push_str(self.right_curly.lexeme_str());
+        sigg
+    }
+
+    fn full_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(self.left_curly.lexeme_str());
+        sigg.extend(self.compound_statements.full_signature().chars());
+        sigg.push_str(self.right_curly.lexeme_str());
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![&self.left_curly, &self.compound_statements, &self.right_curly]
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// An Expression
+/// 
+/// # BNF
+/// ```text
+/// <EXPRESSION> -> <ASSIGNMENT EXPRESSION>
+///               | <CONDITIONAL EXPRESSION>
+///               | <ARITHMETIC EXPRESSION>
+///               | <TYPECAST EXPRESSION>
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the boxes of `ConditionalExpression`, but we can clone
+pub enum Expression {
+    Assignment(AssignmentExpression),
+    Conditional(ConditionalExpression),
+    Arithmetic(ArithmeticExpression),
+    Typecast(TypecastExpression),
+}
+impl Bnf for Expression {
+    fn bnf() -> &'static str {
+        concat!(
+            "<EXPRESSION> -> <ASSIGNMENT EXPRESSION>\n",
+            "              | <CONDITIONAL EXPRESSION>\n",
+            "              | <ARITHMETIC EXPRESSION>\n",
+            "              | <TYPECAST EXPRESSION>",
+        )
+    }
+}
+impl Parse for Expression {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        // expressions nest within expressions, so this is where the nesting limit is enforced
+        buffer.nested(|buffer| {
+            if buffer.peek().is_none() {
+                Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+            }
+
+            // an assignment is only attempted at an `identifier =`, so that the
+            // value is the only expression parsed (rather than re-parsed) after it
+            let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+            if Identifier::parse(&mut fork).is_ok() && Equals::parse(&mut fork).is_ok() {
+                let mut fork = buffer.fork_node("Expression"); // this is to make parse attempts without modifying the original buffer
+                match AssignmentExpression::parse(&mut fork) {
+                    Ok(assignment_expression) => {
+                        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                        return Ok(Expression::Assignment(assignment_expression));
+                    },
+                    Err(_) => (),
+                }
+            }
+
+            // a conditional and an arithmetic expression start alike: the
+            // arithmetic expression is parsed once, then the `? ... : ...` tail, if any
+            let mut fork = buffer.fork_node("Expression"); // this is to make parse attempts without modifying the original buffer
+            let condition = fork.node_mark();
+            match ArithmeticExpression::parse(&mut fork) {
+                Ok(arithmetic_expression) => {
+                    let expression = match ConditionalExpression::parse_tail(arithmetic_expression, &mut fork) {
+                        Ok(conditional_expression) => {
+                            // only now is the already parsed condition known to start a conditional
+                            fork.wrap_node(condition, "ConditionalExpression");
+                            Expression::Conditional(conditional_expression)
+                        },
+                        Err((arithmetic_expression, _)) => Expression::Arithmetic(arithmetic_expression),
+                    };
+                    buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                    return Ok(expression);
+                },
+                Err(_) => (),
+            }
+
+            let mut fork = buffer.fork_node("Expression"); // this is to make parse attempts without modifying the original buffer
+            match TypecastExpression::parse(&mut fork) {
+                Ok(typecast_expression) => {
+                    buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                    return Ok(Expression::Typecast(typecast_expression));
+                },
+                Err(_) => (),
+            }
+
+            Err(format!("Expected either `{} {} {} {}` for {}, but found something else instead", AssignmentExpression::parse_label(), ConditionalExpression::parse_label(), ArithmeticExpression::parse_label(), TypecastExpression::parse_label(), Self::parse_label()))
+        })
+    }
+
+    fn parse_label() -> String {
+        format!("Expression")
+    }
+} 
+impl ParseDisplay for Expression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        let label = label.unwrap_or(Self::parse_label());
+        out.line(depth, &label, None);
+
+        match self {
+            Expression::Assignment(assignment_expression) => assignment_expression.display_to(out, depth+1, None),
+            Expression::Conditional(conditional_expression) => conditional_expression.display_to(out, depth+1, None),
+            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.display_to(out, depth+1, None),
+            Expression::Typecast(typecast_expression) => typecast_expression.display_to(out, depth+1, None),
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        match self {
+            Expression::Assignment(assignment_expression) => assignment_expression.lexeme_signature(),
+            Expression::Conditional(conditional_expression) => conditional_expression.lexeme_signature(),
+            Expression::Arithmetic(arithmetic_expression) => arithmetic_expression.lexeme_signature(),
+            Expression::Typecast(typecast_expression) => typecast_expression.lexeme_signature(),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        match self {
+            Expression::Assignment(assignment_expression) => vec![assignment_expression],
+            Expression::Conditional(conditional_expression) => vec![conditional_expression],
+            Expression::Arithmetic(arithmetic_expression) => vec![arithmetic_expression],
+            Expression::Typecast(typecast_expression) => vec![typecast_expression],
+        }
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// An Assignment Expression
+/// 
+/// This is the lowest precedence expression. Chains of assignments are
+/// right-associative, as `x = y = 0` is parsed as `x = (y = 0)`.
+/// 
+/// # BNF
+/// ```text
+/// <ASSIGNMENT EXPRESSION> -> identifier = <EXPRESSION>
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the box, but we can clone
+pub struct AssignmentExpression {
+    pub target: Identifier,
+    pub equals: Equals,
+    pub value: Box<Expression>,
+}
+impl Bnf for AssignmentExpression {
+    fn bnf() -> &'static str {
+        "<ASSIGNMENT EXPRESSION> -> identifier = <EXPRESSION>"
+    }
+}
+impl Parse for AssignmentExpression {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("AssignmentExpression"); // this is to make parse attempts without modifying the original buffer
+        let assignment_expression = AssignmentExpression {
+            target: Identifier::parse(&mut fork)?,
+            equals: Equals::parse(&mut fork)?,
+            // the value may itself be an assignment, making the chain right-associative
+            value: Box::new(Expression::parse(&mut fork)?),
+        };
+        fork.check_assigned(&assignment_expression.target);
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(assignment_expression);
+    }
+
+    fn parse_label() -> String {
+        format!("Assignment Expression")
+    }
+}
+impl ParseDisplay for AssignmentExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Assignment Expression";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        self.target.display_to(out, depth+1, Some("Target".into()));
+        self.equals.display_to(out, depth+1, Some("Equals".into()));
+        self.value.display_to(out, depth+1, Some("Value".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(self.target.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.equals.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.extend(self.value.lexeme_signature().chars());
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![&self.target, &self.equals, self.value.as_ref()]
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// A Conditional (Ternary) Expression
+/// 
+/// Chains of conditional expressions are right-associative, as
+/// `a ? b : c ? d : e` is parsed as `a ? b : (c ? d : e)`.
+/// 
+/// # BNF
+/// ```text
+/// <CONDITIONAL EXPRESSION> -> <ARITHMETIC EXPRESSION> ? <EXPRESSION> : <EXPRESSION>
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the boxes, but we can clone
+pub struct ConditionalExpression {
+    pub condition: ArithmeticExpression,
+    pub question: Question,
+    pub then_expression: Box<Expression>,
+    pub colon: Colon,
+    pub else_expression: Box<Expression>,
+}
+impl Bnf for ConditionalExpression {
+    fn bnf() -> &'static str {
+        "<CONDITIONAL EXPRESSION> -> <ARITHMETIC EXPRESSION> ? <EXPRESSION> : <EXPRESSION>"
+    }
+}
+impl Parse for ConditionalExpression {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("ConditionalExpression"); // this is to make parse attempts without modifying the original buffer
+        let condition = ArithmeticExpression::parse(&mut fork)?;
+        let conditional_expression = Self::parse_tail(condition, &mut fork).map_err(|(_, err)| err)?;
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(conditional_expression);
+    }
+
+    fn parse_label() -> String {
+        format!("Conditional Expression")
+    }
+}
+impl ConditionalExpression {
+    /// Parses the `? <EXPRESSION> : <EXPRESSION>` after an already parsed
+    /// condition, or gives the condition back (with the error) if there is no such tail.
+    /// 
+    /// Nothing is consumed if there is no such tail.
+    fn parse_tail(condition: ArithmeticExpression, buffer: &mut ParseBuffer) -> Result<Self, (ArithmeticExpression, String)> {
+        // the else expression may itself be conditional, making the chain right-associative
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let tail = (|| {
+            // each expression is boxed as soon as it is parsed, as this is on the stack of every nested conditional
+            let question = Question::parse(&mut fork)?;
+            let then_expression = Box::new(Expression::parse(&mut fork)?);
+            let colon = Colon::parse(&mut fork)?;
+            let else_expression = Box::new(Expression::parse(&mut fork)?);
+            Ok((question, then_expression, colon, else_expression))
+        })();
+        match tail {
+            Ok((question, then_expression, colon, else_expression)) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                Ok(ConditionalExpression { condition, question, then_expression, colon, else_expression })
+            },
+            Err(err) => Err((condition, err)),
+        }
+    }
+}
+impl ParseDisplay for ConditionalExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Conditional Expression";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        self.condition.display_to(out, depth+1, Some("Condition".into()));
+        self.question.display_to(out, depth+1, Some("Question".into()));
+        self.then_expression.display_to(out, depth+1, Some("Then Expression".into()));
+        self.colon.display_to(out, depth+1, Some("Colon".into()));
+        self.else_expression.display_to(out, depth+1, Some("Else Expression".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.extend(self.condition.lexeme_signature().chars());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.question.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.extend(self.then_expression.lexeme_signature().chars());
+        sigg.extend(" ".chars());
+        sigg.push_str(self.colon.lexeme_str());
+        sigg.extend(" ".chars());
+        sigg.extend(self.else_expression.lexeme_signature().chars());
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![&self.condition, &self.question, self.then_expression.as_ref(), &self.colon, self.else_expression.as_ref()]
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
     }
 }
 
@@ -419,411 +1637,1157 @@ impl ParseDisplay for Expression {
 /// 
 /// # BNF
 /// ```text
-/// <TYPECAST EXPRESSION> -> (type)identifier
+/// <TYPECAST EXPRESSION> -> (type)identifier
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the lexemes of the terminals, but we can clone
+pub struct TypecastExpression {
+    pub left_paren: LeftParen,
+    pub type_: Type,
+    pub right_paren: RightParen,
+    pub ident: Identifier,
+}
+impl Bnf for TypecastExpression {
+    fn bnf() -> &'static str {
+        "<TYPECAST EXPRESSION> -> (type)identifier"
+    }
+}
+impl Parse for TypecastExpression {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("TypecastExpression"); // this is to make parse attempts without modifying the original buffer
+        let left_paren = LeftParen::parse(&mut fork)?;
+        let type_ = Type::parse(&mut fork)?;
+        let right_paren = parse_closing(&mut fork, &left_paren.lexeme, left_paren.position)?;
+        let typecast_expression = TypecastExpression {
+            left_paren,
+            type_,
+            right_paren,
+            ident: Identifier::parse(&mut fork)?
+        };
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(typecast_expression);
+    }
+
+    fn parse_label() -> String {
+        format!("Typecast Expression")
+    }
+}
+impl ParseDisplay for TypecastExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Typecast Expression";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        self.left_paren.display_to(out, depth+1, Some("Left Paren".into()));
+        self.type_.display_to(out, depth+1, Some("Cast Type".into()));
+        self.right_paren.display_to(out, depth+1, Some("Right Paren".into()));
+        self.ident.display_to(out, depth+1, Some("Cast Indentifier".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(self.left_paren.lexeme_str());
+        sigg.push_str(self.type_.lexeme_str());
+        sigg.push_str(self.right_paren.lexeme_str());
+        sigg.push_str(self.ident.lexeme_str());
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![&self.left_paren, &self.type_, &self.right_paren, &self.ident]
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// An Arithmetic Expression
+/// 
+/// # BNF
+/// ```text
+/// <ARITHMETIC EXPRESSION> -> <TERM><TERM'>
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the parenthesized factors, but we can clone
+pub struct ArithmeticExpression {
+    pub lhs_term: Term,
+    pub extend: Option<Box<TermExtend>>
+}
+impl Bnf for ArithmeticExpression {
+    fn bnf() -> &'static str {
+        "<ARITHMETIC EXPRESSION> -> <TERM><TERM'>"
+    }
+}
+impl Parse for ArithmeticExpression {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("ArithmeticExpression"); // this is to make parse attempts without modifying the original buffer
+        let arithmetic_expression = ArithmeticExpression {
+            lhs_term: Term::parse(&mut fork)?,
+            extend: TermExtend::parse(&mut fork)?.map(Box::new),
+        };
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(arithmetic_expression);
+    }
+
+    fn parse_label() -> String {
+        format!("Arithmetic Expression")
+    }
+}
+impl ParseDisplay for ArithmeticExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        // Compactly, a lone term is displayed by itself, and a chain as a `Sum`
+        let label = match (out.config().compact_arithmetic, &self.extend) {
+            (true, None) => return self.lhs_term.display_to(out, depth, label),
+            (true, Some(_)) => label.unwrap_or("Sum".into()),
+            (false, _) => label.unwrap_or(Self::parse_label()),
+        };
+
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
+        
+        self.lhs_term.display_to(out, depth+1, None);
+        if let Some(ref extend) = self.extend {
+            extend.display_to(out, depth+1, None);
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.extend(self.lhs_term.lexeme_signature().chars());
+        if let Some(ref extend) = self.extend {
+            sigg.push(' ');
+            sigg.extend(extend.lexeme_signature().chars());
+        }
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![&self.lhs_term];
+        if let Some(ref extend) = self.extend {
+            children.push(extend.as_ref());
+        }
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// A Term
+/// 
+/// This is basically something maybe seperated by + or -.
+/// 
+/// # BNF
+/// ```text
+/// <TERM> -> <FACTOR><FACTOR'>
 /// ```
-#[derive(Clone, Copy)]
-pub struct TypecastExpression {
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the parenthesized factors, but we can clone
+pub struct Term {
+    pub factor: Factor,
+    pub extend: Option<Box<FactorExtend>>
+}
+impl Bnf for Term {
+    fn bnf() -> &'static str {
+        "<TERM> -> <FACTOR><FACTOR'>"
+    }
+}
+impl Term {
+    /// A term left in the place of one moved out of a link being taken apart
+    /// (see `TermExtend::into_parts`), which is dropped right away.
+    fn placeholder() -> Term {
+        Term { factor: Factor::placeholder(), extend: None }
+    }
+}
+impl Parse for Term {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("Term"); // this is to make parse attempts without modifying the original buffer
+        let term = Term {
+            factor: Factor::parse(&mut fork)?,
+            extend: FactorExtend::parse(&mut fork)?.map(Box::new),
+        };
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(term);
+    }
+
+    fn parse_label() -> String {
+        format!("Term")
+    }
+}
+impl ParseDisplay for Term {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        // Compactly, a lone factor is displayed by itself, and a chain as a `Product`
+        let label = match (out.config().compact_arithmetic, &self.extend) {
+            (true, None) => return self.factor.display_to(out, depth, label),
+            (true, Some(_)) => label.unwrap_or("Product".into()),
+            (false, _) => "Term".into(),
+        };
+
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
+
+        self.factor.display_to(out, depth+1, None);
+        if let Some(ref extend) = self.extend {
+            extend.display_to(out, depth+1, None);
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        self.factor.push_signature(&mut sigg);
+        if let Some(ref extend) = self.extend {
+            sigg.push(' ');
+            sigg.extend(extend.lexeme_signature().chars());
+        }
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![&self.factor];
+        if let Some(ref extend) = self.extend {
+            children.push(extend.as_ref());
+        }
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// A Term's Extension
+/// 
+/// This changes a statement to a statement with an addition.
+/// 
+/// # BNF
+/// ```text
+/// <TERM'> -> +<TERM><TERM'>
+///          | -<TERM><TERM'>
+///          | ε
+/// ```
+/// 
+/// Each extension holds the rest of the chain (ex. the `+ c` of `a + b + c`),
+/// which is read left to right.
+/// 
+/// **Note:** the struct encapsulates both non-empty cases, which only differ by the `TermOp`.
+/// The ε option is encapsulated as the `Option<Self>` in the `Parse` implementation
+/// signature
+/// ```ignore
+/// impl Parse<Option<Self>> for TermExtend
+/// ```
+/// 
+/// A chain is cloned, compared, dropped, and signed link by link (rather than
+/// recursively), so a long chain cannot overflow the stack.
+pub struct TermExtend {
+    pub op: TermOp,
+    pub term: Term,
+    pub extend: Option<Box<TermExtend>>,
+}
+impl Bnf for TermExtend {
+    fn bnf() -> &'static str {
+        concat!(
+            "<TERM'> -> +<TERM><TERM'>\n",
+            "         | -<TERM><TERM'>\n",
+            "         | ε",
+        )
+    }
+}
+impl TermExtend {
+    /// Every link of the chain, from this one to the last one.
+    pub fn links(&self) -> impl Iterator<Item = &TermExtend> {
+        std::iter::successors(Some(self), |link| link.extend.as_deref())
+    }
+
+    /// The operator, term, and rest of the chain of this link, taken apart.
+    /// 
+    /// A link cannot be destructured by moving out of it, as it implements `Drop`:
+    /// its term is swapped out for a placeholder instead.
+    pub fn into_parts(mut self) -> (TermOp, Term, Option<Box<TermExtend>>) {
+        let extend = self.extend.take();
+        let term = std::mem::replace(&mut self.term, Term::placeholder());
+        (self.op.clone(), term, extend)
+    }
+
+    /// Parses a single link of the chain (an operator and its operand), without the rest of the chain.
+    /// 
+    /// The link's node is started, but only ended along with the rest of the
+    /// chain, as each link holds the rest of the chain.
+    fn parse_link(buffer: &mut crate::ParseBuffer) -> Result<Option<Self>, String> {
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        fork.emit(ParseEvent::StartNode("TermExtend"));
+        let op = match TermOp::parse(&mut fork) {
+            Ok(op) => op,
+            Err(_) => return Ok(None),
+        };
+        let term = Term::parse(&mut fork)?;
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(Some(TermExtend { op, term, extend: None }));
+    }
+}
+impl Parse<Option<Self>> for TermExtend {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Option<Self>, String> {
+        // The chain is parsed link by link (rather than recursively), so a long chain cannot overflow the stack
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let mut links = vec![];
+        while let Some(link) = Self::parse_link(&mut fork)? {
+            links.push(link);
+        }
+        for _ in &links {
+            fork.emit(ParseEvent::EndNode("TermExtend"));
+        }
+
+        // Each link holds the rest of the chain, so build it back to front
+        let mut extend = None;
+        for mut link in links.into_iter().rev() {
+            link.extend = extend;
+            extend = Some(Box::new(link));
+        }
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(extend.map(|extend| *extend));
+    }
+
+    fn parse_label() -> String {
+        format!("Term Extention")
+    }
+}
+impl Clone for TermExtend {
+    fn clone(&self) -> Self {
+        // Each link holds the rest of the chain, so build it back to front
+        let mut extend = None;
+        for link in self.links().collect::<Vec<_>>().into_iter().rev() {
+            extend = Some(Box::new(TermExtend { op: link.op.clone(), term: link.term.clone(), extend }));
+        }
+        *extend.unwrap()
+    }
+}
+impl PartialEq for TermExtend {
+    fn eq(&self, other: &Self) -> bool {
+        // Compared link by link, so that a long chain cannot overflow the stack
+        let (mut links, mut other_links) = (self.links(), other.links());
+        loop {
+            match (links.next(), other_links.next()) {
+                (Some(link), Some(other_link)) if link.op == other_link.op && link.term == other_link.term => (),
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+impl Drop for TermExtend {
+    fn drop(&mut self) {
+        // unlinking the rest of the chain first, so that no link drops the next one recursively
+        let mut extend = self.extend.take();
+        while let Some(mut link) = extend {
+            extend = link.extend.take();
+        }
+    }
+}
+impl ParseDisplay for TermExtend {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        // Stay at the same depth for Term: We have already been here,
+        // and the rest of the chain also stays at the same depth
+        for link in self.links() {
+            link.op.display_to(out, depth, None);
+            link.term.display_to(out, depth, None);
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        for (i, link) in self.links().enumerate() {
+            if i > 0 {
+                sigg.push(' ');
+            }
+            sigg.push_str(link.op.lexeme_str());
+            sigg.extend(" ".chars());
+            sigg.extend(link.term.lexeme_signature().chars());
+        }
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![&self.op, &self.term];
+        if let Some(ref extend) = self.extend {
+            children.push(extend.as_ref());
+        }
+        children
+    }
+
+    fn span(&self) -> Option<(usize, usize)> {
+        let (start, _) = self.op.span()?;
+        let (_, end) = self.links().last()?.term.span()?;
+        Some((start, end))
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.links().last()?.term.last_token()
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// The operator of a term's extension, `+ | -`.
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the lexemes of the terminals, but we can clone
+pub enum TermOp {
+    Add(Plus),
+    Subtract(Minus),
+}
+impl TermOp {
+    /// The operator's terminal.
+    fn terminal(&self) -> &dyn ParseDisplay {
+        match self {
+            TermOp::Add(plus) => plus,
+            TermOp::Subtract(minus) => minus,
+        }
+    }
+
+    /// The operator's lexeme, borrowed from the token stream (see `Terminal::lexeme_str`).
+    pub fn lexeme_str(&self) -> &str {
+        match self {
+            TermOp::Add(plus) => plus.lexeme_str(),
+            TermOp::Subtract(minus) => minus.lexeme_str(),
+        }
+    }
+
+    /// The precedence of the operator, see `operators::precedence_of`.
+    pub fn precedence(&self) -> u8 {
+        let token = match self {
+            TermOp::Add(plus) => plus.token(),
+            TermOp::Subtract(minus) => minus.token(),
+        };
+        binary_precedence(token)
+    }
+}
+impl Parse for TermOp {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        match Plus::parse(&mut fork) {
+            Ok(plus) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(TermOp::Add(plus));
+            },
+            Err(_) => ()
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        match Minus::parse(&mut fork) {
+            Ok(minus) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(TermOp::Subtract(minus));
+            },
+            Err(_) => ()
+        }
+
+        Err(format!("Expected either `{} {}` for {}, but found something else instead", Plus::parse_label(), Minus::parse_label(), Self::parse_label()))
+    }
+
+    fn parse_label() -> String {
+        format!("Term Operator")
+    }
+}
+impl ParseDisplay for TermOp {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        self.terminal().display_to(out, depth, Some(label.unwrap_or("Operator".into())));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        self.terminal().lexeme_signature()
+    }
+
+    // An operator is a terminal, so it has no children of its own
+    fn span(&self) -> Option<(usize, usize)> {
+        self.terminal().span()
+    }
+
+    fn first_token(&self) -> Option<(Token, &str)> {
+        self.terminal().first_token()
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.terminal().last_token()
+    }
+}
+
+/// A Factor
+/// 
+/// This is either a number, a literal, a parenthesized expression, or any of
+/// these after prefix operators (a unary expression).
+/// 
+/// # BNF
+/// ```text
+/// <FACTOR> -> identifier
+///           | literal
+///           | <PARENTHESIZED EXPRESSION>
+///           | <UNARY EXPRESSION>
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the box of `ParenthesizedExpression`, but we can clone
+pub enum Factor {
+    Identifier(Identifier),
+    Literal(Literal),
+    Parenthesized(ParenthesizedExpression),
+    Unary(UnaryExpression),
+}
+impl Bnf for Factor {
+    fn bnf() -> &'static str {
+        concat!(
+            "<FACTOR> -> identifier\n",
+            "          | literal\n",
+            "          | <PARENTHESIZED EXPRESSION>\n",
+            "          | <UNARY EXPRESSION>",
+        )
+    }
+}
+impl Parse for Factor {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork_node("Factor"); // this is to make parse attempts without modifying the original buffer
+        match Identifier::parse(&mut fork) {
+            Ok(identifier) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Factor::Identifier(identifier));
+            },
+            Err(_) => (),
+        }
+
+        let mut fork = buffer.fork_node("Factor"); // this is to make parse attempts without modifying the original buffer
+        match Literal::parse(&mut fork) {
+            Ok(literal) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Factor::Literal(literal));
+            },
+            Err(_) => (),
+        }
+
+        let mut fork = buffer.fork_node("Factor"); // this is to make parse attempts without modifying the original buffer
+        match ParenthesizedExpression::parse(&mut fork) {
+            Ok(parenthesized_expression) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Factor::Parenthesized(parenthesized_expression));
+            },
+            Err(_) => (),
+        }
+
+        let mut fork = buffer.fork_node("Factor"); // this is to make parse attempts without modifying the original buffer
+        match UnaryExpression::parse(&mut fork) {
+            Ok(unary_expression) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(Factor::Unary(unary_expression));
+            },
+            Err(_) => (),
+        }
+
+        Err(format!("Expected either `{} {} {} {}` for {}, but found something else instead", Identifier::parse_label(), Literal::parse_label(), ParenthesizedExpression::parse_label(), UnaryExpression::parse_label(), Self::parse_label()))
+    }
+
+    fn parse_label() -> String {
+        format!("Factor")
+    }
+}
+impl Factor {
+    /// A factor left in the place of one moved out of a link being taken apart
+    /// (see `FactorExtend::into_parts`), which is dropped right away.
+    fn placeholder() -> Factor {
+        Factor::Identifier(Identifier { token: Token::Identifier, lexeme: Default::default(), position: 0 })
+    }
+
+    /// Extends some signature by this factor's, without copying the lexeme of
+    /// an identifier or literal first (see `Terminal::lexeme_str`).
+    fn push_signature(&self, sigg: &mut String) {
+        match self {
+            Factor::Identifier(identifier) => sigg.push_str(identifier.lexeme_str()),
+            Factor::Literal(literal) => sigg.push_str(literal.lexeme_str()),
+            Factor::Parenthesized(parenthesized_expression) => sigg.push_str(&parenthesized_expression.lexeme_signature()),
+            Factor::Unary(unary_expression) => {
+                for op in &unary_expression.ops {
+                    sigg.push_str(op.lexeme_str());
+                }
+                unary_expression.factor.push_signature(sigg);
+            },
+        }
+    }
+}
+impl ParseDisplay for Factor {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        let label = label.unwrap_or("Factor".into());
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
+
+        match self {
+            Factor::Identifier(identifier) => {
+                identifier.display_to(out, depth+1, Some("Variable".into()));
+            },
+            Factor::Literal(literal) => {
+                literal.display_to(out, depth+1, Some("Literal".into()));
+            },
+            Factor::Parenthesized(parenthesized_expression) => {
+                parenthesized_expression.display_to(out, depth+1, None);
+            },
+            Factor::Unary(unary_expression) => {
+                unary_expression.display_to(out, depth+1, None);
+            },
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        match self {
+            Factor::Identifier(identifier) => identifier.lexeme_signature(),
+            Factor::Literal(literal) => literal.lexeme_signature(),
+            Factor::Parenthesized(parenthesized_expression) => parenthesized_expression.lexeme_signature(),
+            Factor::Unary(unary_expression) => unary_expression.lexeme_signature(),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        match self {
+            Factor::Identifier(identifier) => vec![identifier],
+            Factor::Literal(literal) => vec![literal],
+            Factor::Parenthesized(parenthesized_expression) => vec![parenthesized_expression],
+            Factor::Unary(unary_expression) => vec![unary_expression],
+        }
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
+}
+
+/// A Parenthesized Expression
+/// 
+/// This groups any expression as a single factor, as in `(a + b) * c`.
+/// 
+/// # BNF
+/// ```text
+/// <PARENTHESIZED EXPRESSION> -> (<EXPRESSION>)
+/// ```
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the box, but we can clone
+pub struct ParenthesizedExpression {
     pub left_paren: LeftParen,
-    pub type_: Type,
+    pub expression: Box<Expression>,
     pub right_paren: RightParen,
-    pub ident: Identifier,
 }
-impl Parse for TypecastExpression {
+impl Bnf for ParenthesizedExpression {
+    fn bnf() -> &'static str {
+        "<PARENTHESIZED EXPRESSION> -> (<EXPRESSION>)"
+    }
+}
+impl Parse for ParenthesizedExpression {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        let typecast_expression = TypecastExpression {
-            left_paren: LeftParen::parse(&mut fork)?,
-            type_: Type::parse(&mut fork)?,
-            right_paren: RightParen::parse(&mut fork)?,
-            ident: Identifier::parse(&mut fork)?
+        let mut fork = buffer.fork_node("ParenthesizedExpression"); // this is to make parse attempts without modifying the original buffer
+        let left_paren = LeftParen::parse(&mut fork)?;
+        let expression = Box::new(Expression::parse(&mut fork)?);
+        let right_paren = parse_closing(&mut fork, &left_paren.lexeme, left_paren.position)?;
+        let parenthesized_expression = ParenthesizedExpression {
+            left_paren,
+            expression,
+            right_paren,
         };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
-        return Ok(typecast_expression);
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(parenthesized_expression);
     }
 
     fn parse_label() -> String {
-        format!("Typecast Expression")
+        format!("Parenthesized Expression")
     }
 }
-impl ParseDisplay for TypecastExpression {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
-        let label = "Typecast Expression";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
-
-        self.left_paren.display(depth+1, Some("Left Paren".into()));
-        self.type_.display(depth+1, Some("Cast Type".into()));
-        self.right_paren.display(depth+1, Some("Right Paren".into()));
-        self.ident.display(depth+1, Some("Cast Indentifier".into()));
+impl ParseDisplay for ParenthesizedExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Parenthesized Expression";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        self.left_paren.display_to(out, depth+1, Some("Left Paren".into()));
+        self.expression.display_to(out, depth+1, None);
+        self.right_paren.display_to(out, depth+1, Some("Right Paren".into()));
     }
 
     fn lexeme_signature(&self) -> String {
         let mut sigg = String::new();
-        sigg.extend(self.left_paren.lexeme_signature().chars());
-        sigg.extend(self.type_.lexeme_signature().chars());
-        sigg.extend(self.right_paren.lexeme_signature().chars());
-        sigg.extend(self.ident.lexeme_signature().chars());
+        sigg.push_str(self.left_paren.lexeme_str());
+        sigg.extend(self.expression.lexeme_signature().chars());
+        sigg.push_str(self.right_paren.lexeme_str());
         sigg
     }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![&self.left_paren, self.expression.as_ref(), &self.right_paren]
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
 }
 
-/// An Arithmetic Expression
+/// A Unary Expression
+/// 
+/// Every prefix operator of a factor is in this one layer, so any number of
+/// them can be stacked (ex. `--x`, `-+x`, or `!!x`). They are applied right to left:
+/// the operator nearest the factor applies first. A prefix operator binds
+/// tighter than any binary operator (ex. `-a * b` is `(-a) * b`).
+/// 
+/// Every operator is collected into `ops`, so the factor after them is never
+/// itself a unary expression.
 /// 
 /// # BNF
 /// ```text
-/// <ARITHMETIC EXPRESSION> -> <TERM><TERM'>
+/// <UNARY EXPRESSION> -> <UNARY OPERATOR><UNARY OPERATORS><FACTOR>
+/// <UNARY OPERATORS> -> <UNARY OPERATOR><UNARY OPERATORS>
+///                    | ε
+/// <UNARY OPERATOR> -> + | - | !
 /// ```
-#[derive(Clone, Copy)]
-pub struct ArithmeticExpression {
-    pub lhs_term: Term,
-    pub extend: Option<TermExtend>
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the box, but we can clone
+pub struct UnaryExpression {
+    pub ops: Vec<UnaryOp>,
+    pub factor: Box<Factor>,
 }
-impl Parse for ArithmeticExpression {
+impl Bnf for UnaryExpression {
+    fn bnf() -> &'static str {
+        concat!(
+            "<UNARY EXPRESSION> -> <UNARY OPERATOR><UNARY OPERATORS><FACTOR>\n",
+            "<UNARY OPERATORS> -> <UNARY OPERATOR><UNARY OPERATORS>\n",
+            "                   | ε\n",
+            "<UNARY OPERATOR> -> + | - | !",
+        )
+    }
+}
+impl Parse for UnaryExpression {
     fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        let arithmetic_expression = ArithmeticExpression {
-            lhs_term: Term::parse(&mut fork)?,
-            extend: TermExtend::parse(&mut fork)?
+        let mut fork = buffer.fork_node("UnaryExpression"); // this is to make parse attempts without modifying the original buffer
+        let mut ops = vec![UnaryOp::parse(&mut fork)?];
+        while let Ok(op) = UnaryOp::parse(&mut fork) {
+            ops.push(op);
+        }
+        let factor = Box::new(Factor::parse(&mut fork)?);
+        let unary_expression = UnaryExpression {
+            ops,
+            factor,
         };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
-        return Ok(arithmetic_expression);
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(unary_expression);
     }
 
     fn parse_label() -> String {
-        format!("Arithmetic Expression")
+        format!("Unary Expression")
     }
 }
-impl ParseDisplay for ArithmeticExpression {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        
-        let indent = make_indent(depth);
-        let label = "Arithmetic Expression";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
-        
-        self.lhs_term.display(depth+1, None);
-        self.extend.as_ref().map(|extend| extend.display(depth+1, None));
+impl ParseDisplay for UnaryExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        let label = "Unary Expression";
+        let lexemes_label = out.signature(self);
+        out.line(depth, label, Some(lexemes_label));
+
+        for op in &self.ops {
+            op.display_to(out, depth+1, None);
+        }
+        self.factor.display_to(out, depth+1, None);
     }
 
     fn lexeme_signature(&self) -> String {
         let mut sigg = String::new();
-        sigg.extend(self.lhs_term.lexeme_signature().chars());
-        if let Some(ref extend) = self.extend {
-            sigg.push(' ');
-            sigg.extend(extend.lexeme_signature().chars());
+        for op in &self.ops {
+            sigg.push_str(op.lexeme_str());
         }
+        self.factor.push_signature(&mut sigg);
         sigg
     }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = self.ops.iter().map(|op| op as &dyn ParseDisplay).collect();
+        children.push(self.factor.as_ref());
+        children
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
+    }
 }
 
-/// A Term
-/// 
-/// This is basically something maybe seperated by + or -.
-/// 
-/// # BNF
-/// ```text
-/// <TERM> -> <FACTOR><FACTOR'>
-/// ```
-#[derive(Clone, Copy)]
-pub struct Term {
-    pub factor: Factor,
-    pub extend: Option<FactorExtend>
+/// A prefix operator of a unary expression, `+ | - | !`.
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the lexemes of the terminals, but we can clone
+pub enum UnaryOp {
+    Positive(Plus),
+    Negative(Minus),
+    Not(Not),
 }
-impl Parse for Term {
-    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+impl UnaryOp {
+    /// The operator's terminal.
+    fn terminal(&self) -> &dyn ParseDisplay {
+        match self {
+            UnaryOp::Positive(plus) => plus,
+            UnaryOp::Negative(minus) => minus,
+            UnaryOp::Not(not) => not,
+        }
+    }
+
+    /// The operator's lexeme, borrowed from the token stream (see `Terminal::lexeme_str`).
+    pub fn lexeme_str(&self) -> &str {
+        match self {
+            UnaryOp::Positive(plus) => plus.lexeme_str(),
+            UnaryOp::Negative(minus) => minus.lexeme_str(),
+            UnaryOp::Not(not) => not.lexeme_str(),
+        }
+    }
+}
+impl Parse for UnaryOp {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
             Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        let term = Term {
-            factor: Factor::parse(&mut fork)?,
-            extend: FactorExtend::parse(&mut fork)?,
-        };
-        *buffer = fork; // parse was successful: setting the buffer to the fork
-        return Ok(term);
+        match Plus::parse(&mut fork) {
+            Ok(plus) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(UnaryOp::Positive(plus));
+            },
+            Err(_) => ()
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        match Minus::parse(&mut fork) {
+            Ok(minus) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(UnaryOp::Negative(minus));
+            },
+            Err(_) => ()
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        match Not::parse(&mut fork) {
+            Ok(not) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(UnaryOp::Not(not));
+            },
+            Err(_) => ()
+        }
+
+        Err(format!("Expected either `{} {} {}` for {}, but found something else instead", Plus::parse_label(), Minus::parse_label(), Not::parse_label(), Self::parse_label()))
     }
 
     fn parse_label() -> String {
-        format!("Term")
+        format!("Unary Operator")
     }
 }
-impl ParseDisplay for Term {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        
+impl ParseDisplay for UnaryOp {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        self.terminal().display_to(out, depth, Some(label.unwrap_or("Operator".into())));
+    }
 
-        let indent = make_indent(depth);
-        let label = "Term";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
+    fn lexeme_signature(&self) -> String {
+        self.terminal().lexeme_signature()
+    }
 
-        self.factor.display(depth+1, None);
-        self.extend.as_ref().map(|extend| extend.display(depth+1, None));
+    // An operator is a terminal, so it has no children of its own
+    fn span(&self) -> Option<(usize, usize)> {
+        self.terminal().span()
     }
 
-    fn lexeme_signature(&self) -> String {
-        let mut sigg = String::new();
-        sigg.extend(self.factor.lexeme_signature().chars());
-        if let Some(ref extend) = self.extend {
-            sigg.push(' ');
-            sigg.extend(extend.lexeme_signature().chars());
-        }
-        sigg
+    fn first_token(&self) -> Option<(Token, &str)> {
+        self.terminal().first_token()
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.terminal().last_token()
     }
 }
 
-/// A Term's Extension
+/// A Factor's Extension
 /// 
-/// This changes a statement to a statement with an addition.
+/// This changes a statement to a statement with a multiplication, division, or modulo.
 /// 
 /// # BNF
 /// ```text
-/// <TERM'> -> +<TERM>
-///          | -<TERM>
-///          | ε
+/// <FACTOR'> -> *<FACTOR><FACTOR'>
+///            | /<FACTOR><FACTOR'>
+///            | %<FACTOR><FACTOR'>
+///            | ε
 /// ```
 /// 
-/// **Note:** the enum encapsulates the first two non-empty cases.
+/// Each extension holds the rest of the chain (ex. the `/ c` of `a * b / c`),
+/// which is read left to right.
+/// 
+/// **Note:** the struct encapsulates both non-empty cases, which only differ by the `FactorOp`.
 /// The ε option is encapsulated as the `Option<Self>` in the `Parse` implementation
 /// signature
+/// ```ignore
+/// impl Parse<Option<Self>> for FactorExtend
 /// ```
-/// impl Parse<Option<Self>> for TermExtend
-/// ```
-#[derive(Clone, Copy)]
-pub enum TermExtend {
-    Add(Plus, Term),
-    Subtract(Minus, Term),
+/// 
+/// A chain is cloned, compared, dropped, and signed link by link (rather than
+/// recursively), so a long chain cannot overflow the stack.
+pub struct FactorExtend {
+    pub op: FactorOp,
+    pub factor: Factor,
+    pub extend: Option<Box<FactorExtend>>,
 }
-impl Parse<Option<Self>> for TermExtend {
-    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Option<Self>, String> {
-        if buffer.peek().is_none() {
-            return Ok(None);
-        }
+impl Bnf for FactorExtend {
+    fn bnf() -> &'static str {
+        concat!(
+            "<FACTOR'> -> *<FACTOR><FACTOR'>\n",
+            "           | /<FACTOR><FACTOR'>\n",
+            "           | %<FACTOR><FACTOR'>\n",
+            "           | ε",
+        )
+    }
+}
+impl FactorExtend {
+    /// Every link of the chain, from this one to the last one.
+    pub fn links(&self) -> impl Iterator<Item = &FactorExtend> {
+        std::iter::successors(Some(self), |link| link.extend.as_deref())
+    }
+
+    /// The operator, factor, and rest of the chain of this link, taken apart.
+    /// 
+    /// A link cannot be destructured by moving out of it, as it implements `Drop`:
+    /// its factor is swapped out for a placeholder instead.
+    pub fn into_parts(mut self) -> (FactorOp, Factor, Option<Box<FactorExtend>>) {
+        let extend = self.extend.take();
+        let factor = std::mem::replace(&mut self.factor, Factor::placeholder());
+        (self.op.clone(), factor, extend)
+    }
 
+    /// Parses a single link of the chain (an operator and its operand), without the rest of the chain.
+    /// 
+    /// The link's node is started, but only ended along with the rest of the
+    /// chain, as each link holds the rest of the chain.
+    fn parse_link(buffer: &mut crate::ParseBuffer) -> Result<Option<Self>, String> {
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        match Plus::parse(&mut fork) {
-            Ok(plus) => return Term::parse(&mut fork).map(|term| {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                Some(TermExtend::Add(plus, term))
-            }),
-            Err(_) => ()
-        }
-        
+        fork.emit(ParseEvent::StartNode("FactorExtend"));
+        let op = match FactorOp::parse(&mut fork) {
+            Ok(op) => op,
+            Err(_) => return Ok(None),
+        };
+        let factor = Factor::parse(&mut fork)?;
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(Some(FactorExtend { op, factor, extend: None }));
+    }
+}
+impl Parse<Option<Self>> for FactorExtend {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Option<Self>, String> {
+        // The chain is parsed link by link (rather than recursively), so a long chain cannot overflow the stack
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        match Minus::parse(&mut fork) {
-            Ok(minus) => return Term::parse(&mut fork).map(|term| {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                Some(TermExtend::Subtract(minus, term))
-            }),
-            Err(_) => ()
+        let mut links = vec![];
+        while let Some(link) = Self::parse_link(&mut fork)? {
+            links.push(link);
+        }
+        for _ in &links {
+            fork.emit(ParseEvent::EndNode("FactorExtend"));
         }
 
-        Ok(None)
+        // Each link holds the rest of the chain, so build it back to front
+        let mut extend = None;
+        for mut link in links.into_iter().rev() {
+            link.extend = extend;
+            extend = Some(Box::new(link));
+        }
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        return Ok(extend.map(|extend| *extend));
     }
 
     fn parse_label() -> String {
-        format!("Term Extention")
+        format!("Factor Extention")
     }
 }
-impl ParseDisplay for TermExtend {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        
-        let indent = make_indent(depth);
-
-        // Stay at the same depth for Term: We have already been here
-        match self {
-            TermExtend::Add(plus, term) => {
-                println!("{indent}Operator: {}", plus.lexeme_signature());
-                term.display(depth, None);
-            },
-            TermExtend::Subtract(minus, term) => {
-                println!("{indent}Operator: {}", minus.lexeme_signature());
-                term.display(depth, None);
-            },
+impl Clone for FactorExtend {
+    fn clone(&self) -> Self {
+        // Each link holds the rest of the chain, so build it back to front
+        let mut extend = None;
+        for link in self.links().collect::<Vec<_>>().into_iter().rev() {
+            extend = Some(Box::new(FactorExtend { op: link.op.clone(), factor: link.factor.clone(), extend }));
         }
+        *extend.unwrap()
     }
-
-    fn lexeme_signature(&self) -> String {
-        let mut sigg = String::new();
-        match self {
-            TermExtend::Add(plus, term) => {
-                sigg.extend(plus.lexeme_signature().chars());
-                sigg.extend(" ".chars());
-                sigg.extend(term.lexeme_signature().chars());
-            },
-            TermExtend::Subtract(minus, term) => {
-                sigg.extend(minus.lexeme_signature().chars());
-                sigg.extend(" ".chars());
-                sigg.extend(term.lexeme_signature().chars());
-            },
-        };
-        sigg
+}
+impl PartialEq for FactorExtend {
+    fn eq(&self, other: &Self) -> bool {
+        // Compared link by link, so that a long chain cannot overflow the stack
+        let (mut links, mut other_links) = (self.links(), other.links());
+        loop {
+            match (links.next(), other_links.next()) {
+                (Some(link), Some(other_link)) if link.op == other_link.op && link.factor == other_link.factor => (),
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
     }
 }
-
-/// A Factor
-/// 
-/// This is either a number or a literal.
-/// 
-/// # BNF
-/// ```text
-/// <FACTOR> -> identifier
-///           | literal
-/// ```
-#[derive(Clone, Copy)]
-pub enum Factor {
-    Identifier(Identifier),
-    Literal(Literal),
+impl Drop for FactorExtend {
+    fn drop(&mut self) {
+        // unlinking the rest of the chain first, so that no link drops the next one recursively
+        let mut extend = self.extend.take();
+        while let Some(mut link) = extend {
+            extend = link.extend.take();
+        }
+    }
 }
-impl Parse for Factor {
-    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
-        if buffer.peek().is_none() {
-            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+impl ParseDisplay for FactorExtend {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        // Stay at the same depth for Factor: We have already been here,
+        // and the rest of the chain also stays at the same depth
+        for link in self.links() {
+            link.op.display_to(out, depth, None);
+            link.factor.display_to(out, depth, None);
         }
+    }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        match Identifier::parse(&mut fork) {
-            Ok(identifier) => {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                return Ok(Factor::Identifier(identifier));
-            },
-            Err(_) => (),
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        for (i, link) in self.links().enumerate() {
+            if i > 0 {
+                sigg.push(' ');
+            }
+            sigg.push_str(link.op.lexeme_str());
+            sigg.extend(" ".chars());
+            link.factor.push_signature(&mut sigg);
         }
+        sigg
+    }
 
-        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
-        match Literal::parse(&mut fork) {
-            Ok(literal) => {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                return Ok(Factor::Literal(literal));
-            },
-            Err(_) => (),
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        let mut children: Vec<&dyn ParseDisplay> = vec![&self.op, &self.factor];
+        if let Some(ref extend) = self.extend {
+            children.push(extend.as_ref());
         }
+        children
+    }
 
-        Err(format!("Expected either `{} {}` for {}, but found something else instead", Identifier::parse_label(), Literal::parse_label(), Self::parse_label()))
+    fn span(&self) -> Option<(usize, usize)> {
+        let (start, _) = self.op.span()?;
+        let (_, end) = self.links().last()?.factor.span()?;
+        Some((start, end))
     }
 
-    fn parse_label() -> String {
-        format!("Factor")
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.links().last()?.factor.last_token()
+    }
+
+    fn production(&self) -> Option<&'static str> {
+        Some(Self::bnf())
     }
 }
-impl ParseDisplay for Factor {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
-        let label = "Factor";
-        let lexemes_label = self.lexeme_signature();
-        println!("{indent}{label}: {lexemes_label}");
 
+/// The operator of a factor's extension, `* | / | %`.
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the lexemes of the terminals, but we can clone
+pub enum FactorOp {
+    Multiply(Multiply),
+    Divide(Divide),
+    Modulo(Modulo),
+}
+impl FactorOp {
+    /// The operator's terminal.
+    fn terminal(&self) -> &dyn ParseDisplay {
         match self {
-            Factor::Identifier(identifier) => {
-                identifier.display(depth+1, Some("Variable".into()));
-            },
-            Factor::Literal(literal) => {
-                literal.display(depth+1, Some("Literal".into()));
-            },
+            FactorOp::Multiply(multiply) => multiply,
+            FactorOp::Divide(divide) => divide,
+            FactorOp::Modulo(modulo) => modulo,
         }
     }
 
-    fn lexeme_signature(&self) -> String {
+    /// The operator's lexeme, borrowed from the token stream (see `Terminal::lexeme_str`).
+    pub fn lexeme_str(&self) -> &str {
         match self {
-            Factor::Identifier(identifier) => identifier.lexeme_signature(),
-            Factor::Literal(literal) => literal.lexeme_signature(),
+            FactorOp::Multiply(multiply) => multiply.lexeme_str(),
+            FactorOp::Divide(divide) => divide.lexeme_str(),
+            FactorOp::Modulo(modulo) => modulo.lexeme_str(),
         }
     }
-}
 
-/// A Factor's Extension
-/// 
-/// This changes a statement to a statement with a multiplication or division.
-/// 
-/// # BNF
-/// ```text
-/// <FACTOR'> -> *<FACTOR>
-///            | /<FACTOR>
-///            | ε
-/// ```
-/// 
-/// **Note:** the enum encapsulates the first two non-empty cases.
-/// The ε option is encapsulated as the `Option<Self>` in the `Parse` implementation
-/// signature
-/// ```
-/// impl Parse<Option<Self>> for FactorExtend
-/// ```
-#[derive(Clone, Copy)]
-pub enum FactorExtend {
-    Multiply(Multiply, Factor),
-    Divide(Divide, Factor),
+    /// The precedence of the operator, see `operators::precedence_of`.
+    pub fn precedence(&self) -> u8 {
+        let token = match self {
+            FactorOp::Multiply(multiply) => multiply.token(),
+            FactorOp::Divide(divide) => divide.token(),
+            FactorOp::Modulo(modulo) => modulo.token(),
+        };
+        binary_precedence(token)
+    }
 }
-impl Parse<Option<Self>> for FactorExtend {
-    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Option<Self>, String> {
+impl Parse for FactorOp {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
         if buffer.peek().is_none() {
-            return Ok(None);
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
         }
 
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
         match Multiply::parse(&mut fork) {
-            Ok(multiply) => return Factor::parse(&mut fork).map(|factor| {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                Some(FactorExtend::Multiply(multiply, factor))
-            }),
+            Ok(multiply) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(FactorOp::Multiply(multiply));
+            },
             Err(_) => ()
         }
-        
+
         let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
         match Divide::parse(&mut fork) {
-            Ok(divide) => return Factor::parse(&mut fork).map(|factor| {
-                *buffer = fork; // parse was successful: setting the buffer to the fork
-                Some(FactorExtend::Divide(divide, factor))
-            }),
+            Ok(divide) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(FactorOp::Divide(divide));
+            },
+            Err(_) => ()
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        match Modulo::parse(&mut fork) {
+            Ok(modulo) => {
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(FactorOp::Modulo(modulo));
+            },
             Err(_) => ()
         }
 
-        Ok(None)
+        Err(format!("Expected either `{} {} {}` for {}, but found something else instead", Multiply::parse_label(), Divide::parse_label(), Modulo::parse_label(), Self::parse_label()))
     }
 
     fn parse_label() -> String {
-        format!("Factor Extention")
+        format!("Factor Operator")
     }
 }
-impl ParseDisplay for FactorExtend {
-    fn display(&self, depth: usize, _label: Option<String>) {
-        let indent = make_indent(depth);
-
-        // Stay at the same depth for Term: We have already been here
-        match self {
-            FactorExtend::Multiply(multiply, factor) => {
-                println!("{indent}Operator: {}", multiply.lexeme_signature());
-                factor.display(depth, None);
-            },
-            FactorExtend::Divide(divide, factor) => {
-                println!("{indent}Operator: {}", divide.lexeme_signature());
-                factor.display(depth, None);
-            },
-        }
+impl ParseDisplay for FactorOp {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        self.terminal().display_to(out, depth, Some(label.unwrap_or("Operator".into())));
     }
 
     fn lexeme_signature(&self) -> String {
-        let mut sigg = String::new();
-        match self {
-            FactorExtend::Multiply(multiply, factor) => {
-                sigg.extend(multiply.lexeme_signature().chars());
-                sigg.extend(" ".chars());
-                sigg.extend(factor.lexeme_signature().chars());
-            },
-            FactorExtend::Divide(divide, factor) => {
-                sigg.extend(divide.lexeme_signature().chars());
-                sigg.extend(" ".chars());
-                sigg.extend(factor.lexeme_signature().chars());
-            },
-        };
-        sigg
+        self.terminal().lexeme_signature()
+    }
+
+    // An operator is a terminal, so it has no children of its own
+    fn span(&self) -> Option<(usize, usize)> {
+        self.terminal().span()
+    }
+
+    fn first_token(&self) -> Option<(Token, &str)> {
+        self.terminal().first_token()
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.terminal().last_token()
     }
 }
@@ -0,0 +1,59 @@
+//! # Operators
+//! 
+//! What every operator token is, in one place: the grammar itself only knows
+//! its operators through the rules that parse them (ex. `TermOp`, `FactorOp`),
+//! whose precedence (and the binding power of the `pratt` parser) is looked up
+//! here.
+//! 
+//! From loosest to tightest, the precedence of each operator is
+//! 1. `=` (an assignment expression),
+//! 2. `?` and `:` (a conditional expression),
+//! 3. `+` and `-` (a `TermOp`),
+//! 4. `*`, `/`, and `%` (a `FactorOp`),
+//! 5. a prefix `+`, `-`, and `!` (a `UnaryOp`).
+
+use q1_lib::lexer::{Symbol, Token};
+
+/// If the token is an operator between two operands (ex. `+` in `a + b`).
+/// 
+/// The `?` and `:` of a conditional expression take three operands, so neither is binary.
+pub fn is_binary_operator(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Symbol(Symbol::Plus | Symbol::Minus | Symbol::Multiply | Symbol::Divide | Symbol::Modulo | Symbol::Equal)
+    )
+}
+
+/// If the token can be an operator before a single operand (a `UnaryOp`).
+/// 
+/// `+` and `-` can also be the sign of a number lexed by another lexer (ex. its
+/// `-5`, see `Literal::value`).
+pub fn is_unary_operator(token: &Token) -> bool {
+    matches!(token, Token::Symbol(Symbol::Plus | Symbol::Minus | Symbol::Not))
+}
+
+/// The precedence of an operator token, where a higher precedence binds
+/// tighter (ex. `*` is higher than `+`), or `None` if it is not an operator.
+/// 
+/// A `+` or `-` is given its precedence as a binary operator: as a prefix
+/// operator, it binds tighter than any binary operator, as `!` (only ever a
+/// prefix operator) does.
+pub fn precedence_of(token: &Token) -> Option<u8> {
+    match token {
+        Token::Symbol(Symbol::Equal) => Some(1),
+        Token::Symbol(Symbol::Question | Symbol::Colon) => Some(2),
+        Token::Symbol(Symbol::Plus | Symbol::Minus) => Some(3),
+        Token::Symbol(Symbol::Multiply | Symbol::Divide | Symbol::Modulo) => Some(4),
+        Token::Symbol(Symbol::Not) => Some(5),
+        _ => None,
+    }
+}
+
+/// The precedence of a binary operator token, as the operator of a `TermOp`,
+/// `FactorOp`, or `BinaryOp` always is (see `precedence_of`).
+pub(crate) fn binary_precedence(token: Token) -> u8 {
+    match precedence_of(&token) {
+        Some(precedence) if is_binary_operator(&token) => precedence,
+        _ => unreachable!("`{token:?}` is only ever parsed as a binary operator"),
+    }
+}
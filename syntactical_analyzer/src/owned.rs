@@ -0,0 +1,312 @@
+//! # Owned Trees
+//!
+//! A parse tree shares the lexeme of every terminal with the token stream it
+//! was parsed from (see `SharedToken`), so a tree keeps that stream alive.
+//!
+//! `IntoOwned::into_owned` gives every lexeme of a tree its own allocation
+//! instead, so the tree is the sole owner of each of them: it keeps nothing of
+//! the token stream alive, and nothing it holds is shared with another tree.
+//!
+//! Unlike `OwnedTree` (a copy of the displayed tree), the owned tree is of the
+//! same type, so it can still be visited, rewritten, or evaluated.
+//!
+//! The terminals, modulars, tuples, `Either`, and the `pratt` trees implement
+//! `IntoOwned` next to their other implementations.
+
+use crate::non_terminals::*;
+
+/// A node whose lexemes can all be given their own allocation (see the `owned` module).
+pub trait IntoOwned: Sized {
+    /// This node, with every lexeme within it copied out of the token stream.
+    fn into_owned(self) -> Self;
+}
+
+/// A boxed node is owned as the node it boxes.
+impl<N: IntoOwned> IntoOwned for Box<N> {
+    fn into_owned(self) -> Self {
+        Box::new((*self).into_owned())
+    }
+}
+
+/// An optional node is owned as the node it may be.
+impl<N: IntoOwned> IntoOwned for Option<N> {
+    fn into_owned(self) -> Self {
+        self.map(N::into_owned)
+    }
+}
+
+/// A list of nodes is owned node by node.
+impl<N: IntoOwned> IntoOwned for Vec<N> {
+    fn into_owned(self) -> Self {
+        self.into_iter().map(N::into_owned).collect()
+    }
+}
+
+impl IntoOwned for Program {
+    fn into_owned(self) -> Self {
+        Program {
+            function_definitions: self.function_definitions.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for FunctionDefinition {
+    fn into_owned(self) -> Self {
+        FunctionDefinition {
+            qualifiers: self.qualifiers.into_owned(),
+            type_: self.type_.into_owned(),
+            function_name: self.function_name.into_owned(),
+            left_paren: self.left_paren.into_owned(),
+            parameters: self.parameters.into_owned(),
+            right_paren: self.right_paren.into_owned(),
+            left_curly: self.left_curly.into_owned(),
+            compound_statements: self.compound_statements.into_owned(),
+            right_curly: self.right_curly.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for FunctionSignature {
+    fn into_owned(self) -> Self {
+        FunctionSignature {
+            qualifiers: self.qualifiers.into_owned(),
+            type_: self.type_.into_owned(),
+            function_name: self.function_name.into_owned(),
+            left_paren: self.left_paren.into_owned(),
+            parameters: self.parameters.into_owned(),
+            right_paren: self.right_paren.into_owned(),
+            left_curly: self.left_curly.into_owned(),
+            right_curly: self.right_curly.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for FunctionParameter {
+    fn into_owned(self) -> Self {
+        FunctionParameter {
+            qualifiers: self.qualifiers.into_owned(),
+            type_: self.type_.into_owned(),
+            identifier: self.identifier.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for Qualifier {
+    fn into_owned(self) -> Self {
+        match self {
+            Qualifier::Const(const_) => Qualifier::Const(const_.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for Statement {
+    fn into_owned(self) -> Self {
+        match self {
+            Statement::Assignment(assignment_statement) => Statement::Assignment(assignment_statement.into_owned()),
+            Statement::Return(return_statement) => Statement::Return(return_statement.into_owned()),
+            Statement::If(if_statement) => Statement::If(if_statement.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for AssignmentStatement {
+    fn into_owned(self) -> Self {
+        AssignmentStatement {
+            lhs_identifier: self.lhs_identifier.into_owned(),
+            equals: self.equals.into_owned(),
+            expression: self.expression.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for ReturnStatement {
+    fn into_owned(self) -> Self {
+        ReturnStatement {
+            return_: self.return_.into_owned(),
+            expressions: self.expressions.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for ExpressionList {
+    fn into_owned(self) -> Self {
+        ExpressionList {
+            expressions: self.expressions.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for IfStatement {
+    fn into_owned(self) -> Self {
+        IfStatement {
+            if_: self.if_.into_owned(),
+            left_paren: self.left_paren.into_owned(),
+            condition: self.condition.into_owned(),
+            right_paren: self.right_paren.into_owned(),
+            block: self.block.into_owned(),
+            else_tail: self.else_tail.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for ElseTail {
+    fn into_owned(self) -> Self {
+        match self {
+            ElseTail::If(else_, if_statement) => ElseTail::If(else_.into_owned(), if_statement.into_owned()),
+            ElseTail::Block(else_, block) => ElseTail::Block(else_.into_owned(), block.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for Block {
+    fn into_owned(self) -> Self {
+        Block {
+            left_curly: self.left_curly.into_owned(),
+            compound_statements: self.compound_statements.into_owned(),
+            right_curly: self.right_curly.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for Expression {
+    fn into_owned(self) -> Self {
+        match self {
+            Expression::Assignment(assignment_expression) => Expression::Assignment(assignment_expression.into_owned()),
+            Expression::Conditional(conditional_expression) => Expression::Conditional(conditional_expression.into_owned()),
+            Expression::Arithmetic(arithmetic_expression) => Expression::Arithmetic(arithmetic_expression.into_owned()),
+            Expression::Typecast(typecast_expression) => Expression::Typecast(typecast_expression.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for AssignmentExpression {
+    fn into_owned(self) -> Self {
+        AssignmentExpression {
+            target: self.target.into_owned(),
+            equals: self.equals.into_owned(),
+            value: self.value.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for ConditionalExpression {
+    fn into_owned(self) -> Self {
+        ConditionalExpression {
+            condition: self.condition.into_owned(),
+            question: self.question.into_owned(),
+            then_expression: self.then_expression.into_owned(),
+            colon: self.colon.into_owned(),
+            else_expression: self.else_expression.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for TypecastExpression {
+    fn into_owned(self) -> Self {
+        TypecastExpression {
+            left_paren: self.left_paren.into_owned(),
+            type_: self.type_.into_owned(),
+            right_paren: self.right_paren.into_owned(),
+            ident: self.ident.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for ArithmeticExpression {
+    fn into_owned(self) -> Self {
+        ArithmeticExpression {
+            lhs_term: self.lhs_term.into_owned(),
+            extend: self.extend.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for Term {
+    fn into_owned(self) -> Self {
+        Term {
+            factor: self.factor.into_owned(),
+            extend: self.extend.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for TermExtend {
+    fn into_owned(self) -> Self {
+        let (op, term, extend) = self.into_parts();
+        TermExtend {
+            op: op.into_owned(),
+            term: term.into_owned(),
+            extend: extend.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for TermOp {
+    fn into_owned(self) -> Self {
+        match self {
+            TermOp::Add(plus) => TermOp::Add(plus.into_owned()),
+            TermOp::Subtract(minus) => TermOp::Subtract(minus.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for Factor {
+    fn into_owned(self) -> Self {
+        match self {
+            Factor::Identifier(identifier) => Factor::Identifier(identifier.into_owned()),
+            Factor::Literal(literal) => Factor::Literal(literal.into_owned()),
+            Factor::Parenthesized(parenthesized_expression) => Factor::Parenthesized(parenthesized_expression.into_owned()),
+            Factor::Unary(unary_expression) => Factor::Unary(unary_expression.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for FactorExtend {
+    fn into_owned(self) -> Self {
+        let (op, factor, extend) = self.into_parts();
+        FactorExtend {
+            op: op.into_owned(),
+            factor: factor.into_owned(),
+            extend: extend.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for FactorOp {
+    fn into_owned(self) -> Self {
+        match self {
+            FactorOp::Multiply(multiply) => FactorOp::Multiply(multiply.into_owned()),
+            FactorOp::Divide(divide) => FactorOp::Divide(divide.into_owned()),
+            FactorOp::Modulo(modulo) => FactorOp::Modulo(modulo.into_owned()),
+        }
+    }
+}
+
+impl IntoOwned for ParenthesizedExpression {
+    fn into_owned(self) -> Self {
+        ParenthesizedExpression {
+            left_paren: self.left_paren.into_owned(),
+            expression: self.expression.into_owned(),
+            right_paren: self.right_paren.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for UnaryExpression {
+    fn into_owned(self) -> Self {
+        UnaryExpression {
+            ops: self.ops.into_owned(),
+            factor: self.factor.into_owned(),
+        }
+    }
+}
+
+impl IntoOwned for UnaryOp {
+    fn into_owned(self) -> Self {
+        match self {
+            UnaryOp::Positive(plus) => UnaryOp::Positive(plus.into_owned()),
+            UnaryOp::Negative(minus) => UnaryOp::Negative(minus.into_owned()),
+            UnaryOp::Not(not) => UnaryOp::Not(not.into_owned()),
+        }
+    }
+}
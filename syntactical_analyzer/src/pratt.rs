@@ -0,0 +1,305 @@
+//! # Pratt Parser
+//!
+//! An alternative parser for arithmetic expressions, only with the `pratt` feature.
+//!
+//! Rather than a type per precedence level (`ArithmeticExpression`, `Term`,
+//! and `Factor`), every binary operator is parsed by precedence climbing over
+//! the `binding_power` of each, into a uniform `BinaryExpression` tree.
+//! Adding an operator is then a new `BinaryOp` variant, and its precedence in
+//! the `operators` table.
+//!
+//! The trees have the same semantics as the layered parser: (\*/%) binds
+//! tighter than (+-), and every operator is left-associative. An
+//! `ArithmeticExpression` can be converted into the same tree with `From`.
+
+use q1_lib::lexer::Token; // The first and last token of an operator.
+
+use crate::{
+    display::TreeWriter,
+    non_terminals::{ArithmeticExpression, Factor, FactorExtend, FactorOp, Term, TermExtend, TermOp},
+    operators::binary_precedence,
+    owned::IntoOwned,
+    terminals::{Divide, Minus, Modulo, Multiply, Plus, Terminal},
+    Parse,
+    ParseBuffer,
+    ParseDisplay,
+};
+
+/// Every binary operator of an arithmetic expression.
+#[derive(Clone, PartialEq)] // We cannot derive `Copy` due to the lexemes of the terminals, but we can clone
+pub enum BinaryOp {
+    Add(Plus),
+    Subtract(Minus),
+    Multiply(Multiply),
+    Divide(Divide),
+    Modulo(Modulo),
+}
+impl BinaryOp {
+    /// The operator's terminal.
+    fn terminal(&self) -> &dyn ParseDisplay {
+        match self {
+            BinaryOp::Add(plus) => plus,
+            BinaryOp::Subtract(minus) => minus,
+            BinaryOp::Multiply(multiply) => multiply,
+            BinaryOp::Divide(divide) => divide,
+            BinaryOp::Modulo(modulo) => modulo,
+        }
+    }
+
+    /// The precedence of the operator, see `operators::precedence_of`.
+    pub fn precedence(&self) -> u8 {
+        let token = match self {
+            BinaryOp::Add(plus) => plus.token(),
+            BinaryOp::Subtract(minus) => minus.token(),
+            BinaryOp::Multiply(multiply) => multiply.token(),
+            BinaryOp::Divide(divide) => divide.token(),
+            BinaryOp::Modulo(modulo) => modulo.token(),
+        };
+        binary_precedence(token)
+    }
+}
+impl From<TermOp> for BinaryOp {
+    fn from(op: TermOp) -> Self {
+        match op {
+            TermOp::Add(plus) => BinaryOp::Add(plus),
+            TermOp::Subtract(minus) => BinaryOp::Subtract(minus),
+        }
+    }
+}
+impl From<FactorOp> for BinaryOp {
+    fn from(op: FactorOp) -> Self {
+        match op {
+            FactorOp::Multiply(multiply) => BinaryOp::Multiply(multiply),
+            FactorOp::Divide(divide) => BinaryOp::Divide(divide),
+            FactorOp::Modulo(modulo) => BinaryOp::Modulo(modulo),
+        }
+    }
+}
+impl IntoOwned for BinaryOp {
+    fn into_owned(self) -> Self {
+        match self {
+            BinaryOp::Add(plus) => BinaryOp::Add(plus.into_owned()),
+            BinaryOp::Subtract(minus) => BinaryOp::Subtract(minus.into_owned()),
+            BinaryOp::Multiply(multiply) => BinaryOp::Multiply(multiply.into_owned()),
+            BinaryOp::Divide(divide) => BinaryOp::Divide(divide.into_owned()),
+            BinaryOp::Modulo(modulo) => BinaryOp::Modulo(modulo.into_owned()),
+        }
+    }
+}
+impl Parse for BinaryOp {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        if let Ok(term_op) = TermOp::parse(&mut fork) {
+            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+            return Ok(term_op.into());
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        if let Ok(factor_op) = FactorOp::parse(&mut fork) {
+            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+            return Ok(factor_op.into());
+        }
+
+        Err(format!("Expected either `{} {}` for {}, but found something else instead", TermOp::parse_label(), FactorOp::parse_label(), Self::parse_label()))
+    }
+
+    fn parse_label() -> String {
+        "Binary Operator".to_string()
+    }
+}
+impl ParseDisplay for BinaryOp {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        self.terminal().display_to(out, depth, Some(label.unwrap_or("Operator".into())));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        self.terminal().lexeme_signature()
+    }
+
+    // An operator is a terminal, so it has no children of its own
+    fn span(&self) -> Option<(usize, usize)> {
+        self.terminal().span()
+    }
+
+    fn first_token(&self) -> Option<(Token, &str)> {
+        self.terminal().first_token()
+    }
+
+    fn last_token(&self) -> Option<(Token, &str)> {
+        self.terminal().last_token()
+    }
+}
+
+/// The left and right binding power of an operator: the higher, the tighter it
+/// binds, following its precedence (see `operators::precedence_of`).
+///
+/// A left binding power lower than the right one makes the operator
+/// left-associative (ex. `a - b - c` is `(a - b) - c`).
+pub fn binding_power(op: &BinaryOp) -> (u8, u8) {
+    let precedence = op.precedence();
+    (2 * precedence - 1, 2 * precedence)
+}
+
+/// An arithmetic expression, parsed by precedence climbing.
+///
+/// # BNF
+/// ```text
+/// <PRATT EXPRESSION> -> <FACTOR>
+///                     | <PRATT EXPRESSION> <BINARY OP> <PRATT EXPRESSION>
+/// ```
+///
+/// The ambiguity of the BNF is resolved by `binding_power`.
+#[derive(Clone, PartialEq)]
+pub enum PrattExpression {
+    Factor(Factor),
+    Binary(BinaryExpression),
+}
+impl PrattExpression {
+    /// Parses an expression, only continuing with operators that bind at least as tightly as `min_power`.
+    fn parse_binding(buffer: &mut ParseBuffer, min_power: u8) -> Result<Self, String> {
+        let mut lhs = PrattExpression::Factor(Factor::parse(buffer)?);
+
+        loop {
+            let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+            let op = match BinaryOp::parse(&mut fork) {
+                Ok(op) => op,
+                Err(_) => break,
+            };
+            let (left_power, right_power) = binding_power(&op);
+            if left_power < min_power {
+                break;
+            }
+            buffer.commit(fork); // parse was successful: committing the fork to the buffer
+
+            let rhs = Self::parse_binding(buffer, right_power)?;
+            lhs = PrattExpression::Binary(BinaryExpression {
+                lhs: Box::new(lhs),
+                op,
+                rhs: Box::new(rhs),
+            });
+        }
+
+        Ok(lhs)
+    }
+
+    /// Joins some left-hand side to a chain of operators and their operands, left-associatively.
+    fn fold_left(lhs: Self, chain: impl IntoIterator<Item = (BinaryOp, Self)>) -> Self {
+        chain.into_iter().fold(lhs, |lhs, (op, rhs)| {
+            PrattExpression::Binary(BinaryExpression { lhs: Box::new(lhs), op, rhs: Box::new(rhs) })
+        })
+    }
+}
+impl IntoOwned for PrattExpression {
+    fn into_owned(self) -> Self {
+        match self {
+            PrattExpression::Factor(factor) => PrattExpression::Factor(factor.into_owned()),
+            PrattExpression::Binary(binary) => PrattExpression::Binary(binary.into_owned()),
+        }
+    }
+}
+impl Parse for PrattExpression {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        if buffer.peek().is_none() {
+            Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+        }
+
+        let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+        let pratt_expression = Self::parse_binding(&mut fork, 0)?;
+        buffer.commit(fork); // parse was successful: committing the fork to the buffer
+        Ok(pratt_expression)
+    }
+
+    fn parse_label() -> String {
+        "Pratt Expression".to_string()
+    }
+}
+impl ParseDisplay for PrattExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        match self {
+            PrattExpression::Factor(factor) => factor.display_to(out, depth, label),
+            PrattExpression::Binary(binary_expression) => binary_expression.display_to(out, depth, label),
+        }
+    }
+
+    fn lexeme_signature(&self) -> String {
+        match self {
+            PrattExpression::Factor(factor) => factor.lexeme_signature(),
+            PrattExpression::Binary(binary_expression) => binary_expression.lexeme_signature(),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        match self {
+            PrattExpression::Factor(factor) => factor.children(),
+            PrattExpression::Binary(binary_expression) => binary_expression.children(),
+        }
+    }
+}
+impl From<&ArithmeticExpression> for PrattExpression {
+    fn from(arithmetic_expression: &ArithmeticExpression) -> Self {
+        let mut chain = vec![];
+        let mut extend = arithmetic_expression.extend.as_deref();
+        while let Some(TermExtend { op, term, extend: rest }) = extend {
+            chain.push((op.clone().into(), term.into()));
+            extend = rest.as_deref();
+        }
+        Self::fold_left((&arithmetic_expression.lhs_term).into(), chain)
+    }
+}
+impl From<&Term> for PrattExpression {
+    fn from(term: &Term) -> Self {
+        let mut chain = vec![];
+        let mut extend = term.extend.as_deref();
+        while let Some(FactorExtend { op, factor, extend: rest }) = extend {
+            chain.push((op.clone().into(), PrattExpression::Factor(factor.clone())));
+            extend = rest.as_deref();
+        }
+        Self::fold_left(PrattExpression::Factor(term.factor.clone()), chain)
+    }
+}
+
+/// A binary operation of two expressions.
+#[derive(Clone, PartialEq)]
+pub struct BinaryExpression {
+    pub lhs: Box<PrattExpression>,
+    pub op: BinaryOp,
+    pub rhs: Box<PrattExpression>,
+}
+impl IntoOwned for BinaryExpression {
+    fn into_owned(self) -> Self {
+        BinaryExpression {
+            lhs: self.lhs.into_owned(),
+            op: self.op.into_owned(),
+            rhs: self.rhs.into_owned(),
+        }
+    }
+}
+impl ParseDisplay for BinaryExpression {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        let label = label.unwrap_or("Binary Expression".into());
+        let lexemes_label = out.signature(self);
+        out.line(depth, &label, Some(lexemes_label));
+
+        self.lhs.display_to(out, depth+1, Some("Lhs".into()));
+        self.op.display_to(out, depth+1, None);
+        self.rhs.display_to(out, depth+1, Some("Rhs".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        let mut sigg = String::new();
+        sigg.push_str(&self.lhs.lexeme_signature());
+        sigg.push(' ');
+        sigg.push_str(&self.op.lexeme_signature());
+        sigg.push(' ');
+        sigg.push_str(&self.rhs.lexeme_signature());
+        sigg
+    }
+
+    fn children(&self) -> Vec<&dyn ParseDisplay> {
+        vec![self.lhs.as_ref(), &self.op, self.rhs.as_ref()]
+    }
+}
@@ -0,0 +1,366 @@
+//! # Rewriter
+//!
+//! A rewriting traversal over a parse tree, producing a new tree: the mutating
+//! counterpart of the read-only `Visitor`.
+//!
+//! Every `rewrite_*` method of `Rewriter` is a hook, given a node after all of
+//! its children were already rewritten, and returning the node to take its
+//! place. By default, the node is returned as it is, so an implementor only
+//! overrides the nodes it cares about (ex. folding a constant term, or
+//! normalizing a parenthesized factor).
+//!
+//! Only the nodes the `Visitor` visits are rewritten. Any other terminal (ex.
+//! a parenthesis or a semicolon) is kept as it was.
+//!
+//! A rewriter is started from any non-terminal with `Rewritable::rewrite`,
+//! which takes ownership of the tree.
+
+use crate::{
+    non_terminals::*,
+    terminals::{Identifier, Literal, Type},
+};
+
+/// A rewriter of a parse tree, each hook replacing a node (see the `rewrite` module).
+pub trait Rewriter {
+    fn rewrite_function_definition(&mut self, node: FunctionDefinition) -> FunctionDefinition {
+        node
+    }
+    fn rewrite_function_parameters(&mut self, node: FunctionParameters) -> FunctionParameters {
+        node
+    }
+    fn rewrite_function_parameter(&mut self, node: FunctionParameter) -> FunctionParameter {
+        node
+    }
+    fn rewrite_compound_statements(&mut self, node: CompoundStatements) -> CompoundStatements {
+        node
+    }
+    fn rewrite_statement(&mut self, node: Statement) -> Statement {
+        node
+    }
+    fn rewrite_assignment_statement(&mut self, node: AssignmentStatement) -> AssignmentStatement {
+        node
+    }
+    fn rewrite_return_statement(&mut self, node: ReturnStatement) -> ReturnStatement {
+        node
+    }
+    fn rewrite_if_statement(&mut self, node: IfStatement) -> IfStatement {
+        node
+    }
+    fn rewrite_else_tail(&mut self, node: ElseTail) -> ElseTail {
+        node
+    }
+    fn rewrite_block(&mut self, node: Block) -> Block {
+        node
+    }
+    fn rewrite_expression(&mut self, node: Expression) -> Expression {
+        node
+    }
+    fn rewrite_assignment_expression(&mut self, node: AssignmentExpression) -> AssignmentExpression {
+        node
+    }
+    fn rewrite_conditional_expression(&mut self, node: ConditionalExpression) -> ConditionalExpression {
+        node
+    }
+    fn rewrite_typecast_expression(&mut self, node: TypecastExpression) -> TypecastExpression {
+        node
+    }
+    fn rewrite_arithmetic_expression(&mut self, node: ArithmeticExpression) -> ArithmeticExpression {
+        node
+    }
+    fn rewrite_term(&mut self, node: Term) -> Term {
+        node
+    }
+    fn rewrite_term_extend(&mut self, node: TermExtend) -> TermExtend {
+        node
+    }
+    fn rewrite_factor(&mut self, node: Factor) -> Factor {
+        node
+    }
+    fn rewrite_factor_extend(&mut self, node: FactorExtend) -> FactorExtend {
+        node
+    }
+    fn rewrite_parenthesized_expression(&mut self, node: ParenthesizedExpression) -> ParenthesizedExpression {
+        node
+    }
+    fn rewrite_unary_expression(&mut self, node: UnaryExpression) -> UnaryExpression {
+        node
+    }
+
+    fn rewrite_identifier(&mut self, node: Identifier) -> Identifier {
+        node
+    }
+    fn rewrite_literal(&mut self, node: Literal) -> Literal {
+        node
+    }
+    fn rewrite_type(&mut self, node: Type) -> Type {
+        node
+    }
+}
+
+/// A node a `Rewriter` can be started from.
+pub trait Rewritable: Sized {
+    /// Rewrites every child of this node, then this node itself, with the
+    /// matching `rewrite_*` hook of the rewriter.
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self;
+}
+
+/// A boxed node is rewritten as the node it boxes.
+impl<N: Rewritable> Rewritable for Box<N> {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        Box::new((*self).rewrite(rewriter))
+    }
+}
+
+impl Rewritable for FunctionDefinition {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = FunctionDefinition {
+            type_: self.type_.rewrite(rewriter),
+            function_name: self.function_name.rewrite(rewriter),
+            parameters: self.parameters.rewrite(rewriter),
+            compound_statements: self.compound_statements.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_function_definition(node)
+    }
+}
+
+impl Rewritable for FunctionParameters {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = self.into_items()
+            .into_iter()
+            .map(|(parameter, comma)| (parameter.rewrite(rewriter), comma))
+            .collect::<Vec<_>>()
+            .into();
+        rewriter.rewrite_function_parameters(node)
+    }
+}
+
+impl Rewritable for FunctionParameter {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = FunctionParameter {
+            type_: self.type_.rewrite(rewriter),
+            identifier: self.identifier.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_function_parameter(node)
+    }
+}
+
+impl Rewritable for CompoundStatements {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = self.into_items()
+            .into_iter()
+            .map(|(statement, semicolon)| (statement.rewrite(rewriter), semicolon))
+            .collect::<Vec<_>>()
+            .into();
+        rewriter.rewrite_compound_statements(node)
+    }
+}
+
+impl Rewritable for Statement {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = match self {
+            Statement::Assignment(assignment_statement) => Statement::Assignment(assignment_statement.rewrite(rewriter)),
+            Statement::Return(return_statement) => Statement::Return(return_statement.rewrite(rewriter)),
+            Statement::If(if_statement) => Statement::If(if_statement.rewrite(rewriter)),
+        };
+        rewriter.rewrite_statement(node)
+    }
+}
+
+impl Rewritable for AssignmentStatement {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = AssignmentStatement {
+            lhs_identifier: self.lhs_identifier.rewrite(rewriter),
+            expression: self.expression.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_assignment_statement(node)
+    }
+}
+
+impl Rewritable for ReturnStatement {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = ReturnStatement {
+            expressions: self.expressions.into_items()
+                .into_iter()
+                .map(|(expression, comma)| (expression.rewrite(rewriter), comma))
+                .collect::<Vec<_>>()
+                .into(),
+            ..self
+        };
+        rewriter.rewrite_return_statement(node)
+    }
+}
+
+impl Rewritable for IfStatement {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = IfStatement {
+            condition: self.condition.rewrite(rewriter),
+            block: self.block.rewrite(rewriter),
+            else_tail: self.else_tail.map(|else_tail| else_tail.rewrite(rewriter)),
+            ..self
+        };
+        rewriter.rewrite_if_statement(node)
+    }
+}
+
+impl Rewritable for ElseTail {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = match self {
+            ElseTail::If(else_, if_statement) => ElseTail::If(else_, if_statement.rewrite(rewriter)),
+            ElseTail::Block(else_, block) => ElseTail::Block(else_, block.rewrite(rewriter)),
+        };
+        rewriter.rewrite_else_tail(node)
+    }
+}
+
+impl Rewritable for Block {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = Block {
+            compound_statements: self.compound_statements.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_block(node)
+    }
+}
+
+impl Rewritable for Expression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = match self {
+            Expression::Assignment(assignment_expression) => Expression::Assignment(assignment_expression.rewrite(rewriter)),
+            Expression::Conditional(conditional_expression) => Expression::Conditional(conditional_expression.rewrite(rewriter)),
+            Expression::Arithmetic(arithmetic_expression) => Expression::Arithmetic(arithmetic_expression.rewrite(rewriter)),
+            Expression::Typecast(typecast_expression) => Expression::Typecast(typecast_expression.rewrite(rewriter)),
+        };
+        rewriter.rewrite_expression(node)
+    }
+}
+
+impl Rewritable for AssignmentExpression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = AssignmentExpression {
+            target: self.target.rewrite(rewriter),
+            value: self.value.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_assignment_expression(node)
+    }
+}
+
+impl Rewritable for ConditionalExpression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = ConditionalExpression {
+            condition: self.condition.rewrite(rewriter),
+            then_expression: self.then_expression.rewrite(rewriter),
+            else_expression: self.else_expression.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_conditional_expression(node)
+    }
+}
+
+impl Rewritable for TypecastExpression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = TypecastExpression {
+            type_: self.type_.rewrite(rewriter),
+            ident: self.ident.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_typecast_expression(node)
+    }
+}
+
+impl Rewritable for ArithmeticExpression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = ArithmeticExpression {
+            lhs_term: self.lhs_term.rewrite(rewriter),
+            extend: self.extend.map(|extend| extend.rewrite(rewriter)),
+        };
+        rewriter.rewrite_arithmetic_expression(node)
+    }
+}
+
+impl Rewritable for Term {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = Term {
+            factor: self.factor.rewrite(rewriter),
+            extend: self.extend.map(|extend| extend.rewrite(rewriter)),
+        };
+        rewriter.rewrite_term(node)
+    }
+}
+
+impl Rewritable for TermExtend {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let (op, term, extend) = self.into_parts();
+        let node = TermExtend {
+            op,
+            term: term.rewrite(rewriter),
+            extend: extend.map(|extend| extend.rewrite(rewriter)),
+        };
+        rewriter.rewrite_term_extend(node)
+    }
+}
+
+impl Rewritable for Factor {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = match self {
+            Factor::Identifier(identifier) => Factor::Identifier(identifier.rewrite(rewriter)),
+            Factor::Literal(literal) => Factor::Literal(literal.rewrite(rewriter)),
+            Factor::Parenthesized(parenthesized_expression) => Factor::Parenthesized(parenthesized_expression.rewrite(rewriter)),
+            Factor::Unary(unary_expression) => Factor::Unary(unary_expression.rewrite(rewriter)),
+        };
+        rewriter.rewrite_factor(node)
+    }
+}
+
+impl Rewritable for FactorExtend {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let (op, factor, extend) = self.into_parts();
+        let node = FactorExtend {
+            op,
+            factor: factor.rewrite(rewriter),
+            extend: extend.map(|extend| extend.rewrite(rewriter)),
+        };
+        rewriter.rewrite_factor_extend(node)
+    }
+}
+
+impl Rewritable for ParenthesizedExpression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = ParenthesizedExpression {
+            expression: self.expression.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_parenthesized_expression(node)
+    }
+}
+
+impl Rewritable for UnaryExpression {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        let node = UnaryExpression {
+            factor: self.factor.rewrite(rewriter),
+            ..self
+        };
+        rewriter.rewrite_unary_expression(node)
+    }
+}
+
+impl Rewritable for Identifier {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        rewriter.rewrite_identifier(self)
+    }
+}
+
+impl Rewritable for Literal {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        rewriter.rewrite_literal(self)
+    }
+}
+
+impl Rewritable for Type {
+    fn rewrite(self, rewriter: &mut dyn Rewriter) -> Self {
+        rewriter.rewrite_type(self)
+    }
+}
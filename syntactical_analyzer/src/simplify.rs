@@ -0,0 +1,126 @@
+//! # Simplify
+//! 
+//! A rewriting pass removing every redundant parenthesis from an expression,
+//! where dropping it does not change the grouping.
+//! 
+//! A parenthesis is redundant around
+//! - a single factor, as in `(a)` or `((a + b))`,
+//! - a whole expression, as in `x = (a + b)`,
+//! - a product (or quotient) used as a whole term, as in `(a * b) + c`, since
+//!   `*` and `/` already take precedence over `+` and `-`.
+//! 
+//! Any other parenthesis is kept, as in `a * (b + c)`, `(a * b) * c`, or `-(a + b)`.
+
+use crate::non_terminals::*;
+
+impl Expression {
+    /// This expression, without any redundant parentheses.
+    pub fn simplify(&self) -> Expression {
+        match self {
+            Expression::Assignment(assignment_expression) => Expression::Assignment(AssignmentExpression {
+                value: Box::new(assignment_expression.value.simplify()),
+                ..assignment_expression.clone()
+            }),
+            Expression::Conditional(conditional_expression) => Expression::Conditional(ConditionalExpression {
+                condition: simplify_arithmetic(&conditional_expression.condition),
+                then_expression: Box::new(conditional_expression.then_expression.simplify()),
+                else_expression: Box::new(conditional_expression.else_expression.simplify()),
+                ..conditional_expression.clone()
+            }),
+            Expression::Arithmetic(arithmetic_expression) => {
+                let arithmetic_expression = simplify_arithmetic(arithmetic_expression);
+                // a parenthesized whole expression is the inner expression
+                match sole_factor(&arithmetic_expression) {
+                    Some(Factor::Parenthesized(parenthesized_expression)) => *parenthesized_expression.expression.clone(),
+                    _ => Expression::Arithmetic(arithmetic_expression),
+                }
+            },
+            Expression::Typecast(typecast_expression) => Expression::Typecast(typecast_expression.clone()),
+        }
+    }
+}
+
+/// The only factor of an arithmetic expression, if it has no operators.
+fn sole_factor(arithmetic_expression: &ArithmeticExpression) -> Option<&Factor> {
+    match arithmetic_expression {
+        ArithmeticExpression { lhs_term: Term { factor, extend: None }, extend: None } => Some(factor),
+        _ => None,
+    }
+}
+
+/// An arithmetic expression, with every term simplified.
+fn simplify_arithmetic(arithmetic_expression: &ArithmeticExpression) -> ArithmeticExpression {
+    ArithmeticExpression {
+        lhs_term: simplify_term(&arithmetic_expression.lhs_term),
+        extend: arithmetic_expression.extend.as_deref().map(simplify_term_extend).map(Box::new),
+    }
+}
+
+/// A chain of terms, with every term simplified.
+fn simplify_term_extend(extend: &TermExtend) -> TermExtend {
+    TermExtend {
+        op: extend.op.clone(),
+        term: simplify_term(&extend.term),
+        extend: extend.extend.as_deref().map(simplify_term_extend).map(Box::new),
+    }
+}
+
+/// A term, with every factor simplified.
+fn simplify_term(term: &Term) -> Term {
+    let factor = simplify_factor(&term.factor);
+    let extend = term.extend.as_deref().map(simplify_factor_extend).map(Box::new);
+
+    // a parenthesized term, used as a whole term, is the inner term
+    if extend.is_none() {
+        if let Factor::Parenthesized(ref parenthesized_expression) = factor {
+            if let Expression::Arithmetic(ArithmeticExpression { ref lhs_term, extend: None }) = *parenthesized_expression.expression {
+                return lhs_term.clone();
+            }
+        }
+    }
+
+    Term { factor, extend }
+}
+
+/// A chain of factors, with every factor simplified.
+fn simplify_factor_extend(extend: &FactorExtend) -> FactorExtend {
+    FactorExtend {
+        op: extend.op.clone(),
+        factor: simplify_factor(&extend.factor),
+        extend: extend.extend.as_deref().map(simplify_factor_extend).map(Box::new),
+    }
+}
+
+/// A factor, simplified.
+fn simplify_factor(factor: &Factor) -> Factor {
+    match factor {
+        Factor::Parenthesized(parenthesized_expression) => {
+            let expression = parenthesized_expression.expression.simplify();
+
+            // a parenthesized factor is the inner factor
+            if let Expression::Arithmetic(ref arithmetic_expression) = expression {
+                if let Some(factor) = sole_factor(arithmetic_expression) {
+                    return factor.clone();
+                }
+            }
+
+            Factor::Parenthesized(ParenthesizedExpression {
+                expression: Box::new(expression),
+                ..parenthesized_expression.clone()
+            })
+        },
+        Factor::Unary(unary_expression) => {
+            let mut ops = unary_expression.ops.clone();
+            let factor = match simplify_factor(&unary_expression.factor) {
+                // a parenthesized unary expression after prefix operators joins them (ex. `-(-a)` is `--a`)
+                Factor::Unary(inner) => {
+                    ops.extend(inner.ops);
+                    inner.factor
+                },
+                factor => Box::new(factor),
+            };
+            Factor::Unary(UnaryExpression { ops, factor })
+        },
+        factor => factor.clone(),
+    }
+}
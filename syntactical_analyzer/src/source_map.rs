@@ -0,0 +1,95 @@
+//! # Source Map
+//! 
+//! The parse tree only knows the position of each token in the token stream
+//! (see `ParseBuffer::position`), not where it is in the source text. A
+//! `SourceMap` maps a line and column of the source text to a token position,
+//! so that a node can be found by where it is in the source text (see
+//! `ParseDisplay::node_at`), as an editor would (ex. for a hover).
+//! 
+//! A column is counted in characters, where a tab advances to the next tab
+//! stop (see `SourceMap::with_tab_width`). The tab width only changes which
+//! columns are reported (or looked up), never any byte offset.
+
+use std::ops::Range;
+
+/// The default `SourceMap::with_tab_width`.
+pub const DEFAULT_TAB_WIDTH: usize = 1;
+
+/// The byte range of every token of some source text, and where each of its lines start.
+pub struct SourceMap<'s> {
+    source: &'s str,
+    /// The byte range of every token, in order.
+    tokens: Vec<Range<usize>>,
+    /// The byte offset of the start of every line, in order.
+    line_starts: Vec<usize>,
+    /// The columns between two tab stops.
+    tab_width: usize,
+}
+impl<'s> SourceMap<'s> {
+    /// Lexes some source text, to map it to the tokens it is parsed from.
+    pub fn new(source: &'s str) -> Result<Self, String> {
+        let tokens = q1_lib::lex_with_offsets(source)?
+            .into_iter()
+            .map(|(_token, _lexeme, range)| range)
+            .collect();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(newline, _)| newline + 1))
+            .collect();
+        Ok(SourceMap { source, tokens, line_starts, tab_width: DEFAULT_TAB_WIDTH })
+    }
+
+    /// Set the columns between two tab stops, where a tab advances the column
+    /// to the next one (ex. 8 to match the alignment of an editor). The default
+    /// is `DEFAULT_TAB_WIDTH`, where a tab is a single column like any other
+    /// character.
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
+    /// The column after some character at some column.
+    fn advance(&self, column: usize, c: char) -> usize {
+        match c {
+            '\t' => column + self.tab_width - (column - 1) % self.tab_width,
+            _ => column + 1,
+        }
+    }
+
+    /// The byte offset of some line and column (both counted from 1, with the
+    /// column in characters), if the line has that column.
+    /// 
+    /// Every column a tab spans is the offset of the tab itself.
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let line_end = self.line_starts.get(line).copied().unwrap_or(self.source.len());
+        let mut at = 1; // the column of the next character
+        for (offset, c) in self.source[line_start..line_end].char_indices() {
+            let next = self.advance(at, c);
+            if (at..next).contains(&column) {
+                return Some(line_start + offset);
+            }
+            at = next;
+        }
+        None
+    }
+
+    /// The line and column (both counted from 1) of some byte offset, as it
+    /// would be reported (ex. in an error), or `None` if it is past the source.
+    pub fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset > self.source.len() {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|&line_start| line_start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.source.get(line_start..offset)?.chars().fold(1, |column, c| self.advance(column, c));
+        Some((line, column))
+    }
+
+    /// The position (see `ParseBuffer::position`) of the token at some line
+    /// and column, or `None` if there is no token there (ex. whitespace).
+    pub fn token_at(&self, line: usize, column: usize) -> Option<usize> {
+        let offset = self.offset(line, column)?;
+        let position = self.tokens.partition_point(|range| range.end <= offset);
+        self.tokens.get(position)?.contains(&offset).then_some(position)
+    }
+}
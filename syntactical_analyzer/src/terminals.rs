@@ -5,12 +5,14 @@
 //! The types defined in this module may seem familiar to the `Token` variant
 //! from the lexical analyzer: this is no coincidence.
 //! 
-//! All terminal token types have only two parts:
+//! All terminal token types have only three parts:
 //! - Lexical Token Type
 //! - Associated String Lexeme
+//! - Position of the token in the token stream (see `ParseBuffer::position`)
 //! 
-//! Currently, the token is stored, but completely unused. This is helpful for
-//! any future use, especially semantic analysis.
+//! The token of any terminal can be recovered with the `Terminal` trait (ex. to
+//! write a terminal back into a token stream), which is helpful for any future
+//! use, especially semantic analysis.
 //! 
 //! ## Macros
 //! 
@@ -20,43 +22,78 @@
 //! 
 //! This saves 570 lines of code.
 
+use std::num::IntErrorKind; // Telling an int literal too large apart from an invalid one.
+use std::rc::Rc; // The lexeme of a terminal, shared with the token stream it was parsed from.
+
 use q1_lib::lexer::Token;
 use q1_lib::lexer::Symbol as Sym;
+use q1_lib::lexer::Type as TypeToken;
+use q1_lib::lexer::Literal as LiteralToken;
 
-use crate::make_indent;
+use crate::display::TreeWriter;
+use crate::owned::IntoOwned;
 use crate::Parse;
 use crate::ParseDisplay;
 
 /// An extremely helpful DRY macro for trivially implementing `Parse` and `ParseDisplay` for terminal types.
 /// 
-/// This macro takes in 4 arguments:
+/// This macro takes in 4 (or 5) arguments:
 /// - `SELF`: The type that the Parse is being implemented for.
 /// - `token_pat => token`: 2 arguments sperated by `=>`.
 ///     - `token_pat`: some expected `Token` enum, as used in a match expression
 ///     - `token`: some expected resulting `Token`, which can use identifiers from `token_pat`
 /// - `token_label`: some string label for the type of token to be expected.
+/// - `validate` (optional): some function over the parse buffer and the lexeme, returning `Err(_)` to reject it.
 /// 
-/// Using these four arguments, the same template of code can be used to trivially implement
+/// Using these arguments, the same template of code can be used to trivially implement
 /// any terminal `Parse` implementation: it either is or it isn't.
 /// 
 /// Often `token_pat => token` will look identical on both sides,
 /// which is basically just returning the same token.
 /// 
-/// Also, this macro will automatically derive ParseDisplay using $SELF.
+/// Also, this macro will automatically derive ParseDisplay, `Terminal`, and `IntoOwned` using $SELF.
 /// 
 /// - See `Parse` trait for how this library works.
 /// - See `ParseDisplay` for how this library displays.
 macro_rules! impl_terminal_parse {
-    ($SELF: ty, $token_pat:pat => $token:expr, $token_label:expr) => {
+    ($SELF: ty, $token_pat:pat => $token:expr, $token_label:expr $(, $validate:expr)?) => {
+        impl Terminal for $SELF {
+            fn token(&self) -> Token {
+                self.token
+            }
+
+            fn lexeme_str(&self) -> &str {
+                &self.lexeme
+            }
+        }
+        impl IntoOwned for $SELF {
+            fn into_owned(self) -> Self {
+                Self {
+                    lexeme: Rc::new(self.lexeme.to_string()),
+                    ..self
+                }
+            }
+        }
         impl ParseDisplay for $SELF {
-            fn display(&self, depth: usize, label: Option<String>) {
-                let indent = make_indent(depth);
-                let label = label.unwrap_or(Self::parse_label());
-                println!("{indent}{label}: {}", self.lexeme_signature());
+            fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+                let label = label.unwrap_or_else(|| words_of(stringify!($SELF)));
+                out.line(depth, &label, Some(out.signature(self)));
             }
 
             fn lexeme_signature(&self) -> String {
-                self.lexeme.clone()
+                self.lexeme.to_string()
+            }
+
+            fn span(&self) -> Option<(usize, usize)> {
+                Some((self.position, self.position + 1))
+            }
+
+            fn first_token(&self) -> Option<(Token, &str)> {
+                Some((self.token, self.lexeme_str()))
+            }
+
+            fn last_token(&self) -> Option<(Token, &str)> {
+                Some((self.token, self.lexeme_str()))
             }
         }
         impl Parse for $SELF {
@@ -64,22 +101,32 @@ macro_rules! impl_terminal_parse {
                 // We must expect at least *something*,
                 // so we throw an error if there isnt
                 if buffer.peek().is_none() {
-                    Err(format!("Expected `{}`, but found nothing instead", <$SELF>::parse_label()))?
+                    let err = buffer.early_end_error()
+                        .unwrap_or_else(|| format!("Expected `{}`, but found nothing instead", <$SELF>::parse_label()));
+                    Err(err)?
                 }
                 
                 let mut fork = buffer.fork();
-                // With that, we consume the next token in the parse buffer, and match its token.
-                Ok(match fork.next().unwrap() {
+                let position = fork.position();
+                // With that, we consume the next token in the parse buffer, match its token, and validate its lexeme.
+                let next = fork.next().unwrap();
+                Ok(match &next {
                     // If it is the correct token pattern (Ex. `Token::Symbol(syn)`), then return the struct
                     ($token_pat, lexeme) => {
-                        *buffer = fork;
+                        $( $validate(&*buffer, lexeme).map_err(|err| format!("{err} at {}", buffer.describe_position(position)))?; )?
+                        fork.emit(crate::events::ParseEvent::Token($token, lexeme.clone()));
+                        buffer.commit(fork);
                         Self {
                             token: $token,
-                            lexeme
+                            lexeme: lexeme.clone(),
+                            position,
                         }
                     },
                     // otherwise, throw an error
-                    (_token, lexeme) => Err(format!("Expected `{}`, but found `{lexeme}` instead", <$SELF>::parse_label()))?
+                    _ => {
+                        let err = format!("Expected `{}`, but found {} instead", <$SELF>::parse_label(), buffer.describe_found());
+                        Err(buffer.explain_found(err))?
+                    }
                 })
             }
 
@@ -90,107 +137,388 @@ macro_rules! impl_terminal_parse {
     };
 }
 
-#[derive(Clone, Copy)]
+/// The words of a type's name, as the default label of its terminal (ex. `Left Paren` for `LeftParen`).
+/// 
+/// The label a terminal is parsed by (ex. `(`) is only the token expected, not what it is.
+fn words_of(name: &str) -> String {
+    let mut words = String::new();
+    for c in name.chars() {
+        if c.is_uppercase() && !words.is_empty() {
+            words.push(' ');
+        }
+        words.push(c);
+    }
+    words
+}
+
+/// A terminal: a type parsed from exactly one token of the lexer.
+/// 
+/// This is implemented by every terminal of `impl_terminal_parse`, but not by
+/// `Eof`, which has no token.
+pub trait Terminal {
+    /// The lexer token this terminal was parsed from.
+    fn token(&self) -> Token;
+
+    /// The lexeme this terminal was parsed from, shared with the token stream.
+    /// 
+    /// Unlike `lexeme_signature` (whose `String` is a copy of the lexeme), this
+    /// never allocates, so composites extend their signatures from it.
+    fn lexeme_str(&self) -> &str;
+}
+
+/// Every word reserved by the language, which cannot be used as an `Identifier`,
+/// unless the tokens were lexed with another keyword table (see `ParseBuffer::with_keywords`).
+pub const RESERVED_WORDS: &[&str] = &["int", "float", "return", "if", "else", "const"];
+
+/// Rejects any lexeme which is a reserved word of the buffer (see
+/// `ParseBuffer::is_reserved_word`), unless it is a raw identifier (ex. `` `if` ``),
+/// which is never reserved.
+/// 
+/// The lexer should never tag a reserved word as an identifier, but this
+/// guards the parser against it regardless.
+fn reject_reserved_word(buffer: &crate::ParseBuffer, token: Token, lexeme: &str) -> Result<(), String> {
+    if token != Token::RawIdentifier && buffer.is_reserved_word(lexeme) {
+        Err(format!("The reserved word `{lexeme}` cannot be used as an identifier"))?
+    }
+    Ok(())
+}
+
+/// Parses the closing bracket `C` of some already parsed opening bracket.
+///
+/// If the closing bracket is missing, the error names the unclosed opener and
+/// where it is (ex. "Unclosed `(` opened at 1:6, expected `)` before `{` at 1:8"),
+/// rather than only the bracket that was expected.
+pub(crate) fn parse_closing<C: Parse>(buffer: &mut crate::ParseBuffer, opener: &str, opened_at: usize) -> Result<C, String> {
+    let opened_at = buffer.describe_position(opened_at);
+    C::parse(buffer).map_err(|_| match buffer.peek() {
+        Some(_) => {
+            let err = format!("Unclosed `{opener}` opened at {opened_at}, expected `{}` before {}", C::parse_label(), buffer.describe_found());
+            buffer.explain_found(err)
+        },
+        None => format!("Unclosed `{opener}` opened at {opened_at}, expected `{}` before the end of input", C::parse_label()),
+    })
+}
+
+#[derive(Clone, PartialEq)]
 pub struct Identifier {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
-impl_terminal_parse!(Identifier, Token::Identifier => Token::Identifier, "{identifier}");
+impl_terminal_parse!(Identifier, token @ (Token::Identifier | Token::RawIdentifier) => *token, "{identifier}", |buffer, lexeme| reject_reserved_word(buffer, *token, lexeme));
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Type {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Type, Token::Type(type_token) => Token::Type(*type_token), "{type}");
+impl Type {
+    /// Which type keyword this is, read from the token (rather than the lexeme).
+    pub fn kind(&self) -> TypeKind {
+        match self.token {
+            Token::Type(TypeToken::Int) => TypeKind::Int,
+            Token::Type(TypeToken::Float) => TypeKind::Float,
+            _ => unreachable!("a `Type` is only ever parsed from a type token"),
+        }
+    }
+}
 
-#[derive(Clone, Copy)]
+/// Every type keyword a `Type` can be, see `Type::kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeKind {
+    /// the `int` type
+    Int,
+    /// the `float` type
+    Float,
+}
+
+#[derive(Clone, PartialEq)]
 pub struct Equals {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Equals, Token::Symbol(Sym::Equal) => Token::Symbol(Sym::Equal), "=");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Semicolon {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Semicolon, Token::Symbol(Sym::Semicolon) => Token::Symbol(Sym::Semicolon), ";");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Return {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Return, Token::Return => Token::Return, "return");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Literal {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Literal, Token::Literal(literal) => Token::Literal(*literal), "{literal}");
+impl Literal {
+    /// The numeric value of the literal, read from its lexeme.
+    /// 
+    /// Whether it is an int or a float follows the token (rather than the lexeme).
+    /// Besides the decimal and hexadecimal literals of the lexer (`0xFF`, or the
+    /// hex float `0x1.8p3`, as `1.5 * 2^3`), this also reads those of other
+    /// lexers (see `q1_lib::tokens_from_json`): a leading `-`, binary (`0b101`)
+    /// ints, and floats with an exponent (`1.5e3`).
+    /// 
+    /// Every int is an `i64`, so an int outside of `i64::MIN..=i64::MAX` (ex. a
+    /// 30 digit literal) is an "integer literal too large" error, rather than wrapping.
+    pub fn value(&self) -> Result<LiteralValue, String> {
+        let lexeme = self.lexeme_str();
+        match self.token {
+            Token::Literal(LiteralToken::Int) => {
+                let (sign, digits) = match lexeme.strip_prefix('-') {
+                    Some(digits) => ("-", digits),
+                    None => ("", lexeme),
+                };
+                let (radix, digits) = match digits.get(..2) {
+                    Some("0x" | "0X") => (16, &digits[2..]),
+                    Some("0b" | "0B") => (2, &digits[2..]),
+                    _ => (10, digits),
+                };
+                i64::from_str_radix(&format!("{sign}{digits}"), radix)
+                    .map(LiteralValue::Int)
+                    .map_err(|err| match err.kind() {
+                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => format!(
+                            "Integer literal `{lexeme}` is too large, an int must be from {} to {}",
+                            i64::MIN,
+                            i64::MAX
+                        ),
+                        _ => format!("Invalid int literal `{lexeme}`: {err}"),
+                    })
+            },
+            Token::Literal(LiteralToken::Float) => {
+                let (sign, digits) = match lexeme.strip_prefix('-') {
+                    Some(digits) => (-1.0, digits),
+                    None => (1.0, lexeme),
+                };
+                match digits.get(..2) {
+                    Some("0x" | "0X") => hex_float(&digits[2..])
+                        .map(|value| LiteralValue::Float(sign * value))
+                        .map_err(|err| format!("Invalid float literal `{lexeme}`: {err}")),
+                    _ => lexeme.parse::<f64>()
+                        .map(LiteralValue::Float)
+                        .map_err(|err| format!("Invalid float literal `{lexeme}`: {err}")),
+                }
+            },
+            _ => unreachable!("a `Literal` is only ever parsed from a literal token"),
+        }
+    }
+}
+
+/// The value of a hex float (ex. `1.8p3` of `0x1.8p3`), after its `0x`.
+/// 
+/// The hex digits (with an optional fraction) are scaled by 2 to the power of
+/// the decimal exponent after the `p`, which is required.
+fn hex_float(digits: &str) -> Result<f64, String> {
+    let (mantissa, exponent) = digits.split_once(['p', 'P']).ok_or("a hex float must have a `p` exponent".to_string())?;
+    let exponent = exponent.parse::<i32>().map_err(|err| format!("invalid exponent `{exponent}`: {err}"))?;
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        Err("a hex float must have at least one digit".to_string())?
+    }
 
-#[derive(Clone, Copy)]
+    let hex_digit = |digit: char| digit.to_digit(16).map(f64::from).ok_or(format!("invalid hex digit `{digit}`"));
+
+    let mut value = 0.0;
+    for digit in whole.chars() {
+        value = value * 16.0 + hex_digit(digit)?;
+    }
+    // every fraction digit is another 16th of the digit before it
+    let mut scale = 1.0;
+    for digit in fraction.chars() {
+        scale /= 16.0;
+        value += hex_digit(digit)? * scale;
+    }
+    Ok(value * 2f64.powi(exponent))
+}
+
+/// The numeric value of a `Literal`, see `Literal::value`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LiteralValue {
+    Int(i64),
+    Float(f64),
+}
+
+#[derive(Clone, PartialEq)]
 pub struct LeftParen {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(LeftParen, Token::Symbol(Sym::LeftParen) => Token::Symbol(Sym::LeftParen), "(");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct RightParen {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(RightParen, Token::Symbol(Sym::RightParen) => Token::Symbol(Sym::RightParen), ")");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Plus {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Plus, Token::Symbol(Sym::Plus) => Token::Symbol(Sym::Plus), "+");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Minus {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Minus, Token::Symbol(Sym::Minus) => Token::Symbol(Sym::Minus), "-");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
+pub struct Not {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(Not, Token::Symbol(Sym::Not) => Token::Symbol(Sym::Not), "!");
+
+#[derive(Clone, PartialEq)]
 pub struct Multiply {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Multiply, Token::Symbol(Sym::Multiply) => Token::Symbol(Sym::Multiply), "*");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Divide {
     pub token: Token,
-    pub lexeme: &'static String,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Divide, Token::Symbol(Sym::Divide) => Token::Symbol(Sym::Divide), "/");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
+pub struct Modulo {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(Modulo, Token::Symbol(Sym::Modulo) => Token::Symbol(Sym::Modulo), "%");
+
+#[derive(Clone, PartialEq)]
 pub struct Comma {
     pub token: Token,
-    pub lexeme: &'static String
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(Comma, Token::Symbol(Sym::Comma) => Token::Symbol(Sym::Comma), ",");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct LeftCurly {
     pub token: Token,
-    pub lexeme: &'static String
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
 impl_terminal_parse!(LeftCurly, Token::Symbol(Sym::LeftCurly) => Token::Symbol(Sym::LeftCurly), "{");
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct RightCurly {
     pub token: Token,
-    pub lexeme: &'static String
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(RightCurly, Token::Symbol(Sym::RightCurly) => Token::Symbol(Sym::RightCurly), "}");
+
+#[derive(Clone, PartialEq)]
+pub struct If {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(If, Token::If => Token::If, "if");
+
+#[derive(Clone, PartialEq)]
+pub struct Else {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(Else, Token::Else => Token::Else, "else");
+
+#[derive(Clone, PartialEq)]
+pub struct Const {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(Const, Token::Const => Token::Const, "const");
+
+/// A line break, which is only ever in the token stream of a
+/// `q1_lib::lexer::Lexer::with_newlines` (ex. to separate the items of a `LineDelimited`).
+#[derive(Clone, PartialEq)]
+pub struct Newline {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(Newline, Token::Newline => Token::Newline, "{newline}");
+
+/// The end of the input.
+/// 
+/// Unlike every other terminal, this does not consume a token: it only
+/// succeeds if there are no tokens left. This delegates to
+/// `ParseBuffer::expect_eof`.
+#[derive(Clone, PartialEq)]
+pub struct Eof;
+impl IntoOwned for Eof {
+    fn into_owned(self) -> Self {
+        self
+    }
+}
+impl ParseDisplay for Eof {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        let label = label.unwrap_or(Self::parse_label());
+        out.line(depth, &label, None);
+    }
+
+    fn lexeme_signature(&self) -> String {
+        "".into()
+    }
+}
+impl Parse for Eof {
+    fn parse(buffer: &mut crate::ParseBuffer) -> Result<Self, String> {
+        buffer.expect_eof().map(|()| Eof)
+    }
+
+    fn parse_label() -> String {
+        "end of input".to_string()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Question {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
+}
+impl_terminal_parse!(Question, Token::Symbol(Sym::Question) => Token::Symbol(Sym::Question), "?");
+
+#[derive(Clone, PartialEq)]
+pub struct Colon {
+    pub token: Token,
+    pub lexeme: Rc<String>,
+    pub position: usize,
 }
-impl_terminal_parse!(RightCurly, Token::Symbol(Sym::RightCurly) => Token::Symbol(Sym::RightCurly), "}");
\ No newline at end of file
+impl_terminal_parse!(Colon, Token::Symbol(Sym::Colon) => Token::Symbol(Sym::Colon), ":");
@@ -0,0 +1,75 @@
+//! # Tuples
+//!
+//! `Parse`, `ParseDisplay`, and `IntoOwned` for tuples (of 2 up to 6 elements), for building
+//! other grammars on top of this crate.
+//!
+//! Every non-terminal of this crate parses each of its fields in sequence by
+//! hand, but a simple sequence can instead be a tuple, ex.
+//! `<(LeftParen, Type, RightParen)>::parse(buffer)`.
+
+use crate::{
+    display::TreeWriter,
+    owned::IntoOwned,
+    Parse,
+    ParseBuffer,
+    ParseDisplay,
+};
+
+/// Implements `Parse`, `ParseDisplay`, and `IntoOwned` for a tuple of the given types, each with its index.
+///
+/// The elements are parsed in order on a single fork, which is only committed
+/// if every element parsed: a tuple is parsed entirely, or not at all.
+macro_rules! impl_tuple_parse {
+    ($($T:ident $i:tt),+) => {
+        impl<$($T: Parse),+> Parse for ($($T,)+) {
+            fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+                if buffer.peek().is_none() {
+                    Err(format!("Expected `{}`, but found nothing instead", Self::parse_label()))?
+                }
+
+                let mut fork = buffer.fork(); // this is to make parse attempts without modifying the original buffer
+                let tuple = ($($T::parse(&mut fork)?,)+);
+                buffer.commit(fork); // parse was successful: committing the fork to the buffer
+                return Ok(tuple);
+            }
+
+            fn parse_label() -> String {
+                let labels = [$(format!("`{}`", $T::parse_label())),+];
+                format!("Sequence of {}", labels.join(" "))
+            }
+        }
+        impl<$($T: IntoOwned),+> IntoOwned for ($($T,)+) {
+            fn into_owned(self) -> Self {
+                ($(self.$i.into_owned(),)+)
+            }
+        }
+        impl<$($T: ParseDisplay),+> ParseDisplay for ($($T,)+) {
+            fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+                let label = label.unwrap_or("Sequence".into());
+                let lexemes_label = out.signature(self);
+                out.line(depth, &label, Some(lexemes_label));
+
+                $( self.$i.display_to(out, depth+1, None); )+
+            }
+
+            fn lexeme_signature(&self) -> String {
+                let signatures = [$(self.$i.lexeme_signature()),+];
+                signatures.join(" ")
+            }
+
+            fn full_signature(&self) -> String {
+                let signatures = [$(self.$i.full_signature()),+];
+                signatures.join(" ")
+            }
+
+            fn children(&self) -> Vec<&dyn ParseDisplay> {
+                vec![$(&self.$i),+]
+            }
+        }
+    };
+}
+impl_tuple_parse!(A 0, B 1);
+impl_tuple_parse!(A 0, B 1, C 2);
+impl_tuple_parse!(A 0, B 1, C 2, D 3);
+impl_tuple_parse!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple_parse!(A 0, B 1, C 2, D 3, E 4, F 5);
@@ -0,0 +1,302 @@
+//! # Visitor
+//! 
+//! A read-only traversal over a parse tree.
+//! 
+//! Every `visit_*` method of `Visitor` defaults to recursing into the node's
+//! children (through the matching `walk_*` function). An implementor only
+//! overrides the nodes it cares about, calling the `walk_*` function itself
+//! if it still wants to recurse into the children.
+//! 
+//! Terminals have no children, so their `visit_*` methods do nothing by default.
+//! 
+//! A visitor is started from any non-terminal with `Visitable::accept`.
+
+use crate::{
+    non_terminals::*,
+    terminals::{Identifier, Literal, Type},
+};
+
+/// A read-only visitor over a parse tree, borrowing the tree for `'ast`.
+pub trait Visitor<'ast> {
+    fn visit_function_definition(&mut self, node: &'ast FunctionDefinition) {
+        walk_function_definition(self, node);
+    }
+    fn visit_function_parameters(&mut self, node: &'ast FunctionParameters) {
+        walk_function_parameters(self, node);
+    }
+    fn visit_function_parameter(&mut self, node: &'ast FunctionParameter) {
+        walk_function_parameter(self, node);
+    }
+    fn visit_compound_statements(&mut self, node: &'ast CompoundStatements) {
+        walk_compound_statements(self, node);
+    }
+    fn visit_statement(&mut self, node: &'ast Statement) {
+        walk_statement(self, node);
+    }
+    fn visit_assignment_statement(&mut self, node: &'ast AssignmentStatement) {
+        walk_assignment_statement(self, node);
+    }
+    fn visit_return_statement(&mut self, node: &'ast ReturnStatement) {
+        walk_return_statement(self, node);
+    }
+    fn visit_if_statement(&mut self, node: &'ast IfStatement) {
+        walk_if_statement(self, node);
+    }
+    fn visit_else_tail(&mut self, node: &'ast ElseTail) {
+        walk_else_tail(self, node);
+    }
+    fn visit_block(&mut self, node: &'ast Block) {
+        walk_block(self, node);
+    }
+    fn visit_expression(&mut self, node: &'ast Expression) {
+        walk_expression(self, node);
+    }
+    fn visit_assignment_expression(&mut self, node: &'ast AssignmentExpression) {
+        walk_assignment_expression(self, node);
+    }
+    fn visit_conditional_expression(&mut self, node: &'ast ConditionalExpression) {
+        walk_conditional_expression(self, node);
+    }
+    fn visit_typecast_expression(&mut self, node: &'ast TypecastExpression) {
+        walk_typecast_expression(self, node);
+    }
+    fn visit_arithmetic_expression(&mut self, node: &'ast ArithmeticExpression) {
+        walk_arithmetic_expression(self, node);
+    }
+    fn visit_term(&mut self, node: &'ast Term) {
+        walk_term(self, node);
+    }
+    fn visit_term_extend(&mut self, node: &'ast TermExtend) {
+        walk_term_extend(self, node);
+    }
+    fn visit_factor(&mut self, node: &'ast Factor) {
+        walk_factor(self, node);
+    }
+    fn visit_factor_extend(&mut self, node: &'ast FactorExtend) {
+        walk_factor_extend(self, node);
+    }
+    fn visit_parenthesized_expression(&mut self, node: &'ast ParenthesizedExpression) {
+        walk_parenthesized_expression(self, node);
+    }
+    fn visit_unary_expression(&mut self, node: &'ast UnaryExpression) {
+        walk_unary_expression(self, node);
+    }
+
+    fn visit_identifier(&mut self, _node: &'ast Identifier) {}
+    fn visit_literal(&mut self, _node: &'ast Literal) {}
+    fn visit_type(&mut self, _node: &'ast Type) {}
+}
+
+pub fn walk_function_definition<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast FunctionDefinition) {
+    visitor.visit_type(&node.type_);
+    visitor.visit_identifier(&node.function_name);
+    visitor.visit_function_parameters(&node.parameters);
+    visitor.visit_compound_statements(&node.compound_statements);
+}
+
+pub fn walk_function_parameters<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast FunctionParameters) {
+    for (parameter, _comma) in node {
+        visitor.visit_function_parameter(parameter);
+    }
+}
+
+pub fn walk_function_parameter<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast FunctionParameter) {
+    visitor.visit_type(&node.type_);
+    visitor.visit_identifier(&node.identifier);
+}
+
+pub fn walk_compound_statements<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast CompoundStatements) {
+    for (statement, _semicolon) in node {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Statement) {
+    match node {
+        Statement::Assignment(assignment_statement) => visitor.visit_assignment_statement(assignment_statement),
+        Statement::Return(return_statement) => visitor.visit_return_statement(return_statement),
+        Statement::If(if_statement) => visitor.visit_if_statement(if_statement),
+    }
+}
+
+pub fn walk_assignment_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast AssignmentStatement) {
+    visitor.visit_identifier(&node.lhs_identifier);
+    visitor.visit_expression(&node.expression);
+}
+
+pub fn walk_return_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast ReturnStatement) {
+    for (expression, _comma) in &node.expressions {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_if_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast IfStatement) {
+    visitor.visit_expression(&node.condition);
+    visitor.visit_block(&node.block);
+    if let Some(ref else_tail) = node.else_tail {
+        visitor.visit_else_tail(else_tail);
+    }
+}
+
+pub fn walk_else_tail<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast ElseTail) {
+    match node {
+        ElseTail::If(_else, if_statement) => visitor.visit_if_statement(if_statement),
+        ElseTail::Block(_else, block) => visitor.visit_block(block),
+    }
+}
+
+pub fn walk_block<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Block) {
+    visitor.visit_compound_statements(&node.compound_statements);
+}
+
+pub fn walk_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Expression) {
+    match node {
+        Expression::Assignment(assignment_expression) => visitor.visit_assignment_expression(assignment_expression),
+        Expression::Conditional(conditional_expression) => visitor.visit_conditional_expression(conditional_expression),
+        Expression::Arithmetic(arithmetic_expression) => visitor.visit_arithmetic_expression(arithmetic_expression),
+        Expression::Typecast(typecast_expression) => visitor.visit_typecast_expression(typecast_expression),
+    }
+}
+
+pub fn walk_assignment_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast AssignmentExpression) {
+    visitor.visit_identifier(&node.target);
+    visitor.visit_expression(&node.value);
+}
+
+pub fn walk_conditional_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast ConditionalExpression) {
+    visitor.visit_arithmetic_expression(&node.condition);
+    visitor.visit_expression(&node.then_expression);
+    visitor.visit_expression(&node.else_expression);
+}
+
+pub fn walk_typecast_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast TypecastExpression) {
+    visitor.visit_type(&node.type_);
+    visitor.visit_identifier(&node.ident);
+}
+
+pub fn walk_arithmetic_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast ArithmeticExpression) {
+    visitor.visit_term(&node.lhs_term);
+    if let Some(ref extend) = node.extend {
+        visitor.visit_term_extend(extend);
+    }
+}
+
+pub fn walk_term<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Term) {
+    visitor.visit_factor(&node.factor);
+    if let Some(ref extend) = node.extend {
+        visitor.visit_factor_extend(extend);
+    }
+}
+
+pub fn walk_term_extend<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast TermExtend) {
+    visitor.visit_term(&node.term);
+    if let Some(ref extend) = node.extend {
+        visitor.visit_term_extend(extend);
+    }
+}
+
+pub fn walk_factor<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Factor) {
+    match node {
+        Factor::Identifier(identifier) => visitor.visit_identifier(identifier),
+        Factor::Literal(literal) => visitor.visit_literal(literal),
+        Factor::Parenthesized(parenthesized_expression) => visitor.visit_parenthesized_expression(parenthesized_expression),
+        Factor::Unary(unary_expression) => visitor.visit_unary_expression(unary_expression),
+    }
+}
+
+pub fn walk_factor_extend<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast FactorExtend) {
+    visitor.visit_factor(&node.factor);
+    if let Some(ref extend) = node.extend {
+        visitor.visit_factor_extend(extend);
+    }
+}
+
+pub fn walk_parenthesized_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast ParenthesizedExpression) {
+    visitor.visit_expression(&node.expression);
+}
+
+pub fn walk_unary_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast UnaryExpression) {
+    visitor.visit_factor(&node.factor);
+}
+
+/// A node a `Visitor` can be started from.
+pub trait Visitable {
+    /// Calls the matching `visit_*` method of the visitor on this node.
+    fn accept<'ast, V: Visitor<'ast> + ?Sized>(&'ast self, visitor: &mut V);
+
+    /// Every identifier in this subtree, in source order.
+    /// 
+    /// This includes both where an identifier is declared (ex. the function
+    /// name, or a parameter) and where it is used (ex. a variable in an expression).
+    fn identifiers(&self) -> Vec<&Identifier> {
+        let mut collector = IdentifierCollector::default();
+        self.accept(&mut collector);
+        collector.identifiers
+    }
+
+    /// Every literal in this subtree, in source order (ex. to build a
+    /// constant pool, with the value of each from `Literal::value`).
+    fn collect_literals(&self) -> Vec<&Literal> {
+        let mut collector = LiteralCollector::default();
+        self.accept(&mut collector);
+        collector.literals
+    }
+}
+
+/// Implements `Visitable` for each type, with the `visit_*` method to call on it.
+macro_rules! impl_visitable {
+    ($($SELF:ty => $visit:ident),* $(,)?) => {
+        $(
+            impl Visitable for $SELF {
+                fn accept<'ast, V: Visitor<'ast> + ?Sized>(&'ast self, visitor: &mut V) {
+                    visitor.$visit(self);
+                }
+            }
+        )*
+    };
+}
+impl_visitable!(
+    FunctionDefinition => visit_function_definition,
+    FunctionParameters => visit_function_parameters,
+    FunctionParameter => visit_function_parameter,
+    CompoundStatements => visit_compound_statements,
+    Statement => visit_statement,
+    AssignmentStatement => visit_assignment_statement,
+    ReturnStatement => visit_return_statement,
+    IfStatement => visit_if_statement,
+    ElseTail => visit_else_tail,
+    Block => visit_block,
+    Expression => visit_expression,
+    AssignmentExpression => visit_assignment_expression,
+    ConditionalExpression => visit_conditional_expression,
+    TypecastExpression => visit_typecast_expression,
+    ArithmeticExpression => visit_arithmetic_expression,
+    Term => visit_term,
+    TermExtend => visit_term_extend,
+    Factor => visit_factor,
+    FactorExtend => visit_factor_extend,
+    ParenthesizedExpression => visit_parenthesized_expression,
+    UnaryExpression => visit_unary_expression,
+);
+
+/// The visitor behind `Visitable::identifiers`.
+#[derive(Default)]
+struct IdentifierCollector<'ast> {
+    identifiers: Vec<&'ast Identifier>,
+}
+impl<'ast> Visitor<'ast> for IdentifierCollector<'ast> {
+    fn visit_identifier(&mut self, node: &'ast Identifier) {
+        self.identifiers.push(node);
+    }
+}
+
+/// The visitor behind `Visitable::collect_literals`.
+#[derive(Default)]
+struct LiteralCollector<'ast> {
+    literals: Vec<&'ast Literal>,
+}
+impl<'ast> Visitor<'ast> for LiteralCollector<'ast> {
+    fn visit_literal(&mut self, node: &'ast Literal) {
+        self.literals.push(node);
+    }
+}
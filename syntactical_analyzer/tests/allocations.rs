@@ -0,0 +1,48 @@
+//! Tests of how much the display allocates, with an allocator counting every allocation.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use q2_lib::{non_terminals::FunctionDefinition, parse_str, ParseDisplay};
+
+/// The system allocator, counting every allocation made through it.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations made by `f`.
+fn allocations_of(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn signatures_borrow_the_lexemes_of_terminals() {
+    let body = "x = (int)y; return x + y * 2;".repeat(500);
+    let source = format!("int f(int x, float y) {{ {body} }}");
+    let function_definition = parse_str::<FunctionDefinition>(&source).unwrap();
+    let terminals = q1_lib::lex(&source).unwrap().len();
+
+    let allocations = allocations_of(|| {
+        function_definition.full_signature();
+    });
+    // copying every lexeme would be (at least) one allocation per terminal
+    assert!(allocations < terminals, "{allocations} allocations for {terminals} terminals");
+}
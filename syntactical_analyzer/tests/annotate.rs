@@ -0,0 +1,46 @@
+//! Tests of the side table of annotations on parse tree nodes.
+
+use q2_lib::{
+    annotate::{Annotations, NodeId},
+    non_terminals::*,
+    parse_str,
+    terminals::TypeKind,
+};
+
+#[test]
+fn factors_can_be_annotated_with_a_type() {
+    let expression = parse_str::<ArithmeticExpression>("x * 2.5 + 1").unwrap();
+    let term = &expression.lhs_term;
+    let x = &term.factor;
+    let literal = &term.extend.as_ref().unwrap().factor;
+
+    let mut types = Annotations::new();
+    assert_eq!(types.set(x, TypeKind::Int), None);
+    types.set(literal, TypeKind::Float);
+    assert_eq!(types.get(x), Some(&TypeKind::Int));
+    assert_eq!(types.get(literal), Some(&TypeKind::Float));
+    assert_eq!(types.set(x, TypeKind::Float), Some(TypeKind::Int));
+    assert_eq!(types.len(), 2);
+
+    // a node of another type (or span) is another node, even over the same tokens
+    assert_eq!(types.get(term), None);
+    assert_ne!(NodeId::of(term), NodeId::of(x));
+    assert_eq!(types.get(&expression.extend.as_ref().unwrap().term.factor), None);
+
+    // the same tokens parsed again are the same node
+    let reparsed = parse_str::<ArithmeticExpression>("x * 2.5 + 1").unwrap();
+    assert_eq!(NodeId::of(&reparsed.lhs_term.factor), NodeId::of(x));
+
+    // two empty lists of the same type are each where they were parsed
+    let statement = parse_str::<IfStatement>("if (x) { } else { }").unwrap();
+    let Some(ElseTail::Block(_, else_block)) = &statement.else_tail else {
+        panic!("expected an else block");
+    };
+    let (then_statements, else_statements) = (&statement.block.compound_statements, &else_block.compound_statements);
+    assert_ne!(NodeId::of(then_statements), NodeId::of(else_statements));
+    let mut notes = Annotations::new();
+    notes.set(then_statements, "then");
+    notes.set(else_statements, "else");
+    assert_eq!(notes.get(then_statements), Some(&"then"));
+    assert_eq!(notes.get(else_statements), Some(&"else"));
+}
@@ -0,0 +1,43 @@
+//! Tests of the binary's `--check` mode, by its exit code alone.
+
+use std::{path::PathBuf, process::Command};
+
+/// Runs the binary with `--check` over a `fixtures/check/<name>.src` file.
+fn check(name: &str) -> std::process::Output {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("check").join(format!("{name}.src"));
+    Command::new(env!("CARGO_BIN_EXE_Q2")).arg(path).arg("--check").output().unwrap()
+}
+
+#[test]
+fn check_mode_only_sets_the_exit_code() {
+    let valid = check("valid");
+    assert_eq!(valid.status.code(), Some(0));
+    assert!(valid.stdout.is_empty());
+    assert!(valid.stderr.is_empty());
+
+    let invalid = check("invalid");
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(invalid.stdout.is_empty());
+    let stderr = String::from_utf8(invalid.stderr).unwrap();
+    assert_eq!(stderr.lines().count(), 1, "`{stderr}` is not one line");
+    assert!(stderr.starts_with("PARSE ERROR: "));
+}
+
+#[test]
+fn a_blank_input_has_its_own_error() {
+    for name in ["blank", "shebang"] {
+        let blank = check(name);
+        assert_eq!(blank.status.code(), Some(1));
+        let stderr = String::from_utf8(blank.stderr).unwrap();
+        assert_eq!(stderr, "PARSE ERROR: The input contains no tokens (only whitespace, or a shebang line)\n");
+    }
+}
+
+#[test]
+fn a_lexical_error_fails_like_a_parse_error() {
+    let lexical = check("lexical");
+    assert_eq!(lexical.status.code(), Some(1));
+    assert!(lexical.stdout.is_empty());
+    let stderr = String::from_utf8(lexical.stderr).unwrap();
+    assert_eq!(stderr, "LEXICAL ERROR: Unknown character `0x40` at 2:9\n");
+}
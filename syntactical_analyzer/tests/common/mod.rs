@@ -0,0 +1,9 @@
+//! Helpers shared between the integration tests.
+
+use q1_lib::lex;
+use q2_lib::ParseBuffer;
+
+/// A `ParseBuffer` over the tokens of some source, which must lex.
+pub fn buffer_of(source: &str) -> ParseBuffer {
+    ParseBuffer::from_tokens(lex(source).unwrap())
+}
@@ -0,0 +1,324 @@
+//! Tests of the configurable display of a parse tree.
+
+use q1_lib::lex;
+use q2_lib::{
+    display::{DisplayConfig, IndentStyle, OutputFormat, TreeSink, TreeWriter},
+    non_terminals::{AssignmentStatement, CompoundStatements, FunctionDefinition, Term},
+    format_tokens, parse_str,
+    terminals::{Eof, Identifier},
+    Parse, ParseBuffer, ParseDisplay,
+};
+
+#[test]
+fn tab_mode_indents_with_one_tab_per_level() {
+    let statement = parse_str::<AssignmentStatement>("y = x + 10").unwrap();
+    let config = DisplayConfig { indent_style: IndentStyle::Tabs, ..Default::default() };
+
+    assert_eq!(
+        statement.render_with(0, None, config),
+        "Assignment Statement: y = x + 10\n\
+         \tIdentifier: y\n\
+         \tEquals: =\n\
+         \tExpression:\n\
+         \t\tArithmetic Expression: x + 10\n\
+         \t\t\tTerm: x\n\
+         \t\t\t\tFactor: x\n\
+         \t\t\t\t\tVariable: x\n\
+         \t\t\tOperator: +\n\
+         \t\t\tTerm: 10\n\
+         \t\t\t\tFactor: 10\n\
+         \t\t\t\t\tLiteral: 10\n"
+    );
+
+    // the default is unchanged, 4 spaces per level
+    assert_eq!(
+        statement.render(0, None).replace("    ", "\t"),
+        statement.render_with(0, None, config)
+    );
+}
+
+#[test]
+fn every_output_format_renders_the_same_tree() {
+    let term = parse_str::<Term>("x * 2").unwrap();
+
+    assert_eq!(term.render_as(OutputFormat::Tree), term.render(0, None));
+    assert_eq!(
+        term.render_as(OutputFormat::Sexpr),
+        "(term\n  \
+           (factor\n    (variable \"x\"))\n  \
+           (operator \"*\")\n  \
+           (factor\n    (literal \"2\")))\n"
+    );
+    assert_eq!(
+        term.render_as(OutputFormat::Json),
+        "{\"label\": \"Term\", \"signature\": \"x * 2\", \"children\": [\
+           {\"label\": \"Factor\", \"signature\": \"x\", \"children\": [{\"label\": \"Variable\", \"signature\": \"x\", \"children\": []}]}, \
+           {\"label\": \"Operator\", \"signature\": \"*\", \"children\": []}, \
+           {\"label\": \"Factor\", \"signature\": \"2\", \"children\": [{\"label\": \"Literal\", \"signature\": \"2\", \"children\": []}]}\
+         ]}\n"
+    );
+
+    let dot = term.render_as(OutputFormat::Dot);
+    assert!(dot.starts_with("digraph parse_tree {\n    n0 [label=\"Term\\nx * 2\"];\n"));
+    assert_eq!(dot.matches(" -> ").count(), 5);
+}
+
+/// Counts each call of a walk, and the deepest it went.
+#[derive(Default)]
+struct CountingSink {
+    enters: usize,
+    leaves: usize,
+    leafs: Vec<String>,
+    depth: usize,
+    max_depth: usize,
+}
+impl TreeSink for CountingSink {
+    fn enter(&mut self, _label: &str, _signature: Option<&str>) {
+        self.enters += 1;
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+    }
+
+    fn leaf(&mut self, label: &str, signature: Option<&str>) {
+        self.leafs.push(format!("{label}: {}", signature.unwrap_or_default()));
+    }
+
+    fn leave(&mut self, _label: &str) {
+        self.leaves += 1;
+        self.depth -= 1;
+    }
+}
+
+#[test]
+fn a_tree_can_be_walked_into_any_sink() {
+    let term = parse_str::<Term>("x * 2").unwrap();
+    let mut sink = CountingSink::default();
+    term.visit(&mut sink);
+
+    // `Term`, and each `Factor`
+    assert_eq!((sink.enters, sink.leaves, sink.depth, sink.max_depth), (3, 3, 0, 2));
+    assert_eq!(sink.leafs, ["Variable: x", "Operator: *", "Literal: 2"]);
+
+    // the same walk as every other format
+    let nodes = term.render_as(OutputFormat::Sexpr).matches('(').count();
+    assert_eq!(sink.enters + sink.leafs.len(), nodes);
+}
+
+/// A node labeled with a `: ` of its own, which no text of the tree could tell
+/// apart from the `: ` before a signature.
+struct Pair;
+impl ParseDisplay for Pair {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, _label: Option<String>) {
+        out.line(depth, "Key: Value", None);
+        out.line(depth+1, "Value", Some("a: b".into()));
+    }
+
+    fn lexeme_signature(&self) -> String {
+        "a: b".into()
+    }
+}
+
+#[test]
+fn output_formats_are_walked_from_the_nodes() {
+    assert_eq!(
+        Pair.render_as(OutputFormat::Json),
+        "{\"label\": \"Key: Value\", \"signature\": null, \"children\": [{\"label\": \"Value\", \"signature\": \"a: b\", \"children\": []}]}\n"
+    );
+    assert_eq!(Pair.render_as(OutputFormat::Sexpr), "(key:-value\n  (value \"a: b\"))\n");
+}
+
+#[test]
+fn output_formats_parse_by_name() {
+    assert_eq!("sexpr".parse(), Ok(OutputFormat::Sexpr));
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn the_source_format_reformats_canonically() {
+    let source = "const int f(int x,float   y){x=x+1 ;if(x){return (int)y;} else {};y=(a?b:c)*(x-2);}";
+    let formatted = parse_str::<FunctionDefinition>(source).unwrap().render_as(OutputFormat::Source);
+    assert_eq!(
+        formatted,
+        concat!(
+            "const int f(int x, float y) {\n",
+            "    x = x + 1;\n",
+            "    if (x) {\n",
+            "        return (int)y;\n",
+            "    } else {};\n",
+            "    y = (a ? b : c) * (x - 2);\n",
+            "}\n",
+        )
+    );
+
+    // formatting is idempotent
+    let reformatted = parse_str::<FunctionDefinition>(&formatted).unwrap().render_as(OutputFormat::Source);
+    assert_eq!(reformatted, formatted);
+    assert_eq!("source".parse(), Ok(OutputFormat::Source));
+}
+
+#[test]
+fn a_raw_token_stream_is_formatted_without_parsing() {
+    let tokens = lex("int f(){return 1;}").unwrap();
+    assert_eq!(format_tokens(&tokens), "int f() {\n    return 1;\n}\n");
+
+    // the same as the source format of its parse tree
+    let function_definition = parse_str::<FunctionDefinition>("int f(){return 1;}").unwrap();
+    assert_eq!(format_tokens(&tokens), function_definition.render_as(OutputFormat::Source));
+
+    // even if it does not parse
+    let tokens = lex("x=-a*-(b);return - -c;").unwrap();
+    assert_eq!(format_tokens(&tokens), "x = -a * -(b);\nreturn --c;\n");
+    let tokens = lex("x=a*! !b;return -!c;").unwrap();
+    assert_eq!(format_tokens(&tokens), "x = a * !!b;\nreturn -!c;\n");
+
+    let tokens = lex("int f(){x = = 1 return}").unwrap();
+    assert_eq!(format_tokens(&tokens), "int f() {\n    x = = 1 return\n}\n");
+}
+
+#[test]
+fn empty_lists_are_marked_empty() {
+    let function = parse_str::<FunctionDefinition>("int f(){}").unwrap();
+    let rendered = function.render(0, None);
+
+    assert!(rendered.contains("\n    Function Parameters: (empty)\n"));
+    assert!(rendered.contains("\n    Compound Statements: (empty)\n"));
+
+    // the signature itself is unchanged
+    assert_eq!(function.lexeme_signature(), "int f () {....}");
+}
+
+#[test]
+fn parameters_can_be_displayed_sorted_by_name() {
+    let function_definition = parse_str::<FunctionDefinition>("int f(int z, int a){}").unwrap();
+    let config = DisplayConfig { sort_parameters: true, ..Default::default() };
+    let sorted = function_definition.render_with(0, None, config);
+
+    assert!(sorted.starts_with("Function Definition: int f (int a, int z) {....}\n"));
+    assert!(sorted.contains("Function Parameters: int a, int z\n"));
+    assert!(function_definition.render(0, None).contains("Function Parameters: int z, int a\n"));
+
+    // only the display is sorted, never the parse tree
+    assert_eq!(function_definition.parameters.items()[0].0.identifier.lexeme.as_str(), "z");
+
+    // every other output format is sorted all the same
+    assert!(function_definition.render_as_with(OutputFormat::Json, config).contains("\"int f (int a, int z) {....}\""));
+}
+
+#[test]
+fn signatures_can_be_cut_short() {
+    let compound_statements = parse_str::<CompoundStatements>("total = total + value * 2; return total;").unwrap();
+    let config = DisplayConfig { max_signature_len: Some(12), ..Default::default() };
+    let short = compound_statements.render_with(0, None, config);
+
+    assert!(short.starts_with("Terminated Sequence of `Statement` by `;`: total = tota...\n"));
+    assert!(short.contains("Arithmetic Expression: total + valu...\n"));
+    assert!(short.contains("Return Statement: return total\n"));
+
+    // a cut signature is always a prefix of the whole signature
+    let whole = compound_statements.lexeme_signature();
+    let cut = compound_statements.short_signature(12);
+    assert!(whole.starts_with(cut.trim_end_matches("...")));
+    assert_eq!(compound_statements.short_signature(whole.len()), whole);
+}
+
+#[test]
+fn every_line_of_the_tree_is_cut_short() {
+    let source = "int f(const float alpha_value, int beta_value) {
+        if (alpha_value) { x = alpha_value; }
+        else if (beta_value) { x = beta_value; }
+        else { x = alpha_value + beta_value; };
+        return extremely_long_identifier_name;
+    }";
+    let function_definition = parse_str::<FunctionDefinition>(source).unwrap();
+    let config = DisplayConfig { max_signature_len: Some(10), ..Default::default() };
+    let short = function_definition.render_with(0, None, config);
+
+    // the branches of an if statement, qualifiers, and terminals are cut all the same
+    assert!(short.contains("If Branch: if (alpha_...\n"), "{short}");
+    assert!(short.contains("Else If Branch: else if (b...\n"), "{short}");
+    assert!(short.contains("Else Branch: else {.......\n"), "{short}");
+    assert!(short.contains("Variable: extremely_...\n"), "{short}");
+    for line in short.lines() {
+        let signature = line.split_once(": ").map_or("", |(_, signature)| signature);
+        assert!(signature.len() <= 10 + "...".len(), "`{line}` is not cut short");
+    }
+}
+
+#[test]
+fn the_tree_can_start_at_a_base_depth() {
+    let function_definition = parse_str::<FunctionDefinition>("int f(int x) { return x; }").unwrap();
+    let config = DisplayConfig { base_depth: 2, ..Default::default() };
+    let shifted = function_definition.render_as_with(OutputFormat::Tree, config);
+    let unshifted = function_definition.render(0, None);
+
+    // every line is shifted right by 8 spaces, and nothing else changes
+    assert_eq!(shifted.lines().count(), unshifted.lines().count());
+    for (shifted, unshifted) in shifted.lines().zip(unshifted.lines()) {
+        assert_eq!(shifted, format!("        {unshifted}"));
+    }
+
+    // only the tree is shifted
+    assert_eq!(function_definition.render_as_with(OutputFormat::Json, config), function_definition.render_as(OutputFormat::Json));
+}
+
+#[test]
+fn owned_trees_outlive_their_token_stream() {
+    const SOURCE: &str = "int f(int x) { if (x) { x = 1; }; return x * 2; }";
+    let owned = {
+        let tokens = lex(SOURCE).unwrap();
+        let mut buffer = ParseBuffer::from_tokens(tokens);
+        FunctionDefinition::parse(&mut buffer).unwrap().to_owned_tree()
+    };
+
+    let parsed = parse_str::<FunctionDefinition>(SOURCE).unwrap();
+    assert_eq!(owned.render(0, None), parsed.render(0, None));
+    assert_eq!(owned.lexeme_signature(), parsed.lexeme_signature());
+    assert_eq!(owned.render_as(OutputFormat::Json), parsed.render_as(OutputFormat::Json));
+    assert_eq!(owned.children[1].label, "Function Identifier");
+
+    // even a line without a signature (or a colon) is kept as it was
+    let tuple = parse_str::<(Identifier, Eof)>("x").unwrap();
+    assert_eq!(tuple.to_owned_tree().render(0, None), tuple.render(0, None));
+}
+
+#[test]
+fn owned_trees_borrow_nothing_from_their_buffer() {
+    let parsed = parse_str::<Identifier>("x").unwrap();
+    let owned = parsed.to_owned_tree();
+
+    // the lexeme is copied, rather than pointing into the token stream
+    let signature = owned.signature.as_deref().unwrap();
+    assert_eq!(signature, parsed.lexeme.as_str());
+    assert_ne!(signature.as_ptr(), parsed.lexeme.as_ptr());
+
+    // so the tree can be sent away from the buffer, to another thread
+    let function_definition = parse_str::<FunctionDefinition>("int f(int x) { return x * 2; }").unwrap();
+    let owned = function_definition.to_owned_tree();
+    let rendered = std::thread::spawn(move || owned.render(0, None)).join().unwrap();
+    assert_eq!(rendered, function_definition.render(0, None));
+}
+
+#[test]
+fn diffs_find_where_two_trees_differ() {
+    let expected = parse_str::<FunctionDefinition>("int f(int x) { x = x + 1; return x; }").unwrap();
+    let got = parse_str::<FunctionDefinition>("int f(int x) { x = x - 1; return x; }").unwrap();
+    assert_eq!(
+        got.diff(&expected),
+        ["Function Definition > Compound Statements > [0] Statement > Assignment Statement > Expression \
+          > Arithmetic Expression > Operator: expected `+`, got `-`"]
+    );
+    assert!(expected.diff(&expected).is_empty());
+
+    // a different structure is reported where it starts, rather than under it
+    let got = parse_str::<FunctionDefinition>("int f(int x) { x = (x); }").unwrap();
+    let differences = got.diff(&expected);
+    assert_eq!(differences, [
+        "Function Definition > Compound Statements > Statement > Assignment Statement > Expression \
+         > Arithmetic Expression > Term > Factor: expected `Variable`, got `Parenthesized Expression`",
+        "Function Definition > Compound Statements > Statement > Assignment Statement > Expression \
+         > Arithmetic Expression: missing `Operator`",
+        "Function Definition > Compound Statements > Statement > Assignment Statement > Expression \
+         > Arithmetic Expression: missing `Term`",
+        "Function Definition > Compound Statements: missing `Statement`",
+    ]);
+}
@@ -0,0 +1,20 @@
+//! Tests of the generic `Either` alternation.
+
+use q2_lib::{either::Either, parse_str, terminals::{Identifier, Literal}, ParseDisplay};
+
+#[test]
+fn either_parses_the_first_alternative_that_fits() {
+    let parsed = parse_str::<Either<Identifier, Literal>>("x").unwrap();
+    assert!(matches!(parsed, Either::Left(_)));
+    assert_eq!(parsed.lexeme_signature(), "x");
+
+    let parsed = parse_str::<Either<Identifier, Literal>>("2.5").unwrap();
+    assert!(matches!(parsed, Either::Right(_)));
+    assert_eq!(parsed.render(0, Some("Value".into())), "Value: 2.5\n");
+}
+
+#[test]
+fn either_reports_both_alternatives() {
+    let err = parse_str::<Either<Identifier, Literal>>("+").err().unwrap();
+    assert_eq!(err, "Expected either `{identifier} {literal}`, but found something else instead");
+}
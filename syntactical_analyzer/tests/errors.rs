@@ -0,0 +1,233 @@
+//! Tests of how parse errors are reported.
+
+use q1_lib::{lex, lexer::Lexer};
+use q2_lib::{
+    modulars::{Delimited, Terminated},
+    non_terminals::{Block, CompoundStatements, Expression, FunctionDefinition},
+    parse_str, short_error,
+    terminals::*,
+    display::TreeWriter,
+    Parse, ParseBuffer, ParseDisplay,
+};
+
+#[test]
+fn short_error_keeps_only_the_endpoints() {
+    let err = parse_str::<Delimited<Terminated<Identifier, Semicolon>, Comma>>("a; b;, c; d").err().unwrap();
+    assert_eq!(
+        err,
+        "While parsing Delimited Sequence of `Terminated Sequence of `{identifier}` by `;`` by `,`...\n    \
+         While parsing Terminated Sequence of `{identifier}` by `;`...\n    \
+         Expected `;` after `d`, but found nothing instead"
+    );
+    assert_eq!(
+        short_error(&err),
+        "While parsing Delimited Sequence of `Terminated Sequence of `{identifier}` by `;`` by `,`...\n    \
+         Expected `;` after `d`, but found nothing instead"
+    );
+
+    // without any context, there is nothing to shorten
+    let err = parse_str::<Identifier>("1").err().unwrap();
+    assert_eq!(short_error(&err), err);
+}
+
+#[test]
+fn a_missing_closing_bracket_names_its_opener() {
+    let err = parse_str::<FunctionDefinition>("int f(int x {").err().unwrap();
+    assert_eq!(err, "Unclosed `(` opened at 1:6, expected `)` before `{` at 1:13");
+
+    let err = parse_str::<Block>("{ x = 1;").err().unwrap();
+    assert_eq!(err, "Unclosed `{` opened at 1:1, expected `}` before the end of input");
+
+    // the opener is found by its line and column
+    let err = parse_str::<FunctionDefinition>("int f(int x,\n\tfloat y {}").err().unwrap();
+    assert!(err.ends_with("Unclosed `(` opened at 1:6, expected `)` before `{` at 2:10"), "{err}");
+    let err = parse_str::<FunctionDefinition>("int\n\tf(int x {").err().unwrap();
+    assert_eq!(err, "Unclosed `(` opened at 2:3, expected `)` before `{` at 2:10");
+
+    // without the locations of the tokens, only the position is known
+    let err = FunctionDefinition::parse(&mut ParseBuffer::from_tokens(lex("int f(int x {").unwrap())).err().unwrap();
+    assert_eq!(err, "Unclosed `(` opened at token 2, expected `)` before `{` at token 5");
+}
+
+#[test]
+fn an_unclosed_function_body_is_named() {
+    let err = parse_str::<FunctionDefinition>("int f(){").err().unwrap();
+    assert_eq!(err, "Unclosed function body `{` opened at 1:8, expected `}` before the end of input");
+
+    let err = parse_str::<FunctionDefinition>("int f(){ if (x) { x = 1; };").err().unwrap();
+    assert_eq!(err, "Unclosed function body `{` opened at 1:8, expected `}` before the end of input");
+
+    // only the end of input is an unclosed body, and no braces at all is still a missing `{`
+    let err = parse_str::<FunctionDefinition>("int f(){ x = 1; )").err().unwrap();
+    assert!(err.starts_with("Unclosed `{` opened at 1:8"), "{err}");
+    assert!(parse_str::<FunctionDefinition>("int f()").err().unwrap().contains("`{`"));
+}
+
+#[test]
+fn a_blank_input_is_not_a_malformed_one() {
+    for source in ["", "  \n\t\n", "#!/usr/bin/analyze\n\n"] {
+        let mut buffer = ParseBuffer::from_tokens(lex(source).unwrap());
+        assert_eq!(buffer.expect_tokens(), Err("The input contains no tokens (only whitespace, or a shebang line)".to_string()));
+    }
+    assert!(ParseBuffer::from_tokens(lex("x").unwrap()).expect_tokens().is_ok());
+}
+
+#[test]
+fn a_trailing_comma_is_named() {
+    let err = parse_str::<FunctionDefinition>("int f(int x,) {}").err().unwrap();
+    assert_eq!(
+        short_error(&err),
+        "While parsing Delimited Sequence of `Function Parameter` by `,`...\n    \
+         Unexpected trailing `,` before `)`, remove the trailing delimiter"
+    );
+
+    // without a follow, it is only a missing item
+    let err = parse_str::<(LeftParen, Delimited<Identifier, Comma>, RightParen)>("(a,)").err().unwrap();
+    assert!(err.ends_with("Expected `{identifier}`, but found `)` at 1:4 instead"));
+    let err = Delimited::<Identifier, Comma>::parse_followed_by::<RightParen>(&mut ParseBuffer::from_tokens(lex("a, )").unwrap()))
+        .err()
+        .unwrap();
+    assert!(err.ends_with("Unexpected trailing `,` before `)`, remove the trailing delimiter"));
+}
+
+#[test]
+fn a_missing_semicolon_is_not_an_empty_statement() {
+    let err = parse_str::<Block>("{ x = 1 y = 2; }").err().unwrap();
+    assert_eq!(
+        short_error(&err),
+        "While parsing Terminated Sequence of `Statement` by `;`...\n    \
+         Expected `;` after the Assignment Statement `x = 1`, but found `y` at 1:9 instead"
+    );
+
+    let err = parse_str::<Block>("{ x = 1;; y = 2; }").err().unwrap();
+    assert_eq!(
+        short_error(&err),
+        "While parsing Terminated Sequence of `Statement` by `;`...\n    \
+         Unexpected `;` where a `Statement` was expected, an empty `Statement` is not allowed"
+    );
+    assert!(parse_str::<Block>("{ ; }").err().unwrap().ends_with("an empty `Statement` is not allowed"));
+    assert!(parse_str::<Block>("{ x = 1; y = 2; }").is_ok());
+
+    // on its own, a list still ends right before an empty item
+    let mut buffer = ParseBuffer::from_tokens(lex("x = 1;; y = 2;").unwrap());
+    let statements = CompoundStatements::parse(&mut buffer).unwrap();
+    assert_eq!((statements.items().len(), buffer.position()), (1, 4));
+
+    // a lexical error right after an item is still the error, rather than a missing `;`
+    let mut buffer = ParseBuffer::from_lexer(Lexer::new("x = 1 @".bytes()));
+    assert_eq!(CompoundStatements::parse(&mut buffer).err().as_deref(), Some("While parsing Terminated Sequence of `Statement` by `;`...\n    Unknown character `0x40` at 1:7"));
+}
+
+/// A list nested 12 levels deep, each level delimited by a different symbol.
+type Nested = Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<Delimited<
+    Identifier, Comma>, Semicolon>, Colon>, Question>, Plus>, Minus>, Multiply>, Divide>, Equals>, LeftCurly>, RightCurly>, LeftParen>;
+
+/// Every level of `Nested` is missing the identifier after its last delimiter.
+const NESTED: &str = "a ( a } a { a = a / a * a - a + a ? a : a ; a ,";
+
+#[test]
+fn deep_errors_truncate_the_levels_in_between() {
+    let err = parse_str::<Nested>(NESTED).err().unwrap();
+    let lines = err.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 12);
+    assert!(lines[0].starts_with("While parsing Delimited Sequence of "));
+    assert!(lines[0].ends_with(" by `(`..."));
+    assert_eq!(
+        lines[9..],
+        [
+            "    ... (2 more levels)",
+            "    While parsing Delimited Sequence of `{identifier}` by `,`...",
+            "    Expected `{identifier}`, but found nothing instead",
+        ]
+    );
+    // the endpoints are kept
+    assert_eq!(short_error(&err).lines().last(), Some("    Expected `{identifier}`, but found nothing instead"));
+
+    let levels = |max_error_levels: usize| {
+        let mut buffer = ParseBuffer::from_tokens(lex(NESTED).unwrap()).with_max_error_levels(max_error_levels);
+        let err = Nested::parse(&mut buffer).err().unwrap();
+        err.lines().filter(|line| line.trim_start().starts_with("While parsing ")).count()
+    };
+    assert_eq!(levels(12), 12);
+    assert_eq!(levels(3), 3);
+    let mut buffer = ParseBuffer::from_tokens(lex(NESTED).unwrap()).with_max_error_levels(3);
+    assert!(Nested::parse(&mut buffer).err().unwrap().contains("\n    ... (9 more levels)\n"));
+
+    // a single level is the outermost one
+    assert_eq!(levels(1), 1);
+    let mut buffer = ParseBuffer::from_tokens(lex(NESTED).unwrap()).with_max_error_levels(1);
+    let err = Nested::parse(&mut buffer).err().unwrap();
+    let lines = err.lines().collect::<Vec<_>>();
+    assert!(lines[0].ends_with(" by `(`..."), "{err}");
+    assert_eq!(
+        lines[1..],
+        ["    ... (11 more levels)", "    Expected `{identifier}`, but found nothing instead"]
+    );
+}
+
+/// A parenthesized list of lists, ex. `((), (()))`.
+struct List(Box<(LeftParen, Delimited<List, Comma>, RightParen)>);
+impl Parse for List {
+    fn parse(buffer: &mut ParseBuffer) -> Result<Self, String> {
+        Ok(List(Box::new(<(LeftParen, Delimited<List, Comma>, RightParen)>::parse(buffer)?)))
+    }
+
+    fn parse_label() -> String {
+        "List".into()
+    }
+}
+impl ParseDisplay for List {
+    fn display_to(&self, out: &mut TreeWriter, depth: usize, label: Option<String>) {
+        self.0.display_to(out, depth, label);
+    }
+
+    fn lexeme_signature(&self) -> String {
+        self.0.lexeme_signature()
+    }
+}
+
+#[test]
+fn repeated_levels_of_the_same_rule_are_collapsed() {
+    let err = parse_str::<List>("((), ((), ))").err().unwrap();
+    assert_eq!(
+        err,
+        "While parsing Delimited Sequence of `List` by `,` (nested 2 deep)...\n    \
+         Expected `(`, but found `)` at 1:11 instead"
+    );
+
+    let err = parse_str::<List>("((), ((), ((), )))").err().unwrap();
+    assert!(err.starts_with("While parsing Delimited Sequence of `List` by `,` (nested 3 deep)...\n"));
+}
+
+#[test]
+fn a_grouping_that_could_be_a_typecast_is_warned_about() {
+    let warning = "(warning: `(foo)bar` is ambiguous between a typecast of `bar` to the type `foo`, \
+        and the grouping `(foo)` followed by `bar`: it was parsed as the grouping, since `foo` is not a type)";
+
+    let err = parse_str::<Expression>("(foo)bar").err().unwrap();
+    assert_eq!(err, format!("Expected `end of input`, but found `bar` at 1:6 instead {warning}"));
+
+    let err = parse_str::<FunctionDefinition>("int f() { x = (foo)bar; }").err().unwrap();
+    assert!(err.ends_with(&format!("after the Assignment Statement `x = (foo)`, but found `bar` at 1:20 instead {warning}")), "{err}");
+
+    // neither a real typecast, nor a grouping followed by something else
+    assert!(parse_str::<Expression>("(int)bar").is_ok());
+    assert!(!parse_str::<Expression>("(foo)1").err().unwrap().contains("warning"));
+}
+
+#[test]
+fn a_stray_period_is_a_clear_error() {
+    let explanation = "(a period can only be part of a float literal, ex. `1.5`, as there is no member access, ex. `a.b`)";
+
+    let err = parse_str::<Expression>("a.").err().unwrap();
+    assert_eq!(err, format!("Expected `end of input`, but found `.` at 1:2 instead {explanation}"));
+
+    let err = parse_str::<FunctionDefinition>("int f() { x = a.b.c; }").err().unwrap();
+    assert!(err.ends_with(&format!("after the Assignment Statement `x = a`, but found `.` at 1:16 instead {explanation}")), "{err}");
+
+    let err = parse_str::<FunctionDefinition>("int f(int a.b) {}").err().unwrap();
+    assert!(err.ends_with(explanation), "{err}");
+
+    // a period inside a float literal is never stray
+    assert!(parse_str::<Expression>("a * 1.5").is_ok());
+}
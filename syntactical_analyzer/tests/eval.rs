@@ -0,0 +1,74 @@
+//! Tests of the constant evaluation of expressions.
+
+use q2_lib::{
+    eval::{DivisionMode, EvalConfig},
+    non_terminals::Expression,
+    parse_str,
+    terminals::LiteralValue,
+};
+
+fn eval(source: &str, division: DivisionMode) -> Result<LiteralValue, String> {
+    parse_str::<Expression>(source).unwrap().eval(&EvalConfig { division })
+}
+
+#[test]
+fn division_follows_the_division_mode() {
+    assert_eq!(eval("5 / 2", DivisionMode::Integer), Ok(LiteralValue::Int(2)));
+    assert_eq!(eval("5 / 2", DivisionMode::Truncating), Ok(LiteralValue::Int(2)));
+    assert_eq!(eval("5 / 2", DivisionMode::Float), Ok(LiteralValue::Float(2.5)));
+    assert_eq!(eval("5 / 2", DivisionMode::ByOperands), Ok(LiteralValue::Int(2)));
+    assert_eq!(eval("5.0 / 2", DivisionMode::ByOperands), Ok(LiteralValue::Float(2.5)));
+
+    // integer division rounds down, where truncating rounds towards zero
+    assert_eq!(eval("(0 - 5) / 2", DivisionMode::Integer), Ok(LiteralValue::Int(-3)));
+    assert_eq!(eval("(0 - 5) / 2", DivisionMode::Truncating), Ok(LiteralValue::Int(-2)));
+    assert_eq!(eval("(0 - 5.0) / 2", DivisionMode::Integer), Ok(LiteralValue::Float(-3.0)));
+
+    assert_eq!(eval("5 / 0", DivisionMode::Integer), Err("Cannot divide an int by zero".into()));
+    assert_eq!(eval("5 / 0", DivisionMode::Float), Ok(LiteralValue::Float(f64::INFINITY)));
+}
+
+#[test]
+fn expressions_evaluate_to_a_constant() {
+    let config = EvalConfig::default();
+    let eval = |source: &str| parse_str::<Expression>(source).unwrap().eval(&config);
+    assert_eq!(eval("1 + 2 * 3"), Ok(LiteralValue::Int(7)));
+    assert_eq!(eval("(1 + 2) * 3 - 0.5"), Ok(LiteralValue::Float(8.5)));
+    assert_eq!(eval("x = 4 * 2"), Ok(LiteralValue::Int(8)));
+    assert_eq!(eval("7 % 4 * 2"), Ok(LiteralValue::Int(6)));
+    assert_eq!(eval("7.5 % 2"), Ok(LiteralValue::Float(1.5)));
+    assert_eq!(eval("5 % 0"), Err("Cannot divide an int by zero".into()));
+    assert_eq!(eval("1 - 1 ? 10 : 20"), Ok(LiteralValue::Int(20)));
+    assert_eq!(eval("9223372036854775807 + 1"), Err("The int `9223372036854775807 + 1` overflows".into()));
+    assert_eq!(eval("1 + x"), Err("Cannot evaluate the identifier `x`, only literals are constant".into()));
+}
+
+#[test]
+fn prefix_operators_apply_right_to_left() {
+    assert_eq!(eval("--5", DivisionMode::ByOperands), Ok(LiteralValue::Int(5)));
+    assert_eq!(eval("-+5", DivisionMode::ByOperands), Ok(LiteralValue::Int(-5)));
+    assert_eq!(eval("-+-2.5", DivisionMode::ByOperands), Ok(LiteralValue::Float(2.5)));
+
+    // a prefix operator binds tighter than any binary operator
+    assert_eq!(eval("-5 / 2", DivisionMode::Integer), Ok(LiteralValue::Int(-3)));
+    assert_eq!(eval("2 - -3 * 2", DivisionMode::ByOperands), Ok(LiteralValue::Int(8)));
+}
+
+#[test]
+fn a_prefix_plus_is_a_neutral_sign() {
+    assert_eq!(eval("+5", DivisionMode::ByOperands), Ok(LiteralValue::Int(5)));
+    assert_eq!(eval("-5", DivisionMode::ByOperands), Ok(LiteralValue::Int(-5)));
+    // the plus of a negation, which is still negative
+    assert_eq!(eval("+-5", DivisionMode::ByOperands), Ok(LiteralValue::Int(-5)));
+    assert_eq!(eval("+x", DivisionMode::ByOperands), eval("x", DivisionMode::ByOperands));
+}
+
+#[test]
+fn a_prefix_not_is_an_int_truth_value() {
+    assert_eq!(eval("!0", DivisionMode::ByOperands), Ok(LiteralValue::Int(1)));
+    assert_eq!(eval("!2.5", DivisionMode::ByOperands), Ok(LiteralValue::Int(0)));
+    assert_eq!(eval("!!7", DivisionMode::ByOperands), Ok(LiteralValue::Int(1)));
+    // the negation of a truth value, and the truth value of a negation
+    assert_eq!(eval("-!0", DivisionMode::ByOperands), Ok(LiteralValue::Int(-1)));
+    assert_eq!(eval("!-3", DivisionMode::ByOperands), Ok(LiteralValue::Int(0)));
+}
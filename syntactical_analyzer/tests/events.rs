@@ -0,0 +1,88 @@
+//! Tests of emitting a parse as a stream of events.
+
+use std::rc::Rc;
+
+use q1_lib::{
+    lex,
+    lexer::{Literal, Symbol, Token},
+};
+use q2_lib::{
+    events::{parse_events, Emit, ParseEvent::{self, EndNode, StartNode}},
+    non_terminals::{CompoundStatements, FunctionDefinition},
+    parse_str,
+    ParseBuffer,
+    ParseDisplay,
+};
+
+fn token(token: Token, lexeme: &str) -> ParseEvent {
+    ParseEvent::Token(token, Rc::new(lexeme.to_string()))
+}
+
+fn events_of(source: &str) -> Result<Vec<ParseEvent>, String> {
+    let mut events = vec![];
+    let mut buffer = ParseBuffer::from_tokens(lex(source)?);
+    parse_events::<CompoundStatements>(&mut buffer, |event| events.push(event))?;
+    Ok(events)
+}
+
+#[test]
+fn a_return_statement_is_a_stream_of_events() {
+    assert_eq!(
+        events_of("return 1;").unwrap(),
+        [
+            StartNode("CompoundStatements"),
+            StartNode("Statement"),
+            StartNode("ReturnStatement"),
+            token(Token::Return, "return"),
+            StartNode("Expression"),
+            StartNode("ArithmeticExpression"),
+            StartNode("Term"),
+            StartNode("Factor"),
+            token(Token::Literal(Literal::Int), "1"),
+            EndNode("Factor"),
+            EndNode("Term"),
+            EndNode("ArithmeticExpression"),
+            EndNode("Expression"),
+            EndNode("ReturnStatement"),
+            EndNode("Statement"),
+            token(Token::Symbol(Symbol::Semicolon), ";"),
+            EndNode("CompoundStatements"),
+        ]
+    );
+}
+
+#[test]
+fn every_token_is_emitted_in_order() {
+    let events = events_of("if (a) { x = (int)y; x = y * 2; } else { return a ? b : c; };").unwrap();
+    let lexemes = events.iter()
+        .filter_map(|event| match event {
+            ParseEvent::Token(_, lexeme) => Some(lexeme.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(lexemes, "if ( a ) { x = ( int ) y ; x = y * 2 ; } else { return a ? b : c ; } ;");
+
+    // every node that starts also ends
+    let starts = events.iter().filter(|event| matches!(event, StartNode(_))).count();
+    let ends = events.iter().filter(|event| matches!(event, EndNode(_))).count();
+    assert_eq!(starts, ends);
+
+    assert!(events_of("return 1").is_err());
+}
+
+#[test]
+fn events_emitted_while_parsing_match_the_parsed_tree() {
+    // every backtracked attempt (ex. the assignment tried at `x = ...`, or the
+    // grouping tried at `(int)`) is discarded along with its events
+    let source = "int f(const int a, float b) { x = y = a + b - c * d / e; z = (int)y; \
+        if (a ? b : c) { return -(a + 1), b; } else if (b) { a = 1; } else { b = 2; }; }";
+    let mut events = vec![];
+    let parsed = parse_events::<FunctionDefinition>(&mut ParseBuffer::from_tokens(lex(source).unwrap()), |event| events.push(event)).unwrap();
+
+    // the tree the events were parsed along with is handed back too
+    let mut walked = vec![];
+    parsed.emit(&mut |event| walked.push(event));
+    assert_eq!(events, walked);
+    assert_eq!(parsed.render(0, None), parse_str::<FunctionDefinition>(source).unwrap().render(0, None));
+}
@@ -0,0 +1,70 @@
+//! Golden-file tests of the displayed parse tree.
+//!
+//! Every `fixtures/*.src` file is parsed as a `FunctionDefinition`, and the
+//! rendered tree is compared against the checked-in `fixtures/*.tree` file of
+//! the same name.
+//!
+//! If there is also a `fixtures/*.compact.tree` file, the tree rendered with
+//! `DisplayConfig::compact_arithmetic` is compared against it as well.
+//!
+//! To (re)generate the golden files after an intended output change, run
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+
+use std::{env, fs, path::PathBuf};
+
+use q2_lib::{display::DisplayConfig, non_terminals::FunctionDefinition, parse_str, ParseDisplay};
+
+/// The directory holding all `.src` fixtures and their `.tree` golden files.
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+#[test]
+fn golden_trees() {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+
+    let mut sources = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "src"))
+        .collect::<Vec<_>>();
+    sources.sort();
+    assert!(!sources.is_empty(), "no fixtures found");
+
+    let mut mismatches = vec![];
+    for source_path in sources {
+        let source = fs::read_to_string(&source_path).unwrap();
+        let function_definition = match parse_str::<FunctionDefinition>(&source) {
+            Ok(function_definition) => function_definition,
+            Err(err) => panic!("{} failed to parse:\n{err}", source_path.display()),
+        };
+
+        // the compact golden file is optional, so only compare it if it exists
+        let mut goldens = vec![(source_path.with_extension("tree"), function_definition.render(0, None))];
+        let compact_path = source_path.with_extension("compact.tree");
+        if compact_path.exists() {
+            let config = DisplayConfig { compact_arithmetic: true, ..Default::default() };
+            goldens.push((compact_path, function_definition.render_with(0, None, config)));
+        }
+
+        for (tree_path, rendered) in goldens {
+            if update {
+                fs::write(&tree_path, &rendered).unwrap();
+                continue;
+            }
+
+            let expected = fs::read_to_string(&tree_path)
+                .unwrap_or_else(|_| panic!("missing golden file {}", tree_path.display()));
+            if rendered != expected {
+                mismatches.push(format!(
+                    "{}\n--- expected\n{expected}--- found\n{rendered}",
+                    tree_path.display()
+                ));
+            }
+        }
+    }
+
+    assert!(mismatches.is_empty(), "golden mismatch:\n{}", mismatches.join("\n"));
+}
@@ -0,0 +1,66 @@
+//! Tests of the assembled grammar.
+
+use q2_lib::{
+    grammar::{grammar, Bnf},
+    non_terminals::*,
+    parse_str,
+    source_map::SourceMap,
+    terminals::Identifier,
+    ParseDisplay,
+};
+
+#[test]
+fn grammar_starts_at_the_root() {
+    let grammar = grammar();
+    assert!(grammar.starts_with(FunctionDefinition::bnf()));
+    assert!(grammar.ends_with(&format!("{}\n", FactorExtend::bnf())));
+}
+
+#[test]
+fn every_production_is_defined_once() {
+    let grammar = grammar();
+    let defined: Vec<&str> = grammar
+        .lines()
+        .filter(|line| line.starts_with('<'))
+        .map(|line| line.split(" -> ").next().unwrap())
+        .collect();
+
+    // every non-terminal used on a right-hand side has a production
+    for line in grammar.lines() {
+        let rhs = line.split_once("-> ").map_or(line, |(_, rhs)| rhs);
+        for used in rhs.split('<').skip(1) {
+            let used = format!("<{}", &used[..=used.find('>').unwrap()]);
+            assert!(defined.contains(&used.as_str()), "`{used}` has no production");
+        }
+    }
+
+    let mut deduped = defined.clone();
+    deduped.sort();
+    deduped.dedup();
+    assert_eq!(deduped.len(), defined.len());
+}
+
+#[test]
+fn a_node_knows_its_own_production() {
+    let statement = parse_str::<ReturnStatement>("return x, 1").unwrap();
+    assert!(statement.production().unwrap().contains("return"));
+    assert_eq!(statement.production(), Some(ReturnStatement::bnf()));
+
+    let factor = parse_str::<Factor>("(a)").unwrap();
+    assert!(factor.production().unwrap().starts_with("<FACTOR> ->"));
+
+    // a terminal has no production of its own
+    assert_eq!(parse_str::<Identifier>("a").unwrap().production(), None);
+}
+
+#[test]
+fn a_node_found_in_the_source_knows_its_production() {
+    let source = "int f(int x) {\n    x = x * 2;\n    return x;\n}";
+    let function_definition = parse_str::<FunctionDefinition>(source).unwrap();
+    let source_map = SourceMap::new(source).unwrap();
+
+    let node = function_definition.node_at(&source_map, 2, 9).unwrap();
+    assert_eq!(node.production(), Some(Factor::bnf()));
+    let node = function_definition.node_at(&source_map, 3, 5).unwrap();
+    assert_eq!(node.production(), Some(ReturnStatement::bnf()));
+}
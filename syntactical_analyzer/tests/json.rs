@@ -0,0 +1,97 @@
+//! Tests of the lossless JSON of a parse tree, and reading it back.
+
+use q2_lib::{
+    events::{Emit, ParseEvent},
+    json::{from_json, to_json, MAX_JSON_DEPTH},
+    non_terminals::{CompoundStatements, FunctionDefinition},
+    parse_str,
+    ParseDisplay,
+};
+
+/// Every parse event of a node, in order.
+fn events_of(node: &impl Emit) -> Vec<ParseEvent> {
+    let mut events = vec![];
+    node.emit(&mut |event| events.push(event));
+    events
+}
+
+#[test]
+fn a_tree_round_trips_through_json() {
+    let source = "const int f(int x, float y) { x = -x + 1; if (x) { return (int)y, x; } else {}; y = (a ? b : c) * 2.5; }";
+    let original = parse_str::<FunctionDefinition>(source).unwrap();
+    let json = to_json(&original);
+    let read_back = from_json::<FunctionDefinition>(&json).unwrap();
+
+    assert!(read_back == original);
+    assert_eq!(events_of(&read_back), events_of(&original));
+    assert_eq!(read_back.to_owned_tree(), original.to_owned_tree());
+    assert_eq!(to_json(&read_back), json);
+
+    // a statement list too, including its `;` after every statement
+    let statements = parse_str::<CompoundStatements>("x = !y; return -!x, 2;").unwrap();
+    assert!(from_json::<CompoundStatements>(&to_json(&statements)).unwrap() == statements);
+}
+
+#[test]
+fn every_lexeme_must_lex_as_its_token() {
+    let json = to_json(&parse_str::<CompoundStatements>("x = 1;").unwrap());
+
+    let not_an_identifier = json.replace(r#""lexeme": "x""#, r#""lexeme": "1""#);
+    assert_eq!(
+        from_json::<CompoundStatements>(&not_an_identifier).err().unwrap(),
+        "The lexeme `1` is not a `Identifier` in the JSON tree"
+    );
+    let two_tokens = json.replace(r#""lexeme": "x""#, r#""lexeme": "x y""#);
+    assert!(from_json::<CompoundStatements>(&two_tokens).is_err());
+    let keyword = json.replace(r#""lexeme": "x""#, r#""lexeme": "return""#);
+    assert!(from_json::<CompoundStatements>(&keyword).is_err());
+}
+
+#[test]
+fn a_pathologically_nested_json_is_a_clean_error() {
+    let depth = 100_000;
+    let json = format!("{}{}", r#"{"node": "Expression", "children": ["#.repeat(depth), "]}".repeat(depth));
+    assert_eq!(
+        from_json::<CompoundStatements>(&json).err().unwrap(),
+        format!("JSON tree nested too deeply (the limit is {MAX_JSON_DEPTH} levels)")
+    );
+}
+
+#[test]
+fn the_json_keeps_every_terminal() {
+    let statements = parse_str::<CompoundStatements>("x = 1;").unwrap();
+    assert_eq!(
+        to_json(&statements),
+        concat!(
+            r#"{"node": "CompoundStatements", "children": [{"node": "Statement", "children": [{"node": "AssignmentStatement", "children": ["#,
+            r#"{"token": "Identifier", "lexeme": "x"}, {"token": "Symbol(Equal)", "lexeme": "="}, "#,
+            r#"{"node": "Expression", "children": [{"node": "ArithmeticExpression", "children": [{"node": "Term", "children": [{"node": "Factor", "children": ["#,
+            r#"{"token": "Literal(Int)", "lexeme": "1"}]}]}]}]}]}]}, {"token": "Symbol(Semicolon)", "lexeme": ";"}]}"#,
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn an_edited_json_is_read_back_only_if_it_still_parses_into_it() {
+    let statements = parse_str::<CompoundStatements>("x = 1; y = x;").unwrap();
+    let json = to_json(&statements);
+
+    // a lexeme can be changed
+    let renamed = json.replace(r#""lexeme": "y""#, r#""lexeme": "z""#);
+    let read_back = from_json::<CompoundStatements>(&renamed).unwrap();
+    assert_eq!(read_back.lexeme_signature(), "x = 1; z = x;");
+
+    // a list missing a delimiter is not a list
+    let missing_semicolon = json.replacen(r#", {"token": "Symbol(Semicolon)", "lexeme": ";"}"#, "", 1);
+    assert!(from_json::<CompoundStatements>(&missing_semicolon).is_err());
+
+    // the nodes of the JSON must be the nodes its terminals parse into
+    let renamed_node = json.replacen(r#""node": "AssignmentStatement""#, r#""node": "ReturnStatement""#, 1);
+    assert_eq!(
+        from_json::<CompoundStatements>(&renamed_node).err().unwrap(),
+        "The JSON tree does not match how its terminals parse: expected the start of `ReturnStatement`, but they parse into the start of `AssignmentStatement` instead"
+    );
+
+    assert!(from_json::<CompoundStatements>(r#"{"node": "CompoundStatements", "children": [{"token": "Nope", "lexeme": "x"}]}"#).is_err());
+}
@@ -0,0 +1,64 @@
+//! Tests of parsing the tokens of the lexer (`Q1`), whose own tests are in `Q1`.
+
+use std::collections::HashMap;
+
+use q1_lib::{
+    lex_with_keywords, lex_with_trivia,
+    lexer::{Token, Type},
+};
+use q2_lib::{non_terminals::FunctionDefinition, parse_all, parse_str, terminals::Identifier, Parse, ParseBuffer, ParseDisplay};
+
+#[test]
+fn a_leading_shebang_line_is_skipped() {
+    let function_definition = parse_str::<FunctionDefinition>("#!/usr/bin/analyze\nint f(){}").unwrap();
+    assert_eq!(function_definition.lexeme_signature(), "int f () {....}");
+}
+
+#[test]
+fn trivia_reconstructs_the_exact_source() {
+    let source = "#!/usr/bin/analyze
+int  f(int `x`,float y)
+{
+	x=(x +  y)*2;
+	return x ;}
+";
+    let (tokens, trailing) = lex_with_trivia(source).unwrap();
+    let lexemes = tokens.iter().map(|(token, lexeme, _)| (*token, lexeme.clone())).collect::<Vec<_>>();
+    let function_definition = parse_all::<FunctionDefinition>(&mut ParseBuffer::from_tokens(lexemes)).unwrap();
+    assert_eq!(function_definition.to_source_exact(&tokens) + &trailing, source);
+    assert_ne!(function_definition.full_signature(), source);
+
+    // a subtree is its own tokens, along with the trivia before its first one
+    let body = function_definition.compound_statements.to_source_exact(&tokens);
+    assert_eq!(body, "\n\tx=(x +  y)*2;\n\treturn x ;");
+}
+
+#[test]
+fn a_custom_keyword_table_decides_the_reserved_words() {
+    let keywords = HashMap::from([
+        ("integer".to_string(), Token::Type(Type::Int)),
+        ("ret".to_string(), Token::Return),
+        ("if".to_string(), Token::If),
+        ("else".to_string(), Token::Else),
+    ]);
+    let source = "integer f(integer return) { int = return; ret int; }";
+    let tokens = lex_with_keywords(source, &keywords).unwrap();
+
+    // `return` and `int` are not keywords of the table, so they are identifiers like any other word
+    let function_definition = parse_all::<FunctionDefinition>(&mut ParseBuffer::from_tokens(tokens.clone()).with_keywords(&keywords)).unwrap();
+    assert_eq!(function_definition.lexeme_signature(), "integer f (integer return) {....}");
+    assert_eq!(function_definition.compound_statements.lexeme_signature(), "int = return; ret int;");
+
+    // but they are reserved without the table
+    assert!(parse_all::<FunctionDefinition>(&mut ParseBuffer::from_tokens(tokens)).is_err());
+    let identifier = || ParseBuffer::from_tokens(vec![(Token::Identifier, "return".into())]);
+    assert_eq!(
+        Identifier::parse(&mut identifier()).err().as_deref(),
+        Some("The reserved word `return` cannot be used as an identifier at token 0")
+    );
+    assert!(Identifier::parse(&mut identifier().with_keywords(&keywords)).is_ok());
+
+    // a keyword of the table is reserved in its place
+    let buffer = ParseBuffer::from_tokens(vec![]).with_keywords(&keywords);
+    assert!(buffer.is_reserved_word("ret") && !buffer.is_reserved_word("return"));
+}
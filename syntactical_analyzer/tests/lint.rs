@@ -0,0 +1,95 @@
+//! Tests of the post-parse lints.
+
+use q2_lib::{
+    lint::{lint, lint_with, LintConfig},
+    non_terminals::{FunctionDefinition, FunctionSignature},
+    parse_str,
+};
+
+fn lint_source(source: &str) -> Vec<String> {
+    lint(&parse_str::<FunctionDefinition>(source).unwrap())
+}
+
+#[test]
+fn clean_function_has_no_warnings() {
+    assert!(lint_source("int f(int a, int b) { return a + b; }").is_empty());
+}
+
+#[test]
+fn empty_body_is_flagged() {
+    assert_eq!(lint_source("int f(int a) {}"), ["Function `f` has an empty body"]);
+}
+
+#[test]
+fn duplicate_parameters_are_an_error() {
+    let err = parse_str::<FunctionDefinition>("int f(int x, float x) { return x; }").err().unwrap();
+    assert_eq!(err, "Parameter `x` is declared more than once in function `f`, at 1:11 and 1:20");
+    assert!(parse_str::<FunctionSignature>("int f(int a, float b, int a) {}").is_err());
+}
+
+#[test]
+fn many_parameters_are_checked_in_linear_time() {
+    // every parameter was once compared against every parameter before it
+    let count = 20_000;
+    let parameters = (0..count).map(|i| format!("int p{i}")).collect::<Vec<_>>().join(", ");
+
+    let start = std::time::Instant::now();
+    assert!(parse_str::<FunctionSignature>(&format!("int f({parameters}) {{}}")).is_ok());
+    let elapsed = start.elapsed();
+    assert!(elapsed < std::time::Duration::from_secs(2), "{count} parameters took {elapsed:?}");
+
+    // the duplicate is still found at the very end
+    let err = parse_str::<FunctionSignature>(&format!("int f({parameters}, float p0) {{}}")).err().unwrap();
+    assert!(err.starts_with("Parameter `p0` is declared more than once in function `f`, at 1:11 and "), "{err}");
+}
+
+#[test]
+fn functions_with_many_parameters_are_flagged() {
+    let parameters = |n: usize| (0..n).map(|i| format!("int p{i}")).collect::<Vec<_>>().join(", ");
+    assert!(lint_source(&format!("int f({}) {{ return 0; }}", parameters(8))).is_empty());
+    assert_eq!(
+        lint_source(&format!("int f({}) {{ return 0; }}", parameters(10))),
+        ["Function `f` has 10 parameters, more than the limit of 8"],
+    );
+
+    let function_definition = parse_str::<FunctionDefinition>("int g(int a, int b) { return a; }").unwrap();
+    assert_eq!(
+        lint_with(&function_definition, LintConfig { max_parameters: 1, ..Default::default() }),
+        ["Function `g` has 2 parameters, more than the limit of 1"],
+    );
+}
+
+#[test]
+fn statements_after_a_return_are_flagged() {
+    assert_eq!(
+        lint_source("int f(int a) { return a; a = 1; return 2; }"),
+        [
+            "Statement `a = 1` is unreachable after a return",
+            "Statement `return 2` is unreachable after a return",
+        ],
+    );
+
+    // each block is checked on its own
+    assert_eq!(
+        lint_source("int f(int a) { if (a) { return 1; a = 2; }; return a; }"),
+        ["Statement `a = 2` is unreachable after a return"],
+    );
+}
+
+#[test]
+fn long_functions_are_flagged() {
+    let body = (0..50).map(|i| format!("x = {i}; ")).collect::<String>();
+    assert!(lint_source(&format!("int f(int x) {{ {body}}}")).is_empty());
+    assert_eq!(
+        lint_source(&format!("int f(int x) {{ {body}return x; }}")),
+        ["Function `f` has 51 statements, more than the limit of 50"],
+    );
+
+    // statements nested in blocks count as well, up to a configurable limit
+    let function_definition = parse_str::<FunctionDefinition>("int g(int x) { if (x) { x = 1; x = 2; }; return x; }").unwrap();
+    assert!(lint(&function_definition).is_empty());
+    assert_eq!(
+        lint_with(&function_definition, LintConfig { max_statements: 3, ..Default::default() }),
+        ["Function `g` has 4 statements, more than the limit of 3"],
+    );
+}
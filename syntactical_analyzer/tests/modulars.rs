@@ -0,0 +1,130 @@
+//! Tests of the list-like modular parse types.
+
+mod common;
+
+use q1_lib::lex_with_newlines;
+use q2_lib::{
+    either::Either,
+    modulars::{Delimited, LineDelimited, Repeated},
+    non_terminals::{FunctionParameter, FunctionParameters},
+    terminals::{Comma, Identifier, RightParen},
+    Parse, ParseBuffer, ParseDisplay,
+};
+
+use common::buffer_of;
+
+#[test]
+fn recovering_lists_skip_a_bad_item() {
+    let mut buffer = buffer_of("a, 1 + 2, c)");
+    let (list, errors) = Delimited::<Identifier, Comma>::parse_recovering::<RightParen>(&mut buffer);
+    assert_eq!(list.lexeme_signature(), "a, c");
+    assert_eq!(errors, ["Expected `{identifier}`, but found `1` at token 2 instead"]);
+    // the follow of the list is never skipped
+    assert_eq!(buffer.peek().map(|(_, lexeme)| lexeme.as_str()), Some(")"));
+
+    let mut buffer = buffer_of("int a, float 1, int c) {}");
+    let (parameters, errors) = FunctionParameters::parse_recovering::<RightParen>(&mut buffer);
+    assert_eq!(parameters.items().len(), 2);
+    assert_eq!(parameters.lexeme_signature(), "int a, int c");
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn recovering_lists_end_at_their_follow() {
+    let recover = |source: &str| {
+        let (list, errors) = Delimited::<Identifier, Comma>::parse_recovering::<RightParen>(&mut buffer_of(source));
+        (list.lexeme_signature(), errors.len())
+    };
+
+    assert_eq!(recover(")"), ("".into(), 0));
+    assert_eq!(recover("a, b)"), ("a, b".into(), 0));
+    // a bad last item drops the delimiter before it
+    assert_eq!(recover("a, b, )"), ("a, b".into(), 1));
+    assert_eq!(recover("a, 1 2 3"), ("a".into(), 1));
+    // what follows an item (before its delimiter) is skipped, keeping the item
+    assert_eq!(recover("a 1, b)"), ("a, b".into(), 1));
+    // the list only ends at its follow
+    assert_eq!(recover("1 ) a"), ("".into(), 1));
+    // a delimiter or the follow within brackets is skipped along with them
+    assert_eq!(recover("a, f(b, c), d)"), ("a, f, d".into(), 1));
+    assert_eq!(recover("a, (b, (c)) {e, f}, d)"), ("a, d".into(), 1));
+}
+
+#[test]
+fn line_delimited_lists_split_on_commas_or_newlines() {
+    let mut buffer = ParseBuffer::from_tokens(lex_with_newlines("int a\nint b, float c\nint d)").unwrap());
+    let parameters = LineDelimited::<FunctionParameter>::parse(&mut buffer).unwrap();
+    let delimiters = parameters.items().iter()
+        .map(|(_, d)| match d {
+            Some(Either::Left(_)) => ",",
+            Some(Either::Right(_)) => "\\n",
+            None => "",
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(delimiters, ["\\n", ",", "\\n", ""]);
+    assert_eq!(buffer.peek().map(|(_, lexeme)| lexeme.as_str()), Some(")"));
+
+    // without newline tokens, a line break only separates the tokens
+    let mut buffer = buffer_of("int a\nint b");
+    let parameters = LineDelimited::<FunctionParameter>::parse(&mut buffer).unwrap();
+    assert_eq!(parameters.items().len(), 1);
+}
+
+#[test]
+fn hand_built_lists_never_panic_on_display() {
+    let identifier = |source: &str| Identifier::parse(&mut buffer_of(source)).unwrap();
+    let comma = || Comma::parse(&mut buffer_of(",")).unwrap();
+
+    // a missing delimiter in the middle, and a delimiter after the last item
+    let list = Delimited::<Identifier, Comma>::from(vec![
+        (identifier("a"), None),
+        (identifier("b"), Some(comma())),
+        (identifier("c"), Some(comma())),
+    ]);
+    assert_eq!(list.lexeme_signature(), "a b, c,");
+    assert!(list.render(0, None).contains("a b, c,"));
+}
+
+#[test]
+fn hand_built_lists_can_be_checked() {
+    let identifier = |source: &str| Identifier::parse(&mut buffer_of(source)).unwrap();
+    let comma = || Comma::parse(&mut buffer_of(",")).unwrap();
+    type List = Delimited<Identifier, Comma>;
+
+    let well_formed = || vec![(identifier("a"), Some(comma())), (identifier("b"), None)];
+    assert!(List::from(well_formed()).is_well_formed());
+    assert!(List::from(vec![]).is_well_formed());
+    assert!(List::parse(&mut buffer_of("a, b, c")).unwrap().is_well_formed());
+    assert_eq!(List::try_from_items(well_formed()).unwrap().lexeme_signature(), "a, b");
+
+    // a missing delimiter in the middle
+    let malformed = || vec![(identifier("a"), None), (identifier("b"), None)];
+    assert!(!List::from(malformed()).is_well_formed());
+    assert_eq!(
+        List::try_from_items(malformed()).err().unwrap(),
+        "Item 1 of a `Delimited Sequence of `{identifier}` by `,`` has no delimiter after it, but only the last item can be without one"
+    );
+
+    // a delimiter after the last item
+    let trailing = vec![(identifier("a"), Some(comma()))];
+    assert!(!List::from(trailing.clone()).is_well_formed());
+    assert!(List::try_from_items(trailing).err().unwrap().starts_with("The last item (item 1) of a `Delimited"));
+}
+
+#[test]
+fn repeated_lists_expect_exactly_n_items() {
+    let mut buffer = buffer_of("a b c d");
+    let list = Repeated::<Identifier, 3>::parse(&mut buffer).unwrap();
+    assert_eq!(list.items().len(), 3);
+    assert_eq!(list.lexeme_signature(), "a b c");
+    assert_eq!(list.render(0, None), "Repeated Sequence of 3 `{identifier}`: a b c\n    Identifier: a\n    Identifier: b\n    Identifier: c\n");
+    // nothing past the third item is parsed
+    assert_eq!(buffer.peek().map(|(_, lexeme)| lexeme.as_str()), Some("d"));
+
+    let mut buffer = buffer_of("a b;");
+    let err = Repeated::<Identifier, 3>::parse(&mut buffer).err().unwrap();
+    assert_eq!(err, "While parsing Repeated Sequence of 3 `{identifier}`...\n    Expected `{identifier}`, but found `;` at token 2 instead");
+    // a failed parse consumes nothing
+    assert_eq!(buffer.peek().map(|(_, lexeme)| lexeme.as_str()), Some("a"));
+    assert!(Repeated::<Identifier, 3>::parse(&mut buffer_of("a b")).is_err());
+}
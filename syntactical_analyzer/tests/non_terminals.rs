@@ -0,0 +1,404 @@
+//! Structural tests of the non-terminal parse types.
+
+use q1_lib::lexer::{Symbol, Token};
+use q2_lib::{grammar::Bnf, non_terminals::*, parse_str, source_map::SourceMap, Parse, ParseBuffer, ParseDisplay};
+
+#[test]
+fn if_else_if_else_chains_flat() {
+    let statement = parse_str::<IfStatement>(
+        "if (a) { x = 1; } else if (b) { x = 2; } else { x = 3; }",
+    )
+    .unwrap();
+
+    let Some(ElseTail::If(_, ref else_if)) = statement.else_tail else {
+        panic!("expected `else if` as the first tail");
+    };
+    assert_eq!(else_if.condition.lexeme_signature(), "b");
+    assert!(matches!(else_if.else_tail, Some(ElseTail::Block(..))));
+
+    // every branch is displayed as a sibling, at the same depth
+    let rendered = statement.render(0, None);
+    let branches = rendered
+        .lines()
+        .filter(|line| line.contains("Branch:"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        branches,
+        [
+            "    If Branch: if (a) {....}",
+            "    Else If Branch: else if (b) {....}",
+            "    Else Branch: else {....}",
+        ]
+    );
+}
+
+#[test]
+fn conditional_expressions_are_right_associative() {
+    let expression = parse_str::<Expression>("a ? b : c ? d : e").unwrap();
+
+    let Expression::Conditional(outer) = expression else {
+        panic!("expected a conditional expression");
+    };
+    assert_eq!(outer.condition.lexeme_signature(), "a");
+    assert_eq!(outer.then_expression.lexeme_signature(), "b");
+
+    let Expression::Conditional(ref inner) = *outer.else_expression else {
+        panic!("expected the else expression to be the nested conditional");
+    };
+    assert_eq!(inner.condition.lexeme_signature(), "c");
+    assert_eq!(inner.then_expression.lexeme_signature(), "d");
+    assert_eq!(inner.else_expression.lexeme_signature(), "e");
+}
+
+#[test]
+fn full_signature_does_not_elide_bodies() {
+    let function = parse_str::<FunctionDefinition>(
+        "int f(int a, float b) { x = a * 2; if (x) { return x; } else { return b; }; }",
+    )
+    .unwrap();
+
+    assert_eq!(function.lexeme_signature(), "int f (int a, float b) {....}");
+    assert_eq!(
+        function.full_signature(),
+        "int f (int a, float b) {x = a * 2; if (x) {return x;} else {return b;};}",
+    );
+}
+
+#[test]
+fn assignment_is_right_associative() {
+    let expression = parse_str::<Expression>("x = y = 0").unwrap();
+
+    let Expression::Assignment(ref outer) = expression else {
+        panic!("expected an assignment expression");
+    };
+    assert_eq!(outer.target.lexeme_signature(), "x");
+
+    let Expression::Assignment(ref inner) = *outer.value else {
+        panic!("expected the value to be an assignment expression");
+    };
+    assert_eq!(inner.target.lexeme_signature(), "y");
+    assert_eq!(inner.value.lexeme_signature(), "0");
+
+    // as a statement, the chain is the assigned expression
+    let statement = parse_str::<AssignmentStatement>("x = y = 0").unwrap();
+    assert!(matches!(statement.expression, Expression::Assignment(_)));
+}
+
+#[test]
+fn a_return_statement_returns_one_or_more_expressions() {
+    let single = parse_str::<CompoundStatements>("return 1;").unwrap();
+    let Statement::Return(ref single) = single.items()[0].0 else {
+        panic!("expected a return statement");
+    };
+    assert_eq!(single.expressions.items().len(), 1);
+    // a single expression is not numbered
+    let rendered = single.render(0, None);
+    assert!(rendered.contains("\n    Expression:\n") && !rendered.contains("Expression 1"));
+
+    let multiple = parse_str::<CompoundStatements>("return a, b, c;").unwrap();
+    let Statement::Return(ref multiple) = multiple.items()[0].0 else {
+        panic!("expected a return statement");
+    };
+    let returned = multiple.expressions.items().iter().map(|(e, _)| e.lexeme_signature()).collect::<Vec<_>>();
+    assert_eq!(returned, ["a", "b", "c"]);
+    assert_eq!(multiple.lexeme_signature(), "return a, b, c");
+    let rendered = multiple.render(0, None);
+    assert!(rendered.contains("\n    Expression 1:\n") && rendered.contains("\n    Expression 3:\n"));
+
+    // something must still be returned, and a trailing comma is not an expression
+    assert!(parse_str::<CompoundStatements>("return;").is_err());
+    assert!(parse_str::<CompoundStatements>("return a,;").is_err());
+    let err = parse_str::<ReturnStatement>("return;").err().unwrap();
+    assert!(err.starts_with("Expected `Expression` after `return`, but found `;`"), "{err}");
+}
+
+#[test]
+fn prefix_operators_stack_in_one_unary_expression() {
+    let unary_of = |source: &str| match parse_str::<Factor>(source).unwrap() {
+        Factor::Unary(unary_expression) => unary_expression,
+        _ => panic!("`{source}` is not a unary expression"),
+    };
+
+    let negated_twice = unary_of("--x");
+    assert!(matches!(negated_twice.ops[..], [UnaryOp::Negative(_), UnaryOp::Negative(_)]));
+    assert!(matches!(*negated_twice.factor, Factor::Identifier(_)));
+
+    // the operators are kept in source order, the outermost first
+    let mixed = unary_of("-+x");
+    assert!(matches!(mixed.ops[..], [UnaryOp::Negative(_), UnaryOp::Positive(_)]));
+    assert_eq!(mixed.lexeme_signature(), "-+x");
+    assert!(matches!(unary_of("+-5").ops[..], [UnaryOp::Positive(_), UnaryOp::Negative(_)]));
+    assert!(mixed.render(0, None).starts_with(concat!(
+        "Unary Expression: -+x\n",
+        "    Operator: -\n",
+        "    Operator: +\n",
+        "    Factor: x\n",
+    )));
+
+    // the factor after the operators can be any factor, and binds tighter than `*`
+    assert!(matches!(*unary_of("-(a + b)").factor, Factor::Parenthesized(_)));
+    let term = parse_str::<Term>("-a * b").unwrap();
+    assert!(matches!(term.factor, Factor::Unary(_)));
+    assert_eq!(term.extend.unwrap().factor.lexeme_signature(), "b");
+
+    // `!` stacks with itself and with a sign
+    let not_twice = unary_of("!!b");
+    assert!(matches!(not_twice.ops[..], [UnaryOp::Not(_), UnaryOp::Not(_)]));
+    assert_eq!(not_twice.lexeme_signature(), "!!b");
+    let negated_not = unary_of("-!x");
+    assert!(matches!(negated_not.ops[..], [UnaryOp::Negative(_), UnaryOp::Not(_)]));
+    assert!(matches!(*negated_not.factor, Factor::Identifier(_)));
+    assert_eq!(parse_str::<Expression>("a * -!x").unwrap().lexeme_signature(), "a * -!x");
+
+    // a binary `-` is still binary, even before a prefix `-`
+    let expression = parse_str::<ArithmeticExpression>("a - -b").unwrap();
+    assert_eq!(expression.lhs_term.factor.lexeme_signature(), "a");
+    assert!(matches!(expression.extend.unwrap().term.factor, Factor::Unary(_)));
+}
+
+#[test]
+fn modulo_is_a_factor_operator() {
+    let term = parse_str::<Term>("a % b * c").unwrap();
+    let extend = term.extend.as_deref().unwrap();
+    assert!(matches!(extend.op, FactorOp::Modulo(_)));
+    assert!(matches!(extend.extend.as_deref().unwrap().op, FactorOp::Multiply(_)));
+    assert_eq!(term.lexeme_signature(), "a % b * c");
+
+    // `%` binds as tightly as `*`, tighter than `+`
+    let expression = parse_str::<ArithmeticExpression>("a + b % 2").unwrap();
+    assert_eq!(expression.extend.unwrap().term.lexeme_signature(), "b % 2");
+
+    assert_eq!(
+        parse_str::<Term>("x % 2").unwrap().render(0, None),
+        concat!(
+            "Term: x % 2\n",
+            "    Factor: x\n",
+            "        Variable: x\n",
+            "    Operator: %\n",
+            "    Factor: 2\n",
+            "        Literal: 2\n",
+        )
+    );
+}
+
+#[test]
+fn simplify_removes_only_redundant_parentheses() {
+    let simplified = |source: &str| parse_str::<Expression>(source).unwrap().simplify().lexeme_signature();
+
+    assert_eq!(simplified("((a+b))*c"), "(a + b) * c");
+    assert_eq!(simplified("a*(b+c)"), "a * (b + c)");
+    assert_eq!(simplified("((a))"), "a");
+    assert_eq!(simplified("(a) + b"), "a + b");
+    assert_eq!(simplified("(a*b) + c"), "a * b + c");
+    assert_eq!(simplified("a - (b+c)"), "a - (b + c)");
+    assert_eq!(simplified("x = (y ? (1) : 2)"), "x = y ? 1 : 2");
+    assert_eq!(simplified("-(a+b)"), "-(a + b)");
+    assert_eq!(simplified("-(-(a))"), "--a");
+}
+
+#[test]
+fn max_depth_counts_operators_and_parentheses() {
+    let depth = |source: &str| parse_str::<Expression>(source).unwrap().max_depth();
+
+    assert_eq!(depth("a"), 0);
+    assert_eq!(depth("a+b"), 1);
+    assert_eq!(depth("a+b+c"), 2);
+    assert_eq!(depth("a*b+c"), 2);
+    assert_eq!(depth("((a+b)*c)+d"), 5);
+    assert_eq!(depth("x = y ? (a) : b*c"), 1);
+    assert_eq!(depth("(int)y"), 0);
+
+    assert_eq!(parse_str::<Term>("(a)*b").unwrap().max_depth(), 2);
+    assert_eq!(parse_str::<Factor>("((a))").unwrap().max_depth(), 2);
+}
+
+#[test]
+fn first_and_last_tokens_are_the_outermost_terminals() {
+    let statement = parse_str::<AssignmentStatement>("x = a + b").unwrap();
+    assert_eq!(statement.first_token(), Some((Token::Identifier, "x")));
+    assert_eq!(statement.last_token(), Some((Token::Identifier, "b")));
+
+    let expression = parse_str::<Expression>("(a) * 2 - c").unwrap();
+    assert_eq!(expression.first_token(), Some((Token::Symbol(Symbol::LeftParen), "(")));
+    assert_eq!(expression.last_token(), Some((Token::Identifier, "c")));
+
+    // an empty list has no tokens at all
+    let statements = parse_str::<Block>("{}").unwrap().compound_statements;
+    assert_eq!(statements.first_token(), None);
+    assert_eq!(statements.last_token(), None);
+    // but it is still where it was parsed, after the `{`
+    assert_eq!(statements.span(), Some((1, 1)));
+}
+
+#[test]
+fn highlight_finds_the_smallest_subtree_under_a_token() {
+    // tokens, counted from 1: x(1) =(2) a(3) +(4) b(5)
+    let statement = parse_str::<AssignmentStatement>("x = a + b").unwrap();
+    assert_eq!(statement.span(), Some((0, 5)));
+
+    let a = statement.highlight(3).unwrap();
+    assert_eq!(a.render(0, None), "Factor: a\n    Variable: a\n");
+    assert_eq!(a.span(), Some((2, 3)));
+
+    // an operator's smallest subtree is its extension
+    assert_eq!(statement.highlight(4).unwrap().lexeme_signature(), "+ b");
+    // a terminal directly under the statement resolves to the statement
+    assert_eq!(statement.highlight(2).unwrap().lexeme_signature(), "x = a + b");
+    assert!(statement.highlight(6).is_none());
+    assert!(statement.highlight(0).is_none());
+}
+
+#[test]
+fn node_at_finds_the_smallest_subtree_at_a_line_and_column() {
+    let source = "int f(int x) {\n    x = x * 2;\n    return x;\n}\n";
+    let function_definition = parse_str::<FunctionDefinition>(source).unwrap();
+    let source_map = SourceMap::new(source).unwrap();
+    let node_at = |line, column| {
+        function_definition.node_at(&source_map, line, column).map(|node| node.render(0, None))
+    };
+
+    // the `x` of `x * 2`, on line 2
+    assert_eq!(node_at(2, 9).unwrap(), "Factor: x\n    Variable: x\n");
+    // anywhere in `return`
+    assert!(node_at(3, 5).unwrap().starts_with("Return Statement: return x\n"));
+    assert_eq!(node_at(3, 10), node_at(3, 5));
+    assert!(node_at(1, 1).unwrap().starts_with("Function Definition: "));
+
+    // between tokens, or past the source, there is nothing
+    assert_eq!(node_at(2, 1), None);
+    assert_eq!(node_at(2, 6), None);
+    assert_eq!(node_at(2, 100), None);
+    assert_eq!(node_at(9, 1), None);
+}
+
+#[test]
+fn tabs_advance_columns_to_the_next_tab_stop() {
+    let source = "int f(int x) {\n\tx = x * 2;\n  \treturn x;\n}";
+    let function_definition = parse_str::<FunctionDefinition>(source).unwrap();
+    let source_map = SourceMap::new(source).unwrap().with_tab_width(4);
+    let x = source.find("x * 2").unwrap();
+
+    // the `x` of `x * 2` is 1 tab and 4 characters in, at column 5 + 4
+    assert_eq!(source_map.line_column(x), Some((2, 9)));
+    assert_eq!(source_map.offset(2, 9), Some(x));
+    let node = function_definition.node_at(&source_map, 2, 9).unwrap();
+    assert_eq!(node.render(0, None), "Factor: x\n    Variable: x\n");
+
+    // a tab after 2 spaces only advances to the same tab stop, and spans its columns
+    assert_eq!(source_map.line_column(source.find("return").unwrap()), Some((3, 5)));
+    assert_eq!(source_map.offset(3, 4), source_map.offset(3, 3));
+
+    // by default, a tab is one column
+    let source_map = SourceMap::new(source).unwrap();
+    assert_eq!(source_map.line_column(x), Some((2, 6)));
+    assert_eq!(source_map.line_column(source.len()), Some((4, 2)));
+    assert_eq!(source_map.line_column(source.len() + 1), None);
+}
+
+#[test]
+fn programs_merge_their_function_definitions() {
+    let first = parse_str::<Program>("int f() { return 1; }").unwrap();
+    let second = parse_str::<Program>("float g(int x) { return x; }").unwrap();
+
+    let merged = first.clone().try_merge(second).unwrap();
+    assert_eq!(merged.function_names(), ["f", "g"]);
+    assert_eq!(merged.lexeme_signature(), "int f () {....} float g (int x) {....}");
+
+    // `merge` never checks for clashes, unlike `try_merge`
+    let clash = parse_str::<Program>("int g() {} int f(float y) {}").unwrap();
+    assert_eq!(first.clone().merge(clash.clone()).function_names(), ["f", "g", "f"]);
+    let err = first.try_merge(clash).err().unwrap();
+    assert_eq!(err, "The function `f` is defined in both programs");
+}
+
+#[test]
+fn programs_iterate_every_statement_in_order() {
+    let program = parse_str::<Program>(
+        "int f(int x) { x = 1; if (x) { x = 2; }; return x; } float g() { return 2.5; }",
+    ).unwrap();
+    let statements = program.statements().map(|statement| statement.lexeme_signature()).collect::<Vec<_>>();
+    assert_eq!(statements.len(), 4);
+    assert_eq!(statements[0], "x = 1");
+    assert_eq!(statements[3], "return 2.5");
+
+    assert_eq!(parse_str::<Program>("int f() {} int g() {}").unwrap().statements().count(), 0);
+}
+
+#[test]
+fn types_can_be_const_qualified() {
+    let parameter = parse_str::<FunctionParameter>("const int x").unwrap();
+    assert!(matches!(parameter.qualifiers[..], [Qualifier::Const(_)]));
+    assert_eq!(parameter.lexeme_signature(), "const int x");
+    assert_eq!(
+        parameter.render(0, None),
+        concat!(
+            "Function Parameter: const int x\n",
+            "    Parameter Qualifiers: const\n",
+            "        Qualifier: const\n",
+            "    Parameter Type: int\n",
+            "    Parameter Identifier: x\n",
+        )
+    );
+
+    let function_definition = parse_str::<FunctionDefinition>("const float f(int x, const const int y) {}").unwrap();
+    assert_eq!(function_definition.qualifiers.len(), 1);
+    assert_eq!(function_definition.parameters.items()[1].0.qualifiers.len(), 2);
+    assert_eq!(function_definition.lexeme_signature(), "const float f (int x, const const int y) {....}");
+
+    // a qualifier only ever precedes a type
+    assert!(parse_str::<FunctionParameter>("int const x").is_err());
+    assert!(parse_str::<FunctionParameter>("const x").is_err());
+}
+
+#[test]
+fn signatures_skip_the_function_body() {
+    // none of these bodies would parse, but their curlies are balanced
+    let source = "int f(int x) { x = = ; } float g() { if { { } } return } const int h(float y, int z) {}";
+    let mut buffer = ParseBuffer::from_tokens(q1_lib::lex(source).unwrap());
+    let mut signatures = vec![];
+    while buffer.peek().is_some() {
+        let signature = FunctionSignature::parse(&mut buffer).unwrap();
+        signatures.push(signature.lexeme_signature());
+    }
+    assert_eq!(signatures, ["int f (int x)", "float g ()", "const int h (float y, int z)"]);
+    assert!(parse_str::<Program>(source).is_err());
+
+    let signature = parse_str::<FunctionSignature>("int f(int x) { return x; }").unwrap();
+    assert_eq!(
+        signature.render(0, None),
+        concat!(
+            "Function Signature: int f (int x)\n",
+            "    Funtion Return Type: int\n",
+            "    Function Identifier: f\n",
+            "    Left Paren: (\n",
+            "    Function Parameters: int x\n",
+            "        Function Parameter: int x\n",
+            "            Parameter Type: int\n",
+            "            Parameter Identifier: x\n",
+            "    Right Paren: )\n",
+            "    Left Curly: {\n",
+            "    Right Curly: }\n",
+        )
+    );
+    // the displayed nodes are its children, parens and curlies included
+    assert_eq!(signature.children().len(), 7);
+    assert_eq!(signature.production(), Some(FunctionSignature::bnf()));
+    assert_eq!(signature.span(), Some((0, 11)));
+
+    let err = parse_str::<FunctionSignature>("int f() { { }").err().unwrap();
+    assert_eq!(err, "Unclosed function body `{` opened at 1:9, expected `}` before the end of input");
+}
+
+#[test]
+fn long_operator_chains_do_not_overflow_the_stack() {
+    let operands = 100_000;
+    for operator in [" + ", " * "] {
+        let source = vec!["a"; operands].join(operator);
+        let expression = parse_str::<ArithmeticExpression>(&source).unwrap();
+
+        let copy = expression.clone();
+        drop(expression);
+        assert_eq!(copy.lexeme_signature(), source);
+        assert_eq!(copy.span(), Some((0, 2 * operands - 1)));
+    }
+}
@@ -0,0 +1,43 @@
+//! Tests of the operator token classification.
+
+use q1_lib::lexer::{Symbol, Token};
+use q2_lib::{
+    non_terminals::{FactorOp, TermOp},
+    operators::{is_binary_operator, is_unary_operator, precedence_of},
+    parse_str,
+};
+
+#[test]
+fn plus_binds_looser_than_multiply() {
+    let plus = Token::Symbol(Symbol::Plus);
+    let multiply = Token::Symbol(Symbol::Multiply);
+    assert!(is_binary_operator(&plus));
+    assert!(is_binary_operator(&multiply));
+    assert!(precedence_of(&plus) < precedence_of(&multiply));
+    assert_eq!(precedence_of(&Token::Symbol(Symbol::Minus)), precedence_of(&plus));
+    assert!(precedence_of(&Token::Symbol(Symbol::Equal)) < precedence_of(&Token::Symbol(Symbol::Question)));
+}
+
+#[test]
+fn only_operators_are_classified() {
+    assert!(is_unary_operator(&Token::Symbol(Symbol::Minus)));
+    assert!(is_unary_operator(&Token::Symbol(Symbol::Not)));
+    assert!(!is_binary_operator(&Token::Symbol(Symbol::Not)));
+    assert!(!is_unary_operator(&Token::Symbol(Symbol::Multiply)));
+    assert!(!is_binary_operator(&Token::Symbol(Symbol::Question)));
+
+    for token in [Token::Identifier, Token::Return, Token::Symbol(Symbol::LeftParen), Token::Symbol(Symbol::Semicolon)] {
+        assert!(!is_binary_operator(&token));
+        assert!(!is_unary_operator(&token));
+        assert_eq!(precedence_of(&token), None);
+    }
+}
+
+#[test]
+fn operators_take_their_precedence_from_the_table() {
+    let plus = parse_str::<TermOp>("+").unwrap();
+    let modulo = parse_str::<FactorOp>("%").unwrap();
+    assert_eq!(Some(plus.precedence()), precedence_of(&Token::Symbol(Symbol::Plus)));
+    assert_eq!(Some(modulo.precedence()), precedence_of(&Token::Symbol(Symbol::Modulo)));
+    assert!(plus.precedence() < modulo.precedence());
+}
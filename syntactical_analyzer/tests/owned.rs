@@ -0,0 +1,43 @@
+//! Tests of copying a parse tree out of its token stream with `IntoOwned`.
+
+mod common;
+
+use std::rc::Rc;
+
+use common::buffer_of;
+use q2_lib::{
+    non_terminals::FunctionDefinition,
+    owned::IntoOwned,
+    parse_str,
+    visit::Visitable,
+    Parse,
+    ParseDisplay,
+};
+
+const SOURCE: &str = "int f(int x) { x = -x * 2; return x; }";
+
+#[test]
+fn owned_trees_outlive_their_token_stream() {
+    let mut buffer = buffer_of(SOURCE);
+    let parsed = FunctionDefinition::parse(&mut buffer).unwrap();
+    // shared by the token stream and the tree, along with this copy of it
+    let shared_lexeme = parsed.function_name.lexeme.clone();
+    assert_eq!(Rc::strong_count(&shared_lexeme), 3);
+
+    let owned = parsed.into_owned();
+    drop(buffer);
+    // the token stream is gone, and the owned tree never held its lexeme
+    assert_eq!(Rc::strong_count(&shared_lexeme), 1);
+    assert!(!Rc::ptr_eq(&shared_lexeme, &owned.function_name.lexeme));
+    assert_eq!(Rc::strong_count(&owned.function_name.lexeme), 1);
+
+    // every terminal is owned, down to the literals of a nested expression
+    assert_eq!(owned.identifiers().len(), 5);
+    assert!(owned.identifiers().iter().all(|identifier| Rc::strong_count(&identifier.lexeme) == 1));
+    assert!(owned.collect_literals().iter().all(|literal| Rc::strong_count(&literal.lexeme) == 1));
+
+    // and the owned tree is the same tree
+    let expected = parse_str::<FunctionDefinition>(SOURCE).unwrap();
+    assert_eq!(owned.render(0, None), expected.render(0, None));
+    assert!(owned.diff(&expected).is_empty());
+}
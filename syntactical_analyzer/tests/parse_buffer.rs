@@ -0,0 +1,240 @@
+//! Tests of the `ParseBuffer`, its end-of-input checks, and its nesting limit.
+
+mod common;
+
+use std::rc::Rc;
+
+use q1_lib::{lexer::Lexer, tokens_from_json};
+use q2_lib::{
+    non_terminals::{CompoundStatements, Expression, FunctionDefinition, Statement},
+    parse_and_display, parse_limited, parse_next_statement, parse_str,
+    terminals::{Eof, Identifier},
+    Parse, ParseBuffer, ParseDisplay, DEFAULT_MAX_DEPTH,
+};
+
+use common::buffer_of;
+
+#[test]
+fn expect_eof_after_everything_is_consumed() {
+    let mut buffer = buffer_of("return x");
+    Statement::parse(&mut buffer).unwrap();
+    assert_eq!(buffer.expect_eof(), Ok(()));
+    assert!(Eof::parse(&mut buffer).is_ok());
+}
+
+#[test]
+fn expect_eof_names_the_next_lexeme() {
+    let mut buffer = buffer_of("return x y");
+    Statement::parse(&mut buffer).unwrap();
+    let err = buffer.expect_eof().unwrap_err();
+    assert_eq!(err, "Expected `end of input`, but found `y` at token 2 instead");
+    assert_eq!(Eof::parse(&mut buffer).err(), Some(err));
+
+    // nothing is consumed on failure
+    assert_eq!(buffer.next().map(|(_, lexeme)| lexeme.to_string()).as_deref(), Some("y"));
+}
+
+#[test]
+fn lazy_buffer_parses_like_a_lexed_buffer() {
+    let source = "int foo(float x, int y) { y = x + 10; return (int)y; }";
+
+    let lexed = FunctionDefinition::parse(&mut buffer_of(source)).unwrap();
+
+    let mut lazy_buffer = ParseBuffer::from_lexer(Lexer::new(source.bytes()));
+    let lazy = FunctionDefinition::parse(&mut lazy_buffer).unwrap();
+    assert_eq!(lazy_buffer.expect_eof(), Ok(()));
+    assert_eq!(lazy_buffer.lexical_error(), None);
+
+    assert_eq!(lazy.render(0, None), lexed.render(0, None));
+}
+
+#[test]
+fn the_token_stream_is_dropped_along_with_its_buffer() {
+    // only the lexeme of a parsed terminal outlives the buffer, shared by the terminal alone
+    let mut buffer = buffer_of("x y");
+    let identifier = Identifier::parse(&mut buffer).unwrap();
+    assert_eq!(Rc::strong_count(&identifier.lexeme), 2);
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&identifier.lexeme), 1);
+
+    // a lazy token stream keeps no consumed token either
+    let mut lazy_buffer = ParseBuffer::from_lexer(Lexer::new("x y".bytes()));
+    let identifier = Identifier::parse(&mut lazy_buffer).unwrap();
+    drop(lazy_buffer);
+    assert_eq!(Rc::strong_count(&identifier.lexeme), 1);
+}
+
+#[test]
+fn lazy_buffer_reports_its_lexical_error() {
+    let mut buffer = ParseBuffer::from_lexer(Lexer::new("return x @".bytes()));
+    Statement::parse(&mut buffer).unwrap();
+
+    // the stream ends at the error, which is the error of whatever is parsed there
+    assert_eq!(buffer.expect_eof(), Err("Unknown character `0x40` at 1:10".into()));
+    assert_eq!(Eof::parse(&mut buffer).err(), Some("Unknown character `0x40` at 1:10".into()));
+    assert_eq!(buffer.lexical_error(), Some("Unknown character `0x40` at 1:10".into()));
+
+    let err = FunctionDefinition::parse(&mut ParseBuffer::from_lexer(Lexer::new("int f() @".bytes()))).err().unwrap();
+    assert!(err.ends_with("Unknown character `0x40` at 1:9"), "{err}");
+}
+
+#[test]
+fn lazy_buffer_still_sees_the_tokens_behind_it() {
+    // explaining this error looks back at the `(foo)` already consumed
+    let mut buffer = ParseBuffer::from_lexer(Lexer::new("(foo)bar".bytes()));
+    Expression::parse(&mut buffer).unwrap();
+    let err = buffer.expect_eof().unwrap_err();
+    // a lazy buffer does not know the locations of its tokens, only their positions
+    let located = parse_str::<Expression>("(foo)bar").err().unwrap();
+    assert_eq!(err, located.replace("`bar` at 1:6", "`bar` at token 3"));
+}
+
+#[test]
+fn pathological_nesting_is_a_clean_error() {
+    let depth = 10_000;
+    let source = format!("{}b{}", "a ? ".repeat(depth), " : c".repeat(depth));
+    let err = parse_str::<Expression>(&source).err().unwrap();
+    assert_eq!(err, format!("Expression or statement nested too deeply (the limit is {DEFAULT_MAX_DEPTH} levels)"));
+
+    // Nested if statements take much more stack per level (especially unoptimized),
+    // so this runs with the stack size of a main thread, rather than of a test thread.
+    let nested_ifs = std::thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(move || {
+            let source = format!("{}x = 1;{}", "if (a) { ".repeat(depth), " };".repeat(depth));
+            parse_str::<CompoundStatements>(&source).err().unwrap()
+        })
+        .unwrap();
+    let err = nested_ifs.join().unwrap();
+    assert_eq!(err, format!("Expression or statement nested too deeply (the limit is {DEFAULT_MAX_DEPTH} levels)"));
+}
+
+#[test]
+fn max_depth_is_configurable() {
+    let mut buffer = buffer_of("a ? b ? c : d : e").with_max_depth(2);
+    let err = Expression::parse(&mut buffer).err();
+    assert_eq!(err, Some("Expression or statement nested too deeply (the limit is 2 levels)".into()));
+    assert_eq!(buffer.nesting_error(), err);
+
+    let mut buffer = buffer_of("a ? b ? c : d : e").with_max_depth(3);
+    assert!(Expression::parse(&mut buffer).is_ok());
+    assert_eq!(buffer.nesting_error(), None);
+}
+
+#[test]
+fn exceeding_max_depth_fails_the_whole_parse() {
+    // the too deep `(b)` could have been left out of a shorter parse `a`,
+    // which is not a partial success
+    let mut buffer = buffer_of("a + (b)").with_max_depth(1);
+    assert!(Expression::parse(&mut buffer).is_err());
+    assert_eq!(buffer.position(), 0);
+
+    // nor is a statement list stopping right before the too deep statement
+    let mut buffer = buffer_of("x = 1; if (a) { if (b) { y = 2; }; };").with_max_depth(1);
+    assert!(CompoundStatements::parse(&mut buffer).is_err());
+
+    // once taken, the error is reset, and the buffer can be parsed from again
+    assert!(buffer.take_nesting_error().is_some());
+    assert_eq!(buffer.nesting_error(), None);
+    assert!(Statement::parse(&mut buffer).is_ok());
+}
+
+#[test]
+fn parse_limited_stays_within_the_budget() {
+    // only the 3 tokens consumed count, not the `;` peeked after them
+    let mut buffer = buffer_of("a + b ; c = d");
+    let expression = parse_limited::<Expression>(&mut buffer, 3).unwrap();
+    assert_eq!(expression.lexeme_signature(), "a + b");
+
+    // the rest of the buffer is left as is
+    assert_eq!(buffer.position(), 3);
+    assert_eq!(buffer.next().map(|(_, lexeme)| lexeme.to_string()).as_deref(), Some(";"));
+}
+
+#[test]
+fn statements_can_be_parsed_one_at_a_time() {
+    let mut buffer = buffer_of("a=1; return a;");
+
+    let first = parse_next_statement(&mut buffer).unwrap().unwrap();
+    assert!(matches!(first, Statement::Assignment(_)));
+    assert_eq!(first.lexeme_signature(), "a = 1");
+    // the buffer is left right after the `;`
+    assert_eq!(buffer.position(), 4);
+
+    let second = parse_next_statement(&mut buffer).unwrap().unwrap();
+    assert!(matches!(second, Statement::Return(_)));
+    assert!(parse_next_statement(&mut buffer).is_none());
+
+    // a statement missing its `;` is an error, consuming nothing
+    let mut buffer = buffer_of("a = 1");
+    assert!(parse_next_statement(&mut buffer).unwrap().is_err());
+    assert_eq!(buffer.position(), 0);
+}
+
+#[test]
+fn parse_limited_errors_past_the_budget() {
+    let mut buffer = buffer_of("a + b + c");
+    let err = parse_limited::<Expression>(&mut buffer, 3).err().unwrap();
+    assert_eq!(err, "Token budget exceeded (the limit is 3 tokens)");
+
+    // nothing is consumed on failure, and the budget is lifted
+    assert_eq!(buffer.position(), 0);
+    assert_eq!(Expression::parse(&mut buffer).unwrap().lexeme_signature(), "a + b + c");
+
+    // running out of input is not exceeding the budget
+    let err = parse_limited::<Expression>(&mut buffer_of("a +"), 10).err().unwrap();
+    assert!(!err.starts_with("Token budget exceeded"));
+}
+
+#[test]
+fn buffer_from_json_tokens_parses_like_a_lexed_buffer() {
+    let json = r#"[
+        {"token": "Identifier", "lexeme": "y"},
+        {"token": "Symbol(Equal)", "lexeme": "="},
+        {"token": "Identifier", "lexeme": "x"},
+        {"token": "Symbol(Plus)", "lexeme": "+"},
+        {"token": "Literal(Float)", "lexeme": "2.0"}
+    ]"#;
+    let mut buffer = ParseBuffer::from_tokens(tokens_from_json(json).unwrap());
+    let statement = Statement::parse(&mut buffer).unwrap();
+    assert_eq!(buffer.expect_eof(), Ok(()));
+
+    let lexed = Statement::parse(&mut buffer_of("y = x + 2.0")).unwrap();
+    assert_eq!(statement.render(0, None), lexed.render(0, None));
+}
+
+#[test]
+fn tokens_from_json_rejects_unknown_tokens() {
+    assert_eq!(tokens_from_json(" [ ] ").map(|tokens| tokens.len()), Ok(0));
+    assert_eq!(
+        tokens_from_json(r#"[{"token": "Symbol(Caret)", "lexeme": "^"}]"#).err(),
+        Some("Unknown symbol `Caret`".into())
+    );
+    assert_eq!(
+        tokens_from_json(r#"[{"token": "Identifier"}]"#).err(),
+        Some("Expected a `lexeme` in token 0 of the JSON".into())
+    );
+    assert!(tokens_from_json(r#"[{"token": "Identifier", "lexeme": "x"}"#).is_err());
+}
+
+#[test]
+fn parse_and_display_returns_the_error_for_bad_input() {
+    assert!(parse_and_display::<FunctionDefinition>(&mut buffer_of("int f() {}")).is_ok());
+
+    let err = parse_and_display::<FunctionDefinition>(&mut buffer_of("int f() {} x")).err();
+    assert_eq!(err.as_deref(), Some("Expected `end of input`, but found `x` at token 6 instead"));
+    assert!(parse_and_display::<FunctionDefinition>(&mut buffer_of("int f(")).is_err());
+}
+
+#[test]
+fn deeply_parenthesized_expressions_parse_in_linear_time() {
+    // every alternative of an expression once re-parsed the whole arithmetic
+    // expression, so each level of parentheses doubled the time (20 levels took seconds)
+    let depth = 64;
+    let source = format!("{}a + b{}", "(".repeat(depth), ")".repeat(depth));
+
+    let start = std::time::Instant::now();
+    assert!(parse_str::<Expression>(&source).is_ok());
+    let elapsed = start.elapsed();
+    assert!(elapsed < std::time::Duration::from_secs(2), "{depth} levels took {elapsed:?}");
+}
@@ -0,0 +1,69 @@
+//! Tests of the precedence climbing parser, only with the `pratt` feature.
+#![cfg(feature = "pratt")]
+
+use q2_lib::{
+    non_terminals::ArithmeticExpression,
+    parse_str,
+    pratt::{binding_power, BinaryOp, PrattExpression},
+    ParseDisplay,
+};
+
+/// Renders an expression fully parenthesized, to compare the structure of two trees.
+fn parenthesized(expression: &PrattExpression) -> String {
+    match expression {
+        PrattExpression::Factor(factor) => factor.lexeme_signature(),
+        PrattExpression::Binary(binary) => format!(
+            "({} {} {})",
+            parenthesized(&binary.lhs),
+            binary.op.lexeme_signature(),
+            parenthesized(&binary.rhs)
+        ),
+    }
+}
+
+#[test]
+fn precedence_and_left_associativity() {
+    let expression = parse_str::<PrattExpression>("a - b - c * d / 2 + (x + y) * z").unwrap();
+    assert_eq!(parenthesized(&expression), "(((a - b) - ((c * d) / 2)) + ((x + y) * z))");
+    assert_eq!(expression.lexeme_signature(), "a - b - c * d / 2 + (x + y) * z");
+
+    let add = parse_str::<BinaryOp>("+").unwrap();
+    let multiply = parse_str::<BinaryOp>("*").unwrap();
+    assert!(binding_power(&multiply).0 > binding_power(&add).1);
+    let modulo = parse_str::<BinaryOp>("%").unwrap();
+    assert_eq!(binding_power(&modulo), binding_power(&multiply));
+    let expression = parse_str::<PrattExpression>("a + b % c * d").unwrap();
+    assert_eq!(parenthesized(&expression), "(a + ((b % c) * d))");
+}
+
+#[test]
+fn matches_the_layered_parser() {
+    let sources = ["x", "10 + 30", "a * b + c / 2", "a + b % c * d", "a - b * 3", "a + b + c", "a * b / c - 1", "(a + b) * c"];
+    for source in sources {
+        let pratt = parse_str::<PrattExpression>(source).unwrap();
+        let layered = parse_str::<ArithmeticExpression>(source).unwrap();
+        let converted = PrattExpression::from(&layered);
+
+        assert_eq!(parenthesized(&pratt), parenthesized(&converted), "{source}");
+        assert_eq!(pratt.render(0, None), converted.render(0, None), "{source}");
+        assert_eq!(pratt.lexeme_signature(), layered.lexeme_signature(), "{source}");
+    }
+}
+
+#[test]
+fn displays_a_uniform_tree() {
+    let expression = parse_str::<PrattExpression>("a + b * 2").unwrap();
+    assert_eq!(
+        expression.render(0, None),
+        "Binary Expression: a + b * 2\n\
+         \x20   Lhs: a\n\
+         \x20       Variable: a\n\
+         \x20   Operator: +\n\
+         \x20   Rhs: b * 2\n\
+         \x20       Lhs: b\n\
+         \x20           Variable: b\n\
+         \x20       Operator: *\n\
+         \x20       Rhs: 2\n\
+         \x20           Literal: 2\n"
+    );
+}
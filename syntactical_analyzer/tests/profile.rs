@@ -0,0 +1,43 @@
+//! Tests of the backtracking counters, only with the `profile` feature.
+#![cfg(feature = "profile")]
+
+use q1_lib::lex;
+use q2_lib::{
+    either::Either,
+    non_terminals::Factor,
+    terminals::{Comma, Eof, Identifier, Semicolon},
+    Parse, ParseBuffer,
+};
+
+#[test]
+fn counts_forks_and_discards() {
+    // An identifier is the first alternative of a factor: no backtracking
+    let mut buffer = ParseBuffer::from_tokens(lex("x").unwrap());
+    Factor::parse(&mut buffer).unwrap();
+    assert_eq!((buffer.fork_count(), buffer.discard_count()), (2, 0));
+
+    // A literal is the second alternative: the factor's first fork and the
+    // identifier's fork are both discarded
+    let mut buffer = ParseBuffer::from_tokens(lex("5").unwrap());
+    Factor::parse(&mut buffer).unwrap();
+    assert_eq!((buffer.fork_count(), buffer.discard_count()), (4, 2));
+}
+
+#[test]
+fn tracks_the_deepest_token_read_before_backtracking() {
+    // the first alternative reads `x`, and then backtracks at the `,`
+    let mut buffer = ParseBuffer::from_tokens(lex("x ,").unwrap());
+    Either::<(Identifier, Semicolon), (Identifier, Comma)>::parse(&mut buffer).unwrap();
+    assert_eq!(buffer.deepest_reach(), Some(1));
+    assert_eq!(buffer.describe_deepest_reach().unwrap(), "deepest speculative reach: token 2 (',')");
+
+    // the first alternative reads `x`, and then only peeks at the `,` (which is not the end of input)
+    let mut buffer = ParseBuffer::from_tokens(lex("x ,").unwrap());
+    Either::<(Identifier, Eof), (Identifier, Comma)>::parse(&mut buffer).unwrap();
+    assert_eq!(buffer.deepest_reach(), Some(1));
+
+    // nothing was backtracked
+    let mut buffer = ParseBuffer::from_tokens(lex("x").unwrap());
+    Factor::parse(&mut buffer).unwrap();
+    assert_eq!(buffer.deepest_reach(), None);
+}
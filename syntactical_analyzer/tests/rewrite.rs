@@ -0,0 +1,39 @@
+//! Tests of transforming a parse tree with a `Rewriter`.
+
+use q2_lib::{
+    non_terminals::{Expression, TermExtend, TermOp},
+    parse_str,
+    rewrite::{Rewritable, Rewriter},
+    terminals::Minus,
+    ParseDisplay,
+};
+
+/// Swaps every `+` for a `-`.
+struct Subtract {
+    minus: Minus,
+}
+impl Rewriter for Subtract {
+    fn rewrite_term_extend(&mut self, mut node: TermExtend) -> TermExtend {
+        if let TermOp::Add(plus) = &node.op {
+            node.op = TermOp::Subtract(Minus { position: plus.position, ..self.minus.clone() });
+        }
+        node
+    }
+}
+
+#[test]
+fn a_rewriter_swaps_operators_throughout() {
+    let minus = parse_str::<Minus>("-").unwrap();
+    let expression = parse_str::<Expression>("a = b + (c + 1) * 2 - d ? e + f : g").unwrap();
+    let rewritten = expression.rewrite(&mut Subtract { minus });
+    assert_eq!(rewritten.lexeme_signature(), "a = b - (c - 1) * 2 - d ? e - f : g");
+}
+
+#[test]
+fn the_default_rewriter_keeps_the_tree() {
+    struct Identity;
+    impl Rewriter for Identity {}
+
+    let expression = parse_str::<Expression>("x = (int)y").unwrap();
+    assert_eq!(expression.rewrite(&mut Identity).lexeme_signature(), "x = (int)y");
+}
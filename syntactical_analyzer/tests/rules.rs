@@ -0,0 +1,83 @@
+//! Tests of parsing a rule chosen at runtime.
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use q2_lib::{grammar::Bnf, non_terminals::ExpressionList, parse_rule, parse_rule_all, parse_rule_str, parse_str, ParseDisplay, RULE_NAMES};
+
+use common::buffer_of;
+
+#[test]
+fn parse_rule_dispatches_by_name() {
+    let expression = parse_rule("Expression", &mut buffer_of("a * 2 + b")).unwrap();
+    assert_eq!(expression.lexeme_signature(), "a * 2 + b");
+    assert!(expression.render(0, None).starts_with("Expression:\n"));
+
+    let statement = parse_rule("Statement", &mut buffer_of("x = (int)y")).unwrap();
+    assert_eq!(statement.lexeme_signature(), "x = (int)y");
+
+    assert!(parse_rule("Statement", &mut buffer_of("int")).is_err());
+}
+
+#[test]
+fn every_rule_name_is_known() {
+    for name in RULE_NAMES {
+        let err = parse_rule(name, &mut buffer_of("")).err().unwrap_or_default();
+        assert!(!err.starts_with("Unknown rule"), "`{name}` is not dispatched");
+    }
+
+    let err = parse_rule("Nonsense", &mut buffer_of("")).err().unwrap();
+    assert!(err.starts_with("Unknown rule `Nonsense`"));
+}
+
+#[test]
+fn every_root_parses_its_fixture() {
+    // every `fixtures/roots/<rule>.src` is parsed as a whole as `<rule>`
+    let roots = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("roots");
+    let mut parsed = vec![];
+    for entry in fs::read_dir(roots).unwrap() {
+        let path = entry.unwrap().path();
+        let rule = path.file_stem().unwrap().to_str().unwrap().to_string();
+        let source = fs::read_to_string(&path).unwrap();
+        if let Err(err) = parse_rule_all(&rule, &mut buffer_of(&source)) {
+            panic!("{} failed to parse:\n{err}", path.display());
+        }
+        parsed.push(rule);
+    }
+    parsed.sort();
+    assert_eq!(parsed, ["Expression", "ExpressionList", "FunctionDefinition", "Program", "Statement"]);
+
+    // unlike `parse_rule`, nothing can be left over
+    assert!(parse_rule("Expression", &mut buffer_of("a b")).is_ok());
+    assert!(parse_rule_all("Expression", &mut buffer_of("a b")).is_err());
+}
+
+#[test]
+fn parse_rule_str_lexes_and_parses_a_whole_line() {
+    let statement = parse_rule_str("Statement", "x = a * 2").unwrap();
+    assert_eq!(statement.lexeme_signature(), "x = a * 2");
+
+    assert!(parse_rule_str("Expression", "a b").is_err());
+    assert!(parse_rule_str("Expression", "a @ b").is_err());
+    assert!(parse_rule_str("Nonsense", "a").err().unwrap().starts_with("Unknown rule `Nonsense`"));
+}
+
+#[test]
+fn an_expression_list_is_a_root_of_its_own() {
+    let list = parse_str::<ExpressionList>("1+2, a*b, (int)x").unwrap();
+    let expressions = list.items().iter().map(|(expression, _comma)| expression.lexeme_signature()).collect::<Vec<_>>();
+    assert_eq!(expressions, ["1 + 2", "a * b", "(int)x"]);
+
+    // each expression is displayed under the list
+    let rendered = parse_rule_str("ExpressionList", "1+2, a*b, (int)x").unwrap().render(0, None);
+    assert!(rendered.starts_with("Expression List: 1 + 2, a * b, (int)x\n"), "{rendered}");
+    let displayed = rendered.lines().filter(|line| line.starts_with("    Expression:")).count();
+    assert_eq!(displayed, 3);
+    assert_eq!(list.production(), Some(ExpressionList::bnf()));
+
+    // an empty input is an empty list, but a trailing comma is not
+    assert!(parse_str::<ExpressionList>("").unwrap().is_empty());
+    assert!(parse_rule_str("ExpressionList", "").is_ok());
+    assert!(parse_rule_str("ExpressionList", "a,").is_err());
+}
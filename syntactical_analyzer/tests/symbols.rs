@@ -0,0 +1,57 @@
+//! Tests of parsing with a symbol table.
+
+mod common;
+
+use std::collections::HashMap;
+
+use q2_lib::{non_terminals::*, parse_with_symbols, terminals::TypeKind};
+
+use common::buffer_of;
+
+#[test]
+fn assigning_to_an_undeclared_variable_is_a_diagnostic() {
+    let mut symbols = HashMap::new();
+    let source = "int f(int x, float y) { x = 1; z = y; if (x) { w = 2; }; }";
+    let (_, diagnostics) = parse_with_symbols::<FunctionDefinition>(&mut buffer_of(source), &mut symbols).unwrap();
+    assert_eq!(
+        diagnostics,
+        [
+            "Assignment to the undeclared variable `z` (at token 14)",
+            "Assignment to the undeclared variable `w` (at token 23)",
+        ]
+    );
+    assert!(symbols.is_empty()); // the parameters are only declared within the function
+}
+
+#[test]
+fn parameters_are_only_declared_within_their_function() {
+    let mut symbols = HashMap::new();
+    let source = "int f(int x) { x = 1; } int g() { x = 2; }";
+    let (_, diagnostics) = parse_with_symbols::<Program>(&mut buffer_of(source), &mut symbols).unwrap();
+    assert_eq!(diagnostics, ["Assignment to the undeclared variable `x` (at token 17)"]);
+}
+
+#[test]
+fn the_target_of_an_assignment_expression_is_checked() {
+    let mut symbols = HashMap::from([("x".to_string(), TypeKind::Int)]);
+    let (_, diagnostics) = parse_with_symbols::<CompoundStatements>(&mut buffer_of("x = y = 1; return z = x;"), &mut symbols).unwrap();
+    assert_eq!(
+        diagnostics,
+        [
+            "Assignment to the undeclared variable `y` (at token 2)",
+            "Assignment to the undeclared variable `z` (at token 7)",
+        ]
+    );
+}
+
+#[test]
+fn the_caller_can_declare_symbols_ahead() {
+    let mut symbols = HashMap::from([("z".to_string(), TypeKind::Float)]);
+    let (_, diagnostics) = parse_with_symbols::<CompoundStatements>(&mut buffer_of("z = 1; a = z;"), &mut symbols).unwrap();
+    assert_eq!(diagnostics, ["Assignment to the undeclared variable `a` (at token 4)"]);
+
+    // a failed parse leaves no declarations behind
+    let mut symbols = HashMap::new();
+    assert!(parse_with_symbols::<FunctionDefinition>(&mut buffer_of("int f(int x) {"), &mut symbols).is_err());
+    assert!(symbols.is_empty());
+}
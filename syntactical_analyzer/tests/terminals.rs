@@ -0,0 +1,109 @@
+//! Tests of the terminal parse types.
+
+use q1_lib::lexer::{Literal as LiteralToken, Symbol, Token};
+use q2_lib::{
+    display::OutputFormat,
+    non_terminals::{AssignmentStatement, FunctionDefinition},
+    parse_str,
+    terminals::*,
+    Parse, ParseBuffer, ParseDisplay,
+};
+
+#[test]
+fn reserved_words_are_not_identifiers() {
+    // the lexer tags `if` as a keyword, which is not an identifier to begin with
+    let err = parse_str::<AssignmentStatement>("if = 1").err().unwrap();
+    assert_eq!(err, "Expected `{identifier}`, but found `if` at 1:1 instead");
+
+    // and if the lexer were to tag a reserved word as an identifier, it is still rejected
+    let mut buffer = ParseBuffer::from_tokens(vec![(Token::Identifier, "else".into())]);
+    let err = Identifier::parse(&mut buffer).err().unwrap();
+    assert_eq!(err, "The reserved word `else` cannot be used as an identifier at token 0");
+    assert!(buffer.peek().is_some()); // nothing is consumed on failure
+
+    assert!(parse_str::<Identifier>("iffy").is_ok());
+}
+
+#[test]
+fn raw_identifiers_can_be_spelled_as_reserved_words() {
+    let assignment_statement = parse_str::<AssignmentStatement>("`if` = 1").unwrap();
+    assert_eq!(assignment_statement.lhs_identifier.lexeme_str(), "if");
+    assert_eq!(assignment_statement.lhs_identifier.token, Token::RawIdentifier);
+
+    let function_definition = parse_str::<FunctionDefinition>("int f(int `return`) { `return` = `return` + 1; return `return`; }").unwrap();
+    // formatted, it keeps its backticks, so that it parses again
+    let source = function_definition.render_as(OutputFormat::Source);
+    assert_eq!(source, "int f(int `return`) {\n    `return` = `return` + 1;\n    return `return`;\n}\n");
+    assert!(parse_str::<FunctionDefinition>(&source).is_ok());
+}
+
+#[test]
+fn type_kind_follows_the_type_keyword() {
+    assert_eq!(parse_str::<Type>("int").unwrap().kind(), TypeKind::Int);
+    assert_eq!(parse_str::<Type>("float").unwrap().kind(), TypeKind::Float);
+}
+
+#[test]
+fn literal_values_are_read_from_the_lexeme() {
+    assert_eq!(parse_str::<Literal>("42").unwrap().value(), Ok(LiteralValue::Int(42)));
+    assert_eq!(parse_str::<Literal>("2.5").unwrap().value(), Ok(LiteralValue::Float(2.5)));
+
+    // the literals of other lexers, which `Q1` never produces
+    let value = |token: LiteralToken, lexeme: &str| {
+        let mut buffer = ParseBuffer::from_tokens(vec![(Token::Literal(token), lexeme.into())]);
+        Literal::parse(&mut buffer).unwrap().value()
+    };
+    assert_eq!(value(LiteralToken::Int, "0xFF"), Ok(LiteralValue::Int(255)));
+    assert_eq!(value(LiteralToken::Int, "0b101"), Ok(LiteralValue::Int(5)));
+    assert_eq!(value(LiteralToken::Int, "-0x10"), Ok(LiteralValue::Int(-16)));
+    assert_eq!(value(LiteralToken::Float, "1.5e3"), Ok(LiteralValue::Float(1500.0)));
+    assert_eq!(
+        value(LiteralToken::Int, "9223372036854775808"),
+        Err("Integer literal `9223372036854775808` is too large, an int must be from -9223372036854775808 to 9223372036854775807".into())
+    );
+    assert_eq!(value(LiteralToken::Int, "-9223372036854775808"), Ok(LiteralValue::Int(i64::MIN)));
+    assert!(value(LiteralToken::Int, "0xZZ").is_err());
+}
+
+#[test]
+fn hex_float_literals_have_a_binary_exponent() {
+    let literal = parse_str::<Literal>("0x1.8p3").unwrap();
+    assert_eq!(literal.token, Token::Literal(LiteralToken::Float));
+    assert_eq!(literal.value(), Ok(LiteralValue::Float(12.0)));
+    assert_eq!(parse_str::<Literal>("0XAp-2").unwrap().value(), Ok(LiteralValue::Float(2.5)));
+    assert_eq!(parse_str::<Literal>("0xff").unwrap().value(), Ok(LiteralValue::Int(255)));
+
+    // the exponent is required
+    assert_eq!(parse_str::<Literal>("0x1.8").err(), Some("The hex float `0x1.8` is missing its `p` exponent at 1:6".into()));
+    assert!(parse_str::<Literal>("0x1.8p").is_err());
+    assert!(parse_str::<Literal>("0x").is_err());
+    assert!(parse_str::<Literal>("1x2").is_err());
+}
+
+#[test]
+fn huge_int_literals_are_too_large() {
+    let literal = parse_str::<Literal>("123456789012345678901234567890").unwrap();
+    assert_eq!(
+        literal.value(),
+        Err("Integer literal `123456789012345678901234567890` is too large, an int must be from -9223372036854775808 to 9223372036854775807".into())
+    );
+    // only the magnitude of an int is limited
+    assert!(parse_str::<Literal>("123456789012345678901234567890.5").unwrap().value().is_ok());
+}
+
+#[test]
+fn terminals_recover_their_token() {
+    assert_eq!(parse_str::<Plus>("+").unwrap().token(), Token::Symbol(Symbol::Plus));
+    assert_eq!(parse_str::<Identifier>("x").unwrap().token(), Token::Identifier);
+
+    // through a generic function, as any `Terminal`
+    fn tokens(terminals: &[&dyn Terminal]) -> Vec<Token> {
+        terminals.iter().map(|terminal| terminal.token()).collect()
+    }
+    let left_paren = parse_str::<LeftParen>("(").unwrap();
+    let right_paren = parse_str::<RightParen>(")").unwrap();
+    assert_eq!(
+        tokens(&[&left_paren, &right_paren]),
+        [Token::Symbol(Symbol::LeftParen), Token::Symbol(Symbol::RightParen)]
+    );
+}
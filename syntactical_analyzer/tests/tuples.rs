@@ -0,0 +1,34 @@
+//! Tests of tuples as generic sequences.
+
+use q1_lib::lex;
+use q2_lib::{
+    parse_str,
+    terminals::{LeftParen, RightParen, Type},
+    Parse, ParseBuffer, ParseDisplay,
+};
+
+#[test]
+fn tuple_parses_each_element_in_order() {
+    let (left_paren, type_, right_paren) = parse_str::<(LeftParen, Type, RightParen)>("(int)").unwrap();
+    assert_eq!(
+        [left_paren.lexeme.as_str(), type_.lexeme.as_str(), right_paren.lexeme.as_str()],
+        ["(", "int", ")"]
+    );
+
+    let tuple = parse_str::<(LeftParen, Type, RightParen)>("( float )").unwrap();
+    assert_eq!(tuple.lexeme_signature(), "( float )");
+    assert_eq!(
+        tuple.render(0, Some("Cast".into())),
+        "Cast: ( float )\n    Left Paren: (\n    Type: float\n    Right Paren: )\n"
+    );
+}
+
+#[test]
+fn tuple_commits_all_or_nothing() {
+    let mut buffer = ParseBuffer::from_tokens(lex("(int x").unwrap());
+    let err = <(LeftParen, Type, RightParen)>::parse(&mut buffer).err().unwrap();
+    assert_eq!(err, "Expected `)`, but found `x` at token 2 instead");
+
+    // nothing is consumed on failure
+    assert_eq!(buffer.position(), 0);
+}
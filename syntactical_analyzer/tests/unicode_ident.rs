@@ -0,0 +1,10 @@
+//! Tests of parsing Unicode identifiers, only with the `unicode-ident` feature.
+#![cfg(feature = "unicode-ident")]
+
+use q2_lib::{non_terminals::AssignmentStatement, parse_str, ParseDisplay};
+
+#[test]
+fn identifiers_can_contain_non_ascii_letters() {
+    let statement = parse_str::<AssignmentStatement>("größe = länge * 2").unwrap();
+    assert_eq!(statement.lexeme_signature(), "größe = länge * 2");
+}
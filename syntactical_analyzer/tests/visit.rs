@@ -0,0 +1,42 @@
+//! Tests of traversing a parse tree with a `Visitor`.
+
+use q2_lib::{
+    non_terminals::{CompoundStatements, Expression, FunctionDefinition},
+    parse_str,
+    terminals::LiteralValue,
+    visit::Visitable,
+};
+
+#[test]
+fn identifiers_include_declarations_and_uses() {
+    let function = parse_str::<FunctionDefinition>("int f(int x){return x+y;}").unwrap();
+    let identifiers = function
+        .identifiers()
+        .into_iter()
+        .map(|identifier| identifier.lexeme.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(identifiers, ["f", "x", "x", "y"]);
+}
+
+#[test]
+fn identifiers_of_a_subtree() {
+    let expression = parse_str::<Expression>("a = b ? (c + 1) : d").unwrap();
+    let identifiers = expression
+        .identifiers()
+        .into_iter()
+        .map(|identifier| (identifier.lexeme.as_str(), identifier.position))
+        .collect::<Vec<_>>();
+    assert_eq!(identifiers, [("a", 0), ("b", 2), ("c", 5), ("d", 10)]);
+}
+
+#[test]
+fn literals_are_collected_in_source_order() {
+    let statements = parse_str::<CompoundStatements>("return 1 + 2 * 3;").unwrap();
+    let literals = statements
+        .collect_literals()
+        .into_iter()
+        .map(|literal| literal.value())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(literals, [LiteralValue::Int(1), LiteralValue::Int(2), LiteralValue::Int(3)]);
+}